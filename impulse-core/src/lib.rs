@@ -0,0 +1,11 @@
+//! Rocket design and flight simulation, independent of any user interface
+//!
+//! A [`rocket::Rocket`] is turned into a [`sim::Vehicle`] and flown with a
+//! [`sim::Simulation`], which can be stepped one integrator step at a time,
+//! iterated over as a stream of [`sim::FlightUpdate`]s or run to completion.
+
+#![deny(unsafe_code)]
+
+pub mod project;
+pub mod rocket;
+pub mod sim;
@@ -0,0 +1,256 @@
+//! Project files, which save everything needed to reproduce a simulation
+//!
+//! Projects are stored as JSON with a `version` field. Files written by older
+//! versions of impulse are migrated forward one version at a time before being
+//! deserialized, so that the current structs never need to know about old
+//! layouts.
+
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    rocket::Rocket,
+    sim::{
+        DispersionSettings, FieldLimits, LaunchConditions, Motor, SettingsError, SimulationConfig,
+        SimulationSettings,
+    },
+};
+
+/// Version of the project format written by this build
+pub const CURRENT_VERSION: u64 = 4;
+
+/// File extension of project files
+pub const EXTENSION: &str = "impulse";
+
+/// Everything the user has set up for a simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub rocket: Rocket,
+    pub motor: Motor,
+    pub launch: LaunchConditions,
+    pub settings: SimulationSettings,
+    pub dispersion: DispersionSettings,
+    pub field: FieldLimits,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            rocket: Rocket::default(),
+            motor: Motor::demo(),
+            launch: LaunchConditions::default(),
+            settings: SimulationSettings::default(),
+            dispersion: DispersionSettings::default(),
+            field: FieldLimits::default(),
+        }
+    }
+}
+
+/// On disk layout of a project, tagged with its format version
+#[derive(Serialize)]
+struct ProjectFile<'a> {
+    version: u64,
+    #[serde(flatten)]
+    project: &'a Project,
+}
+
+#[derive(Debug, Error)]
+pub enum ProjectError {
+    #[error("failed to access project file: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid project file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the project file has no version")]
+    MissingVersion,
+    #[error("the project file was saved by a newer version of impulse (format {0})")]
+    NewerVersion(u64),
+    #[error("the project file has an unknown format version {0}")]
+    UnknownVersion(u64),
+    #[error("invalid simulation settings: {0}")]
+    Settings(#[from] SettingsError),
+}
+
+impl Project {
+    pub fn load(path: &Path) -> Result<Self, ProjectError> {
+        let contents = fs::read_to_string(path)?;
+
+        Self::from_json(&contents)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProjectError> {
+        fs::write(path, self.to_json()?)?;
+
+        Ok(())
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, ProjectError> {
+        let mut value: Value = serde_json::from_str(contents)?;
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or(ProjectError::MissingVersion)?;
+
+        if version > CURRENT_VERSION {
+            return Err(ProjectError::NewerVersion(version));
+        }
+        if version < 1 {
+            return Err(ProjectError::UnknownVersion(version));
+        }
+
+        for from in version..CURRENT_VERSION {
+            migrate(from, &mut value);
+        }
+
+        let project: Self = serde_json::from_value(value)?;
+        project.settings.validate()?;
+
+        Ok(project)
+    }
+
+    /// Everything needed to fly the rocket with the project's motor
+    pub fn config(&self) -> SimulationConfig {
+        SimulationConfig {
+            vehicle: self.rocket.to_vehicle(Some(self.motor.clone())),
+            launch: self.launch,
+            settings: self.settings,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, ProjectError> {
+        Ok(serde_json::to_string_pretty(&ProjectFile {
+            version: CURRENT_VERSION,
+            project: self,
+        })?)
+    }
+}
+
+/// Upgrade a project file from one version to the next. Every change to the
+/// format bumps [`CURRENT_VERSION`] and adds an arm here.
+fn migrate(from: u64, project: &mut Value) {
+    match from {
+        // Version 2 added the location of the launch site
+        1 => {
+            if let Some(launch) = project.get_mut("launch").and_then(Value::as_object_mut) {
+                launch.insert("latitude".into(), 0.0.into());
+                launch.insert("longitude".into(), 0.0.into());
+            }
+        }
+        // Version 3 added the settings of dispersion analyses
+        2 => {
+            let dispersion = serde_json::to_value(DispersionSettings::default());
+
+            if let (Some(project), Ok(dispersion)) = (project.as_object_mut(), dispersion) {
+                project.insert("dispersion".into(), dispersion);
+            }
+        }
+        // Version 4 added the range safety limits of the launch field
+        3 => {
+            let field = serde_json::to_value(FieldLimits::default());
+
+            if let (Some(project), Ok(field)) = (project.as_object_mut(), field) {
+                project.insert("field".into(), field);
+            }
+        }
+        _ => unreachable!("no migration from project version {}", from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// The default project as it would have been saved in the first version of
+    /// the format
+    fn version_1() -> Value {
+        let mut value: Value =
+            serde_json::from_str(&Project::default().to_json().unwrap()).unwrap();
+        let project = value.as_object_mut().unwrap();
+
+        project.insert("version".into(), 1.into());
+        project.remove("dispersion");
+        project.remove("field");
+        let launch = project["launch"].as_object_mut().unwrap();
+        launch.remove("latitude");
+        launch.remove("longitude");
+
+        value
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut project = Project::default();
+        project.launch.latitude = 0.5;
+        project.settings.time_step = 0.005;
+
+        assert_eq!(
+            Project::from_json(&project.to_json().unwrap()).unwrap(),
+            project
+        );
+    }
+
+    #[test]
+    fn migrates_from_version_1() {
+        let project = Project::from_json(&version_1().to_string()).unwrap();
+
+        assert_eq!(project, Project::default());
+    }
+
+    #[test]
+    fn migrates_from_every_version() {
+        for version in 1..CURRENT_VERSION {
+            let mut value = version_1();
+            for from in 1..version {
+                migrate(from, &mut value);
+            }
+            value["version"] = version.into();
+
+            assert_eq!(
+                Project::from_json(&value.to_string()).unwrap(),
+                Project::default(),
+                "migrating from version {}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut value = version_1();
+
+        value["version"] = 0.into();
+        assert!(matches!(
+            Project::from_json(&value.to_string()),
+            Err(ProjectError::UnknownVersion(0))
+        ));
+
+        value["version"] = (CURRENT_VERSION + 1).into();
+        assert!(matches!(
+            Project::from_json(&value.to_string()),
+            Err(ProjectError::NewerVersion(_))
+        ));
+
+        value.as_object_mut().unwrap().remove("version");
+        assert!(matches!(
+            Project::from_json(&value.to_string()),
+            Err(ProjectError::MissingVersion)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        for (time_step, max_time) in [(0.0, 600.0), (-0.01, 600.0), (0.01, -1.0)] {
+            let mut value = version_1();
+            value["settings"] = json!({ "time_step": time_step, "max_time": max_time });
+
+            assert!(matches!(
+                Project::from_json(&value.to_string()),
+                Err(ProjectError::Settings(_))
+            ));
+        }
+    }
+}
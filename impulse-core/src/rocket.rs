@@ -0,0 +1,833 @@
+//! Rocket designs as edited by the user, and their conversion into the lumped
+//! properties used by the simulation
+
+use std::{
+    f64::consts::PI,
+    fmt::{self, Display},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{Deployment, Motor, RecoveryDevice, Vehicle};
+
+pub use self::{
+    aerodynamics::Aerodynamics,
+    import::{Import, ImportError, ImportLevel, ImportMessage, MotorReference},
+};
+
+mod aerodynamics;
+mod import;
+mod openrocket;
+mod rocksim;
+
+/// A rocket design, made up of external components stacked from the nose
+/// towards the tail, each of which can carry internal components
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rocket {
+    pub name: String,
+    pub components: Vec<Component>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Component {
+    pub name: String,
+    /// Distance from the front of the parent component to the front of this
+    /// one (m). Ignored for external components, which are stacked in order.
+    pub position: f64,
+    pub kind: ComponentKind,
+    pub children: Vec<Component>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ComponentKind {
+    NoseCone {
+        shape: NoseShape,
+        length: f64,
+        diameter: f64,
+        thickness: f64,
+        density: f64,
+    },
+    BodyTube {
+        length: f64,
+        diameter: f64,
+        thickness: f64,
+        density: f64,
+    },
+    Transition {
+        length: f64,
+        fore_diameter: f64,
+        aft_diameter: f64,
+        thickness: f64,
+        density: f64,
+    },
+    FinSet {
+        count: u32,
+        root_chord: f64,
+        tip_chord: f64,
+        span: f64,
+        /// Distance from the leading edge of the root to that of the tip,
+        /// measured along the body (m)
+        sweep: f64,
+        thickness: f64,
+        /// Angle of the fins relative to the body axis (rad)
+        cant: f64,
+        density: f64,
+    },
+    /// An inner tube that holds the motor, aligned with its aft end
+    MotorMount {
+        length: f64,
+        diameter: f64,
+        thickness: f64,
+        density: f64,
+    },
+    MassItem {
+        mass: f64,
+        length: f64,
+    },
+    Parachute {
+        diameter: f64,
+        drag_coefficient: f64,
+        mass: f64,
+        deployment: Deployment,
+    },
+}
+
+/// Profile of a nose cone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoseShape {
+    Conical,
+    Ogive,
+    Parabolic,
+    Ellipsoid,
+}
+
+impl NoseShape {
+    pub const ALL: [NoseShape; 4] = [
+        NoseShape::Conical,
+        NoseShape::Ogive,
+        NoseShape::Parabolic,
+        NoseShape::Ellipsoid,
+    ];
+
+    /// Radius of the nose at a fraction of its length from the tip
+    pub fn radius(self, fraction: f64, length: f64, diameter: f64) -> f64 {
+        let radius = diameter / 2.0;
+        let x = fraction.clamp(0.0, 1.0);
+
+        match self {
+            NoseShape::Conical => radius * x,
+            NoseShape::Ogive => {
+                let rho = (radius * radius + length * length) / (2.0 * radius);
+                let distance_from_base = length * (1.0 - x);
+
+                (rho * rho - distance_from_base * distance_from_base).sqrt() + radius - rho
+            }
+            NoseShape::Parabolic => radius * (2.0 * x - x * x),
+            NoseShape::Ellipsoid => radius * (1.0 - (1.0 - x) * (1.0 - x)).sqrt(),
+        }
+    }
+}
+
+impl Display for NoseShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self, f)
+    }
+}
+
+/// Mass and center of gravity of a part of the rocket
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MassProperties {
+    /// Mass (kg)
+    pub mass: f64,
+    /// Center of gravity from the nose tip (m)
+    pub cg: f64,
+    /// Longitudinal moment of inertia about the center of gravity (kg·m²)
+    pub longitudinal_inertia: f64,
+    /// Roll moment of inertia (kg·m²)
+    pub roll_inertia: f64,
+}
+
+impl MassProperties {
+    /// Combine two sets of mass properties into those of the whole
+    pub fn combine(self, other: MassProperties) -> MassProperties {
+        let mass = self.mass + other.mass;
+
+        if mass <= 0.0 {
+            return MassProperties::default();
+        }
+
+        let cg = (self.mass * self.cg + other.mass * other.cg) / mass;
+
+        MassProperties {
+            mass,
+            cg,
+            longitudinal_inertia: self.longitudinal_inertia
+                + self.mass * (self.cg - cg).powi(2)
+                + other.longitudinal_inertia
+                + other.mass * (other.cg - cg).powi(2),
+            roll_inertia: self.roll_inertia + other.roll_inertia,
+        }
+    }
+}
+
+/// A problem with a design that prevents it from being simulated
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// Name of the offending component
+    pub component: String,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.component, self.message)
+    }
+}
+
+impl Default for Rocket {
+    /// A 66mm minimum complexity rocket with a 29mm motor mount
+    fn default() -> Self {
+        const CARDBOARD: f64 = 680.0;
+        const PLYWOOD: f64 = 630.0;
+        const PLASTIC: f64 = 1050.0;
+
+        Self {
+            name: "Untitled".into(),
+            components: vec![
+                Component::new(
+                    "Nose cone",
+                    ComponentKind::NoseCone {
+                        shape: NoseShape::Ogive,
+                        length: 0.26,
+                        diameter: 0.066,
+                        thickness: 0.002,
+                        density: PLASTIC,
+                    },
+                )
+                .with_child(
+                    Component::new(
+                        "Nose weight",
+                        ComponentKind::MassItem {
+                            mass: 0.05,
+                            length: 0.02,
+                        },
+                    )
+                    .at(0.16),
+                ),
+                Component::new(
+                    "Body tube",
+                    ComponentKind::BodyTube {
+                        length: 0.84,
+                        diameter: 0.066,
+                        thickness: 0.0015,
+                        density: CARDBOARD,
+                    },
+                )
+                .with_child(
+                    Component::new(
+                        "Main parachute",
+                        ComponentKind::Parachute {
+                            diameter: 0.9,
+                            drag_coefficient: 0.8,
+                            mass: 0.06,
+                            deployment: Deployment::Apogee { delay: 1.0 },
+                        },
+                    )
+                    .at(0.05),
+                )
+                .with_child(
+                    Component::new(
+                        "Electronics",
+                        ComponentKind::MassItem {
+                            mass: 0.12,
+                            length: 0.1,
+                        },
+                    )
+                    .at(0.3),
+                )
+                .with_child(
+                    Component::new(
+                        "Motor mount",
+                        ComponentKind::MotorMount {
+                            length: 0.2,
+                            diameter: 0.031,
+                            thickness: 0.001,
+                            density: CARDBOARD,
+                        },
+                    )
+                    .at(0.64),
+                )
+                .with_child(
+                    Component::new(
+                        "Fins",
+                        ComponentKind::FinSet {
+                            count: 3,
+                            root_chord: 0.15,
+                            tip_chord: 0.06,
+                            span: 0.075,
+                            sweep: 0.08,
+                            thickness: 0.003,
+                            cant: 0.0,
+                            density: PLYWOOD,
+                        },
+                    )
+                    .at(0.69),
+                ),
+            ],
+        }
+    }
+}
+
+impl Rocket {
+    /// Overall length from the nose tip to the aft end of the last external component (m)
+    pub fn length(&self) -> f64 {
+        self.components
+            .iter()
+            .map(|component| component.kind.length())
+            .sum()
+    }
+
+    /// Largest external diameter, used as the aerodynamic reference (m)
+    pub fn reference_diameter(&self) -> f64 {
+        self.components
+            .iter()
+            .map(|component| component.kind.outer_diameter())
+            .fold(0.0, f64::max)
+    }
+
+    /// External components paired with the distance of their front from the nose tip (m)
+    pub fn stack(&self) -> impl Iterator<Item = (f64, &Component)> {
+        self.components.iter().scan(0.0, |offset, component| {
+            let front = *offset;
+            *offset += component.kind.length();
+
+            Some((front, component))
+        })
+    }
+
+    /// Component at a path of child indices, starting from the external components
+    pub fn component(&self, path: &[usize]) -> Option<&Component> {
+        let (&first, rest) = path.split_first()?;
+
+        rest.iter()
+            .try_fold(self.components.get(first)?, |component, &index| {
+                component.children.get(index)
+            })
+    }
+
+    pub fn component_mut(&mut self, path: &[usize]) -> Option<&mut Component> {
+        let (&first, rest) = path.split_first()?;
+
+        rest.iter()
+            .try_fold(self.components.get_mut(first)?, |component, &index| {
+                component.children.get_mut(index)
+            })
+    }
+
+    /// Children of the component at a path, or the external components if the
+    /// path is empty
+    pub fn children_mut(&mut self, path: &[usize]) -> Option<&mut Vec<Component>> {
+        if path.is_empty() {
+            Some(&mut self.components)
+        } else {
+            self.component_mut(path)
+                .map(|component| &mut component.children)
+        }
+    }
+
+    /// Every component paired with the distance of its front from the nose tip (m)
+    pub fn flatten(&self) -> Vec<(f64, &Component)> {
+        fn visit<'a>(front: f64, component: &'a Component, out: &mut Vec<(f64, &'a Component)>) {
+            out.push((front, component));
+
+            for child in &component.children {
+                visit(front + child.position, child, out);
+            }
+        }
+
+        let mut components = Vec::new();
+
+        for (front, component) in self.stack() {
+            visit(front, component, &mut components);
+        }
+
+        components
+    }
+
+    /// Mass properties of the rocket without a motor
+    pub fn mass_properties(&self) -> MassProperties {
+        self.flatten()
+            .into_iter()
+            .map(|(front, component)| component.kind.mass_properties(front))
+            .fold(MassProperties::default(), MassProperties::combine)
+    }
+
+    /// Position of the aft end of the motor mount, or of the rocket if there is none (m)
+    pub fn motor_position(&self) -> f64 {
+        self.flatten()
+            .into_iter()
+            .find_map(|(front, component)| match component.kind {
+                ComponentKind::MotorMount { length, .. } => Some(front + length),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.length())
+    }
+
+    /// Where nose weight would be added: the center of gravity of the nose
+    /// cone, or the nose tip if the rocket does not start with one (m)
+    pub fn nose_weight_position(&self) -> f64 {
+        match self.components.first().map(|component| &component.kind) {
+            Some(kind @ ComponentKind::NoseCone { length, .. }) => {
+                let nose = kind.mass_properties(0.0);
+
+                if nose.mass > 0.0 {
+                    nose.cg
+                } else {
+                    length / 2.0
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Mass properties of the rocket loaded with a motor, before ignition
+    pub fn loaded_mass_properties(&self, motor: Option<&Motor>) -> MassProperties {
+        let dry = self.mass_properties();
+
+        match motor {
+            Some(motor) => dry.combine(MassProperties {
+                mass: motor.total_mass,
+                cg: self.motor_position() - motor.length / 2.0,
+                longitudinal_inertia: motor.total_mass * motor.length * motor.length / 12.0,
+                roll_inertia: motor.total_mass * motor.diameter * motor.diameter / 8.0,
+            }),
+            None => dry,
+        }
+    }
+
+    /// Check that a motor fits within the motor mount of the rocket
+    pub fn check_motor(&self, motor: &Motor) -> Vec<ValidationError> {
+        let mount = self
+            .flatten()
+            .into_iter()
+            .find_map(|(_, component)| match component.kind {
+                ComponentKind::MotorMount {
+                    diameter,
+                    thickness,
+                    ..
+                } => Some((component, diameter - 2.0 * thickness)),
+                _ => None,
+            });
+
+        match mount {
+            // Allow for motor casings that are nominally the same size as the mount
+            Some((component, inner_diameter)) if motor.diameter > inner_diameter + 0.0005 => {
+                vec![component.error(&format!(
+                    "the {} is {:.1} mm in diameter, too wide for the {:.1} mm mount",
+                    motor.designation,
+                    motor.diameter * 1000.0,
+                    inner_diameter * 1000.0
+                ))]
+            }
+            Some(_) => Vec::new(),
+            None => vec![ValidationError {
+                component: self.name.clone(),
+                message: "there is no motor mount to hold a motor".into(),
+            }],
+        }
+    }
+
+    /// Check that the design is complete and physically meaningful
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        match self.components.first() {
+            Some(Component {
+                kind: ComponentKind::NoseCone { .. },
+                ..
+            }) => {}
+            Some(component) => {
+                errors.push(component.error("the first component must be a nose cone"))
+            }
+            None => errors.push(ValidationError {
+                component: self.name.clone(),
+                message: "the rocket has no components".into(),
+            }),
+        }
+
+        for component in &self.components {
+            if !component.kind.is_external() {
+                errors.push(
+                    component.error("internal components must be placed inside an external one"),
+                );
+            }
+        }
+
+        let mut motor_mounts = 0;
+
+        for (_, component) in self.flatten() {
+            if !component.kind.dimensions().iter().all(|&value| value > 0.0) {
+                errors.push(component.error("dimensions must be greater than zero"));
+            }
+
+            match component.kind {
+                ComponentKind::BodyTube {
+                    diameter,
+                    thickness,
+                    ..
+                }
+                | ComponentKind::MotorMount {
+                    diameter,
+                    thickness,
+                    ..
+                } if thickness * 2.0 >= diameter => {
+                    errors.push(component.error("the wall is thicker than the tube"))
+                }
+                ComponentKind::FinSet { count: 0, .. } => {
+                    errors.push(component.error("there must be at least one fin"))
+                }
+                _ => {}
+            }
+
+            if let ComponentKind::MotorMount { .. } = component.kind {
+                motor_mounts += 1;
+            }
+
+            for child in &component.children {
+                if !component.kind.is_external() {
+                    errors.push(child.error("only external components can contain others"));
+                } else if child.kind.is_external() {
+                    errors.push(child.error("external components cannot be placed inside others"));
+                } else if child.position < 0.0
+                    || child.position + child.kind.length() > component.kind.length() + 1e-9
+                {
+                    errors.push(child.error("does not fit within its parent"));
+                }
+            }
+        }
+
+        if motor_mounts > 1 {
+            errors.push(ValidationError {
+                component: self.name.clone(),
+                message: "only a single motor mount is supported".into(),
+            });
+        }
+
+        errors
+    }
+
+    /// Convert a design saved by another simulator, either OpenRocket (`.ork`)
+    /// or RockSim (`.rkt`)
+    pub fn import(path: &Path) -> Result<Import, ImportError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("ork") => openrocket::import(path),
+            Some("rkt") => rocksim::import(path),
+            _ => Err(ImportError::UnknownFormat),
+        }
+    }
+
+    /// Lumped properties of the rocket flying with a given motor
+    pub fn to_vehicle(&self, motor: Option<Motor>) -> Vehicle {
+        let dry = self.mass_properties();
+        let aerodynamics = Aerodynamics::new(self);
+
+        Vehicle {
+            dry_mass: dry.mass,
+            dry_cg: dry.cg,
+            dry_longitudinal_inertia: dry.longitudinal_inertia,
+            dry_roll_inertia: dry.roll_inertia,
+            length: self.length(),
+            reference_diameter: self.reference_diameter(),
+            drag_coefficient: aerodynamics.drag_coefficient,
+            normal_force_slope: aerodynamics.normal_force_slope,
+            cp: aerodynamics.cp,
+            fin_misalignment: 0.0,
+            motor: motor.map(|motor| (motor, self.motor_position())),
+            recovery: self
+                .flatten()
+                .into_iter()
+                .filter_map(|(_, component)| match component.kind {
+                    ComponentKind::Parachute {
+                        diameter,
+                        drag_coefficient,
+                        deployment,
+                        ..
+                    } => Some(RecoveryDevice {
+                        name: component.name.clone(),
+                        drag_area: drag_coefficient * PI * diameter * diameter / 4.0,
+                        deployment,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Component {
+    pub fn new(name: impl Into<String>, kind: ComponentKind) -> Self {
+        Self {
+            name: name.into(),
+            position: 0.0,
+            kind,
+            children: Vec::new(),
+        }
+    }
+
+    /// Place the component a distance from the front of its parent (m)
+    pub fn at(mut self, position: f64) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_child(mut self, child: Component) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn error(&self, message: &str) -> ValidationError {
+        ValidationError {
+            component: self.name.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+impl ComponentKind {
+    /// Whether the component forms part of the outer airframe
+    pub fn is_external(&self) -> bool {
+        matches!(
+            self,
+            ComponentKind::NoseCone { .. }
+                | ComponentKind::BodyTube { .. }
+                | ComponentKind::Transition { .. }
+        )
+    }
+
+    /// Human readable name of the kind of component
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ComponentKind::NoseCone { .. } => "Nose cone",
+            ComponentKind::BodyTube { .. } => "Body tube",
+            ComponentKind::Transition { .. } => "Transition",
+            ComponentKind::FinSet { .. } => "Fin set",
+            ComponentKind::MotorMount { .. } => "Motor mount",
+            ComponentKind::MassItem { .. } => "Mass item",
+            ComponentKind::Parachute { .. } => "Parachute",
+        }
+    }
+
+    /// Axial length taken up by the component (m)
+    pub fn length(&self) -> f64 {
+        match *self {
+            ComponentKind::NoseCone { length, .. }
+            | ComponentKind::BodyTube { length, .. }
+            | ComponentKind::Transition { length, .. }
+            | ComponentKind::MotorMount { length, .. }
+            | ComponentKind::MassItem { length, .. } => length,
+            ComponentKind::FinSet { root_chord, .. } => root_chord,
+            ComponentKind::Parachute { .. } => 0.0,
+        }
+    }
+
+    /// Largest outer diameter of an external component (m)
+    pub fn outer_diameter(&self) -> f64 {
+        match *self {
+            ComponentKind::NoseCone { diameter, .. } | ComponentKind::BodyTube { diameter, .. } => {
+                diameter
+            }
+            ComponentKind::Transition {
+                fore_diameter,
+                aft_diameter,
+                ..
+            } => fore_diameter.max(aft_diameter),
+            _ => 0.0,
+        }
+    }
+
+    /// Density of the material the component is made from, if it has one
+    fn density_mut(&mut self) -> Option<&mut f64> {
+        match self {
+            ComponentKind::NoseCone { density, .. }
+            | ComponentKind::BodyTube { density, .. }
+            | ComponentKind::Transition { density, .. }
+            | ComponentKind::FinSet { density, .. }
+            | ComponentKind::MotorMount { density, .. } => Some(density),
+            ComponentKind::MassItem { .. } | ComponentKind::Parachute { .. } => None,
+        }
+    }
+
+    /// Dimensions that must be strictly positive for the component to be valid
+    fn dimensions(&self) -> Vec<f64> {
+        match *self {
+            ComponentKind::NoseCone {
+                length,
+                diameter,
+                thickness,
+                density,
+                ..
+            }
+            | ComponentKind::BodyTube {
+                length,
+                diameter,
+                thickness,
+                density,
+            }
+            | ComponentKind::MotorMount {
+                length,
+                diameter,
+                thickness,
+                density,
+            } => vec![length, diameter, thickness, density],
+            ComponentKind::Transition {
+                length,
+                thickness,
+                density,
+                ..
+            } => vec![length, thickness, density],
+            ComponentKind::FinSet {
+                root_chord,
+                span,
+                thickness,
+                density,
+                ..
+            } => vec![root_chord, span, thickness, density],
+            ComponentKind::MassItem { mass, .. } => vec![mass],
+            ComponentKind::Parachute {
+                diameter,
+                drag_coefficient,
+                ..
+            } => vec![diameter, drag_coefficient],
+        }
+    }
+
+    /// Mass properties of the component alone, given the distance of its
+    /// front from the nose tip (m)
+    pub fn mass_properties(&self, front: f64) -> MassProperties {
+        match *self {
+            ComponentKind::NoseCone {
+                shape,
+                length,
+                diameter,
+                thickness,
+                density,
+            } => revolved_shell(front, length, thickness, density, |x| {
+                shape.radius(x, length, diameter)
+            }),
+            ComponentKind::Transition {
+                length,
+                fore_diameter,
+                aft_diameter,
+                thickness,
+                density,
+            } => revolved_shell(front, length, thickness, density, |x| {
+                (fore_diameter + (aft_diameter - fore_diameter) * x) / 2.0
+            }),
+            ComponentKind::BodyTube {
+                length,
+                diameter,
+                thickness,
+                density,
+            }
+            | ComponentKind::MotorMount {
+                length,
+                diameter,
+                thickness,
+                density,
+            } => {
+                let outer = diameter / 2.0;
+                let inner = (outer - thickness).max(0.0);
+                let mass = density * PI * (outer * outer - inner * inner) * length;
+
+                MassProperties {
+                    mass,
+                    cg: front + length / 2.0,
+                    longitudinal_inertia: mass
+                        * (3.0 * (outer * outer + inner * inner) + length * length)
+                        / 12.0,
+                    roll_inertia: mass * (outer * outer + inner * inner) / 2.0,
+                }
+            }
+            ComponentKind::FinSet {
+                count,
+                root_chord,
+                tip_chord,
+                span,
+                sweep,
+                thickness,
+                density,
+                ..
+            } => {
+                let chord_sum = (root_chord + tip_chord).max(f64::EPSILON);
+                let mass = count as f64 * density * thickness * span * chord_sum / 2.0;
+                let centroid = (root_chord * root_chord
+                    + root_chord * tip_chord
+                    + tip_chord * tip_chord
+                    + sweep * (root_chord + 2.0 * tip_chord))
+                    / (3.0 * chord_sum);
+                let spanwise_centroid = span * (root_chord + 2.0 * tip_chord) / (3.0 * chord_sum);
+
+                MassProperties {
+                    mass,
+                    cg: front + centroid,
+                    longitudinal_inertia: mass * root_chord * root_chord / 12.0,
+                    roll_inertia: mass * spanwise_centroid * spanwise_centroid,
+                }
+            }
+            ComponentKind::MassItem { mass, length } => MassProperties {
+                mass,
+                cg: front + length / 2.0,
+                longitudinal_inertia: mass * length * length / 12.0,
+                roll_inertia: 0.0,
+            },
+            ComponentKind::Parachute { mass, .. } => MassProperties {
+                mass,
+                cg: front,
+                longitudinal_inertia: 0.0,
+                roll_inertia: 0.0,
+            },
+        }
+    }
+}
+
+/// Mass properties of a shell revolved around the body axis, given its
+/// radius at each fraction of its length. The wall thickness is measured
+/// across the surface, and a wall as thick as the radius fills the shell.
+fn revolved_shell(
+    front: f64,
+    length: f64,
+    thickness: f64,
+    density: f64,
+    radius: impl Fn(f64) -> f64,
+) -> MassProperties {
+    const SLICES: usize = 50;
+
+    (0..SLICES)
+        .map(|slice| {
+            let start = slice as f64 / SLICES as f64;
+            let end = (slice + 1) as f64 / SLICES as f64;
+            let (r0, r1) = (radius(start), radius(end));
+            let width = length / SLICES as f64;
+            let slant = ((r1 - r0).powi(2) + width * width).sqrt();
+
+            // Sloped walls are thicker across the slice than across the surface
+            let outer = (r0 + r1) / 2.0;
+            let inner = (outer - thickness * slant / width).max(0.0);
+            let squares = outer * outer + inner * inner;
+            let mass = density * PI * (outer * outer - inner * inner) * width;
+
+            MassProperties {
+                mass,
+                cg: front + length * (start + end) / 2.0,
+                longitudinal_inertia: mass * squares / 4.0,
+                roll_inertia: mass * squares / 2.0,
+            }
+        })
+        .fold(MassProperties::default(), MassProperties::combine)
+}
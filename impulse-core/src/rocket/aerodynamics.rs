@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+
+use super::{ComponentKind, NoseShape, Rocket};
+
+/// Skin friction coefficient for a typical painted airframe
+const SKIN_FRICTION: f64 = 0.0045;
+
+/// Subsonic aerodynamic coefficients of a rocket, referenced to the area of
+/// its largest diameter
+#[derive(Debug, Clone, Copy)]
+pub struct Aerodynamics {
+    /// Normal force coefficient slope (per radian)
+    pub normal_force_slope: f64,
+    /// Center of pressure from the nose tip (m)
+    pub cp: f64,
+    /// Zero angle of attack drag coefficient
+    pub drag_coefficient: f64,
+}
+
+impl Aerodynamics {
+    /// Estimate the coefficients of a rocket using the Barrowman equations for
+    /// the normal force and a component build up for the drag
+    pub fn new(rocket: &Rocket) -> Self {
+        let reference_diameter = rocket.reference_diameter().max(f64::EPSILON);
+        let reference_area = PI * reference_diameter * reference_diameter / 4.0;
+        let length = rocket.length();
+
+        let mut normal_force_slope = 0.0;
+        let mut moment = 0.0;
+        let mut wetted_area = 0.0;
+        let mut fin_drag = 0.0;
+        let mut aft_diameter = 0.0;
+
+        let mut add = |slope: f64, position: f64| {
+            normal_force_slope += slope;
+            moment += slope * position;
+        };
+
+        for (front, component) in rocket.flatten() {
+            match component.kind {
+                ComponentKind::NoseCone {
+                    shape,
+                    length,
+                    diameter,
+                    ..
+                } => {
+                    let center = match shape {
+                        NoseShape::Conical => 0.666,
+                        NoseShape::Ogive => 0.466,
+                        NoseShape::Parabolic => 0.5,
+                        NoseShape::Ellipsoid => 0.333,
+                    };
+
+                    add(
+                        2.0 * (diameter / reference_diameter).powi(2),
+                        front + center * length,
+                    );
+                    wetted_area +=
+                        PI * diameter / 2.0 * (length * length + diameter * diameter / 4.0).sqrt();
+                    aft_diameter = diameter;
+                }
+                ComponentKind::BodyTube {
+                    length, diameter, ..
+                } => {
+                    wetted_area += PI * diameter * length;
+                    aft_diameter = diameter;
+                }
+                ComponentKind::Transition {
+                    length,
+                    fore_diameter,
+                    aft_diameter: transition_aft_diameter,
+                    ..
+                } => {
+                    let ratio = fore_diameter / transition_aft_diameter.max(f64::EPSILON);
+                    let center = if (1.0 - ratio).abs() > f64::EPSILON {
+                        front + length / 3.0 * (1.0 + (1.0 - ratio) / (1.0 - ratio * ratio))
+                    } else {
+                        front + length / 2.0
+                    };
+
+                    add(
+                        2.0 * ((transition_aft_diameter / reference_diameter).powi(2)
+                            - (fore_diameter / reference_diameter).powi(2)),
+                        center,
+                    );
+                    wetted_area += PI * (fore_diameter + transition_aft_diameter) / 2.0 * length;
+                    aft_diameter = transition_aft_diameter;
+                }
+                ComponentKind::FinSet {
+                    count,
+                    root_chord,
+                    tip_chord,
+                    span,
+                    sweep,
+                    thickness,
+                    ..
+                } => {
+                    let body_radius = body_diameter_at(rocket, front) / 2.0;
+                    let chord_sum = (root_chord + tip_chord).max(f64::EPSILON);
+                    let mid_chord_line =
+                        (span * span + (sweep + tip_chord / 2.0 - root_chord / 2.0).powi(2)).sqrt();
+                    let interference = 1.0 + body_radius / (span + body_radius);
+                    // Fin sets with more than four fins gain less than proportionally
+                    let effective_count = match count {
+                        0..=4 => count as f64,
+                        _ => 4.0 + (count as f64 - 4.0) * 0.5,
+                    };
+
+                    add(
+                        interference * 4.0 * effective_count * (span / reference_diameter).powi(2)
+                            / (1.0 + (1.0 + (2.0 * mid_chord_line / chord_sum).powi(2)).sqrt()),
+                        front
+                            + sweep * (root_chord + 2.0 * tip_chord) / (3.0 * chord_sum)
+                            + (chord_sum - root_chord * tip_chord / chord_sum) / 6.0,
+                    );
+
+                    // Both sides of each fin are wetted
+                    let mean_chord = chord_sum / 2.0;
+                    fin_drag += 2.0
+                        * SKIN_FRICTION
+                        * (1.0 + 2.0 * thickness / mean_chord.max(f64::EPSILON))
+                        * count as f64
+                        * span
+                        * mean_chord
+                        / reference_area;
+                }
+                _ => {}
+            }
+        }
+
+        let fineness = length / reference_diameter;
+        let body_drag =
+            1.02 * SKIN_FRICTION * (1.0 + 1.5 / fineness.max(1.0).powf(1.5)) * wetted_area
+                / reference_area;
+        let base_drag = 0.029 / body_drag.max(f64::EPSILON).sqrt()
+            * (aft_diameter / reference_diameter).powi(2);
+
+        Self {
+            normal_force_slope,
+            cp: if normal_force_slope > 0.0 {
+                moment / normal_force_slope
+            } else {
+                length
+            },
+            drag_coefficient: body_drag + base_drag + fin_drag,
+        }
+    }
+}
+
+/// Outer diameter of the airframe at a distance from the nose tip (m)
+fn body_diameter_at(rocket: &Rocket, position: f64) -> f64 {
+    rocket
+        .stack()
+        .find(|(front, component)| position < front + component.kind.length())
+        .or_else(|| rocket.stack().last())
+        .map(|(front, component)| match component.kind {
+            ComponentKind::NoseCone {
+                shape,
+                length,
+                diameter,
+                ..
+            } => 2.0 * shape.radius((position - front) / length, length, diameter),
+            ComponentKind::Transition {
+                length,
+                fore_diameter,
+                aft_diameter,
+                ..
+            } => fore_diameter + (aft_diameter - fore_diameter) * (position - front) / length,
+            ref kind => kind.outer_diameter(),
+        })
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,156 @@
+use std::{
+    f64::consts::PI,
+    fmt::{self, Display},
+    io,
+};
+
+use thiserror::Error;
+
+use super::{Component, ComponentKind, Rocket};
+
+/// A rocket design converted from another simulator
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub rocket: Rocket,
+    /// Motor the design was set up to fly with, if any
+    pub motor: Option<MotorReference>,
+    /// What happened to each component along the way
+    pub log: Vec<ImportMessage>,
+}
+
+/// A motor referred to by name in an imported design, which still needs to be
+/// found on ThrustCurve or in a motor file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotorReference {
+    pub manufacturer: Option<String>,
+    pub designation: String,
+    /// Ejection delay (s)
+    pub delay: Option<f64>,
+}
+
+/// A note about how a single component was imported
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportMessage {
+    pub component: String,
+    pub level: ImportLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportLevel {
+    /// The component was converted as expected
+    Info,
+    /// The component was approximated or left out
+    Warning,
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("failed to read design file: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to unpack design file: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("failed to parse design XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("unsupported design file extension, expected .ork or .rkt")]
+    UnknownFormat,
+}
+
+impl Display for ImportMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.component, self.message)
+    }
+}
+
+/// Collects [`ImportMessage`]s while converting a design
+#[derive(Debug, Default)]
+pub(super) struct ImportLog {
+    messages: Vec<ImportMessage>,
+}
+
+impl ImportLog {
+    pub fn info(&mut self, component: &str, message: impl Into<String>) {
+        self.push(component, ImportLevel::Info, message.into());
+    }
+
+    pub fn warn(&mut self, component: &str, message: impl Into<String>) {
+        self.push(component, ImportLevel::Warning, message.into());
+    }
+
+    fn push(&mut self, component: &str, level: ImportLevel, message: String) {
+        self.messages.push(ImportMessage {
+            component: component.to_string(),
+            level,
+            message,
+        });
+    }
+
+    /// Replace the computed mass of a component with one given in the design,
+    /// by scaling the material density for components that do not have a
+    /// mass of their own
+    pub fn set_mass(&mut self, component: &mut Component, mass: f64) {
+        let current = component.kind.mass_properties(0.0).mass;
+
+        match &mut component.kind {
+            ComponentKind::MassItem { mass: item, .. }
+            | ComponentKind::Parachute { mass: item, .. } => *item = mass,
+            kind if current > 0.0 => {
+                if let Some(density) = kind.density_mut() {
+                    *density *= mass / current;
+                }
+            }
+            _ => {
+                self.warn(&component.name, "mass override ignored");
+
+                return;
+            }
+        }
+
+        self.info(
+            &component.name,
+            format!("mass overridden to {:.1} g", mass * 1000.0),
+        );
+    }
+
+    pub fn into_messages(self) -> Vec<ImportMessage> {
+        self.messages
+    }
+}
+
+/// The trapezoidal fin closest to an arbitrary outline
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Trapezoid {
+    pub root_chord: f64,
+    pub tip_chord: f64,
+    pub span: f64,
+    pub sweep: f64,
+}
+
+impl Trapezoid {
+    /// Fit a trapezoid to the outline of a fin, given as points along the body
+    /// and out from the root (m)
+    pub fn from_points(points: &[(f64, f64)]) -> Self {
+        let leading = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let trailing = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+        let span = points.iter().map(|p| p.1).fold(0.0, f64::max);
+        let tip = points.iter().filter(|p| p.1 >= span * 0.99).map(|p| p.0);
+        let tip_leading = tip.clone().fold(f64::INFINITY, f64::min);
+        let tip_trailing = tip.fold(f64::NEG_INFINITY, f64::max);
+
+        Self {
+            root_chord: (trailing - leading).max(0.0),
+            tip_chord: (tip_trailing - tip_leading).max(0.0),
+            span,
+            sweep: (tip_leading - leading).max(0.0),
+        }
+    }
+}
+
+/// Mass of a solid ring or disc (kg)
+pub(super) fn ring_mass(outer_radius: f64, inner_radius: f64, length: f64, density: f64) -> f64 {
+    let inner_radius = inner_radius.max(0.0);
+
+    density * PI * (outer_radius * outer_radius - inner_radius * inner_radius) * length
+}
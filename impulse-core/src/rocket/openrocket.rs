@@ -0,0 +1,1092 @@
+//! OpenRocket designs (`.ork`)
+//!
+//! An `.ork` file is a zip archive (or a gzip stream, for older versions)
+//! holding an XML document with every length in metres. Stages are flattened
+//! into a single airframe, and anything impulse cannot represent is either
+//! approximated or left out, with a note in the import log.
+
+use std::{
+    f64::consts::PI,
+    fs,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+use roxmltree::{Document, Node};
+use zip::ZipArchive;
+
+use super::{
+    import::{ring_mass, Import, ImportError, ImportLog, MotorReference, Trapezoid},
+    Component, ComponentKind, NoseShape, Rocket,
+};
+use crate::sim::Deployment;
+
+/// Radius used for external components whose radius cannot be worked out (m)
+const DEFAULT_RADIUS: f64 = 0.025;
+
+/// Drag coefficients OpenRocket uses when a recovery device's is set to `auto`
+const AUTO_PARACHUTE_CD: f64 = 0.8;
+const AUTO_STREAMER_CD: f64 = 0.6;
+
+/// Wall of the motor mount added to body tubes that hold a motor directly (m)
+const MOUNT_THICKNESS: f64 = 0.0005;
+
+pub(super) fn import(path: &Path) -> Result<Import, ImportError> {
+    parse(&unpack(&fs::read(path)?)?)
+}
+
+/// Extract the XML document from the container it was saved in
+fn unpack(bytes: &[u8]) -> Result<String, ImportError> {
+    let mut xml = String::new();
+
+    match bytes {
+        [b'P', b'K', ..] => {
+            let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+            let name = archive
+                .file_names()
+                .find(|name| name.ends_with(".ork") || name.ends_with(".xml"))
+                .map(String::from)
+                .ok_or_else(|| ImportError::Invalid("the archive has no design in it".into()))?;
+
+            archive.by_name(&name)?.read_to_string(&mut xml)?;
+        }
+        [0x1f, 0x8b, ..] => {
+            GzDecoder::new(bytes).read_to_string(&mut xml)?;
+        }
+        _ => {
+            xml = String::from_utf8(bytes.to_vec())
+                .map_err(|_| ImportError::Invalid("not an OpenRocket design".into()))?;
+        }
+    }
+
+    Ok(xml)
+}
+
+fn parse(xml: &str) -> Result<Import, ImportError> {
+    let document = Document::parse(xml)?;
+    let root = document.root_element();
+
+    if !root.has_tag_name("openrocket") {
+        return Err(ImportError::Invalid("not an OpenRocket design".into()));
+    }
+
+    let rocket = child(root, "rocket")
+        .ok_or_else(|| ImportError::Invalid("the design has no rocket in it".into()))?;
+    let name = text(rocket, "name")
+        .unwrap_or("Imported rocket")
+        .to_string();
+
+    let mut importer = Importer {
+        log: ImportLog::default(),
+        motor: None,
+        configuration: rocket
+            .children()
+            .filter(|node| node.has_tag_name("motorconfiguration"))
+            .find(|node| node.attribute("default") == Some("true"))
+            .and_then(|node| node.attribute("configid")),
+    };
+
+    let stages: Vec<_> = subcomponents(rocket)
+        .filter(|node| node.has_tag_name("stage"))
+        .collect();
+
+    if stages.len() > 1 {
+        for stage in &stages[1..] {
+            importer.log.warn(
+                &component_name(*stage),
+                "stages are flattened into a single airframe, so separation is not simulated",
+            );
+        }
+    }
+
+    let externals: Vec<_> = stages
+        .iter()
+        .flat_map(|stage| subcomponents(*stage))
+        .collect();
+    let radii = importer.radii(&externals);
+
+    let mut components = Vec::new();
+    let mut front = 0.0;
+
+    for (node, [fore, aft]) in externals.into_iter().zip(radii) {
+        if let Some(component) = importer.external(node, front, fore, aft) {
+            front += component.kind.length();
+            components.push(component);
+        }
+    }
+
+    let rocket = Rocket { name, components };
+
+    for error in rocket.validate() {
+        importer.log.warn(
+            &error.component,
+            format!("{}, which needs fixing in the editor", error.message),
+        );
+    }
+
+    Ok(Import {
+        rocket,
+        motor: importer.motor,
+        log: importer.log.into_messages(),
+    })
+}
+
+/// Where an internal component is being placed
+struct Frame {
+    /// Distance of the parent's front from the nose tip (m)
+    front: f64,
+    length: f64,
+    /// Radius available inside the parent (m)
+    inner_radius: f64,
+}
+
+struct Importer<'a> {
+    log: ImportLog,
+    motor: Option<MotorReference>,
+    /// Motor configuration selected as the default in OpenRocket
+    configuration: Option<&'a str>,
+}
+
+impl<'a> Importer<'a> {
+    /// Fore and aft radius of each external component, filling in those set to
+    /// `auto` from their neighbours
+    fn radii(&mut self, externals: &[Node]) -> Vec<[f64; 2]> {
+        let mut radii: Vec<[Option<f64>; 2]> = externals
+            .iter()
+            .map(|&node| match node.tag_name().name() {
+                "nosecone" => [Some(0.0), radius(node, "aftradius")],
+                "bodytube" => [radius(node, "radius"); 2],
+                "transition" => [radius(node, "foreradius"), radius(node, "aftradius")],
+                _ => [None, None],
+            })
+            .collect();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for index in 0..radii.len() {
+                let previous = index.checked_sub(1).and_then(|previous| radii[previous][1]);
+                let next = radii.get(index + 1).and_then(|next| next[0]);
+                let radius = &mut radii[index];
+
+                if radius[0].is_none() && previous.is_some() {
+                    radius[0] = previous;
+                    changed = true;
+                }
+
+                if radius[1].is_none() && next.is_some() {
+                    radius[1] = next;
+                    changed = true;
+                }
+
+                if externals[index].has_tag_name("bodytube") && radius[0] != radius[1] {
+                    let tube = radius[0].or(radius[1]);
+                    *radius = [tube, tube];
+                    changed = true;
+                }
+            }
+        }
+
+        externals
+            .iter()
+            .zip(radii)
+            .map(|(&node, [fore, aft])| {
+                let mut resolve = |value: Option<f64>, tag: &str| {
+                    value.or_else(|| number(node, tag)).unwrap_or_else(|| {
+                        self.log.warn(
+                            &component_name(node),
+                            format!(
+                                "could not work out its radius, so {} mm was used",
+                                DEFAULT_RADIUS * 1000.0
+                            ),
+                        );
+
+                        DEFAULT_RADIUS
+                    })
+                };
+
+                match node.tag_name().name() {
+                    "bodytube" => [resolve(fore, "radius"); 2],
+                    "nosecone" => [0.0, resolve(aft, "aftradius")],
+                    "transition" => [resolve(fore, "foreradius"), resolve(aft, "aftradius")],
+                    _ => [0.0, 0.0],
+                }
+            })
+            .collect()
+    }
+
+    fn external(
+        &mut self,
+        node: Node<'a, '_>,
+        front: f64,
+        fore_radius: f64,
+        aft_radius: f64,
+    ) -> Option<Component> {
+        let name = component_name(node);
+        let length = number(node, "length").unwrap_or(0.0);
+        let thickness = number(node, "thickness").unwrap_or(0.0);
+        let density = density(node);
+
+        let kind = match node.tag_name().name() {
+            "nosecone" => {
+                let shape = match text(node, "shape").unwrap_or("ogive") {
+                    "conical" => NoseShape::Conical,
+                    "ogive" => NoseShape::Ogive,
+                    "ellipsoid" => NoseShape::Ellipsoid,
+                    "parabolic" => NoseShape::Parabolic,
+                    other => {
+                        self.log.warn(
+                            &name,
+                            format!("{} profile approximated as parabolic", other),
+                        );
+
+                        NoseShape::Parabolic
+                    }
+                };
+
+                ComponentKind::NoseCone {
+                    shape,
+                    length,
+                    diameter: aft_radius * 2.0,
+                    thickness: self.wall(node, &name, thickness, aft_radius),
+                    density,
+                }
+            }
+            "bodytube" => ComponentKind::BodyTube {
+                length,
+                diameter: fore_radius * 2.0,
+                thickness,
+                density,
+            },
+            "transition" => {
+                if let Some(shape) = text(node, "shape").filter(|&shape| shape != "conical") {
+                    self.log
+                        .warn(&name, format!("{} profile approximated as conical", shape));
+                }
+
+                ComponentKind::Transition {
+                    length,
+                    fore_diameter: fore_radius * 2.0,
+                    aft_diameter: aft_radius * 2.0,
+                    thickness: self.wall(node, &name, thickness, fore_radius.max(aft_radius)),
+                    density,
+                }
+            }
+            other => {
+                self.log.warn(
+                    &name,
+                    format!("{} components are not supported and were left out", other),
+                );
+
+                return None;
+            }
+        };
+
+        let mut component = Component::new(name, kind);
+        self.override_mass(node, &mut component);
+
+        let frame = Frame {
+            front,
+            length,
+            inner_radius: (fore_radius.max(aft_radius) - thickness).max(0.0),
+        };
+
+        if let Some(radius) = number(node, "foreshoulderradius") {
+            self.shoulder(node, "fore", radius, 0.0, &mut component);
+        }
+
+        if let Some(radius) = number(node, "aftshoulderradius") {
+            self.shoulder(node, "aft", radius, length, &mut component);
+        }
+
+        if node.has_tag_name("bodytube") && child(node, "motormount").is_some() {
+            // OpenRocket lets the airframe itself hold the motor, which
+            // impulse models with a motor mount the length of the tube
+            if self.motor_reference(node) {
+                self.log.warn(
+                    &component.name,
+                    "holds the motor directly, so a motor mount was added inside it",
+                );
+
+                component.children.push(Component::new(
+                    "Motor mount",
+                    ComponentKind::MotorMount {
+                        length,
+                        diameter: (frame.inner_radius + MOUNT_THICKNESS) * 2.0,
+                        thickness: MOUNT_THICKNESS,
+                        density,
+                    },
+                ));
+            }
+        }
+
+        for node in subcomponents(node) {
+            self.internal(node, &frame, &mut component.children);
+        }
+
+        self.log.info(&component.name, "imported");
+
+        Some(component)
+    }
+
+    /// Convert an internal component, flattening anything placed inside inner
+    /// tubes into their parent
+    fn internal(&mut self, node: Node<'a, '_>, parent: &Frame, out: &mut Vec<Component>) {
+        let name = component_name(node);
+        let length = number(node, "length").unwrap_or(0.0);
+        let thickness = number(node, "thickness").unwrap_or(0.0);
+        let density = density(node);
+
+        let kind = match node.tag_name().name() {
+            "trapezoidfinset" => ComponentKind::FinSet {
+                count: fin_count(node),
+                root_chord: number(node, "rootchord").unwrap_or(0.0),
+                tip_chord: number(node, "tipchord").unwrap_or(0.0),
+                span: number(node, "height").unwrap_or(0.0),
+                sweep: number(node, "sweeplength").unwrap_or(0.0),
+                thickness,
+                cant: number(node, "cant").unwrap_or(0.0).to_radians(),
+                density,
+            },
+            "ellipticalfinset" => {
+                // A trapezoid with the same root chord, span and area
+                let root_chord = number(node, "rootchord").unwrap_or(0.0);
+                let tip_chord = root_chord * (PI / 2.0 - 1.0);
+
+                self.log
+                    .warn(&name, "elliptical fins approximated as trapezoidal");
+
+                ComponentKind::FinSet {
+                    count: fin_count(node),
+                    root_chord,
+                    tip_chord,
+                    span: number(node, "height").unwrap_or(0.0),
+                    sweep: (root_chord - tip_chord) / 2.0,
+                    thickness,
+                    cant: number(node, "cant").unwrap_or(0.0).to_radians(),
+                    density,
+                }
+            }
+            "freeformfinset" => {
+                let points: Vec<(f64, f64)> = child(node, "finpoints")
+                    .into_iter()
+                    .flat_map(|points| points.children())
+                    .filter(|point| point.has_tag_name("point"))
+                    .filter_map(|point| {
+                        Some((
+                            point.attribute("x")?.parse().ok()?,
+                            point.attribute("y")?.parse().ok()?,
+                        ))
+                    })
+                    .collect();
+
+                let outline = Trapezoid::from_points(&points);
+
+                self.log.warn(
+                    &name,
+                    "freeform fins approximated as trapezoidal from their outline",
+                );
+
+                ComponentKind::FinSet {
+                    count: fin_count(node),
+                    root_chord: outline.root_chord,
+                    tip_chord: outline.tip_chord,
+                    span: outline.span,
+                    sweep: outline.sweep,
+                    thickness,
+                    cant: number(node, "cant").unwrap_or(0.0).to_radians(),
+                    density,
+                }
+            }
+            "innertube" => {
+                let outer_radius = number(node, "outerradius").unwrap_or(0.0);
+                let inner_radius = (outer_radius - thickness).max(0.0);
+                let position = self.position(node, length, parent);
+
+                self.inside(node, parent, position, length, inner_radius, out);
+
+                if child(node, "motormount").is_none() || !self.motor_reference(node) {
+                    let mass = ring_mass(outer_radius, inner_radius, length, density);
+
+                    return self.mass_item(node, &name, mass, length, position, out);
+                }
+
+                if text(node, "clusterconfiguration").is_some_and(|c| c != "single") {
+                    self.log.warn(
+                        &name,
+                        "motor clusters are not supported, so one motor is used",
+                    );
+                }
+
+                let kind = ComponentKind::MotorMount {
+                    length,
+                    diameter: outer_radius * 2.0,
+                    thickness,
+                    density,
+                };
+
+                return self.push(node, name, kind, position, out);
+            }
+            "tubecoupler" => {
+                let outer_radius = radius(node, "outerradius").unwrap_or(parent.inner_radius);
+                let mass = ring_mass(outer_radius, outer_radius - thickness, length, density);
+                let position = self.position(node, length, parent);
+
+                self.inside(
+                    node,
+                    parent,
+                    position,
+                    length,
+                    outer_radius - thickness,
+                    out,
+                );
+
+                return self.mass_item(node, &name, mass, length, position, out);
+            }
+            "centeringring" | "bulkhead" | "engineblock" => {
+                let outer_radius = radius(node, "outerradius").unwrap_or(parent.inner_radius);
+                let inner_radius = match node.tag_name().name() {
+                    "bulkhead" => 0.0,
+                    "engineblock" => outer_radius - thickness,
+                    _ => radius(node, "innerradius").unwrap_or_else(|| {
+                        // An automatic inner radius fits the inner tube it centres
+                        node.parent()
+                            .into_iter()
+                            .flat_map(|parent| parent.children())
+                            .find(|sibling| sibling.has_tag_name("innertube"))
+                            .and_then(|tube| number(tube, "outerradius"))
+                            .unwrap_or(0.0)
+                    }),
+                };
+                let mass = ring_mass(outer_radius, inner_radius, length, density);
+                let position = self.position(node, length, parent);
+
+                return self.mass_item(node, &name, mass, length, position, out);
+            }
+            "masscomponent" => {
+                let mass = number(node, "mass").unwrap_or(0.0);
+                let length = number(node, "packedlength").unwrap_or(0.0);
+                let position = self.position(node, length, parent);
+
+                return self.mass_item(node, &name, mass, length, position, out);
+            }
+            "shockcord" => {
+                let mass = number(node, "cordlength").unwrap_or(0.0) * density;
+                let length = number(node, "packedlength").unwrap_or(0.0);
+                let position = self.position(node, length, parent);
+
+                return self.mass_item(node, &name, mass, length, position, out);
+            }
+            "parachute" => {
+                let diameter = number(node, "diameter").unwrap_or(0.0);
+                let lines = number(node, "linecount").unwrap_or(0.0)
+                    * number(node, "linelength").unwrap_or(0.0)
+                    * child(node, "linematerial")
+                        .and_then(|material| material.attribute("density"))
+                        .and_then(|density| density.parse().ok())
+                        .unwrap_or(0.0);
+
+                let deployment = match self.deployment(node, &name) {
+                    Some(deployment) => deployment,
+                    None => return,
+                };
+
+                ComponentKind::Parachute {
+                    diameter,
+                    drag_coefficient: self.drag_coefficient(node, &name, AUTO_PARACHUTE_CD),
+                    mass: PI * diameter * diameter / 4.0 * density + lines,
+                    deployment,
+                }
+            }
+            "streamer" => {
+                let area = number(node, "striplength").unwrap_or(0.0)
+                    * number(node, "stripwidth").unwrap_or(0.0);
+
+                let deployment = match self.deployment(node, &name) {
+                    Some(deployment) => deployment,
+                    None => return,
+                };
+
+                self.log.warn(
+                    &name,
+                    "streamer approximated as a parachute with the same area",
+                );
+
+                ComponentKind::Parachute {
+                    diameter: (4.0 * area / PI).sqrt(),
+                    drag_coefficient: self.drag_coefficient(node, &name, AUTO_STREAMER_CD),
+                    mass: area * density,
+                    deployment,
+                }
+            }
+            "launchlug" | "railbutton" => {
+                self.log.warn(
+                    &name,
+                    "launch guides are not modelled, so their drag is ignored",
+                );
+
+                return;
+            }
+            other => {
+                self.log.warn(
+                    &name,
+                    format!("{} components are not supported and were left out", other),
+                );
+
+                return;
+            }
+        };
+
+        let position = self.position(node, kind.length(), parent);
+
+        self.push(node, name, kind, position, out);
+    }
+
+    fn push(
+        &mut self,
+        node: Node<'a, '_>,
+        name: String,
+        kind: ComponentKind,
+        position: f64,
+        out: &mut Vec<Component>,
+    ) {
+        let mut component = Component::new(name, kind).at(position);
+        self.override_mass(node, &mut component);
+        self.log.info(&component.name, "imported");
+
+        out.push(component);
+    }
+
+    /// Convert the components inside an inner tube or coupler, which impulse
+    /// places directly in the external component holding the tube
+    fn inside(
+        &mut self,
+        node: Node<'a, '_>,
+        parent: &Frame,
+        position: f64,
+        length: f64,
+        inner_radius: f64,
+        out: &mut Vec<Component>,
+    ) {
+        let frame = Frame {
+            front: parent.front + position,
+            length,
+            inner_radius,
+        };
+
+        let mut inside = Vec::new();
+
+        for node in subcomponents(node) {
+            self.internal(node, &frame, &mut inside);
+        }
+
+        out.extend(inside.into_iter().map(|child| {
+            let position = child.position + position;
+            child.at(position)
+        }));
+    }
+
+    /// Add a component impulse has no equivalent for as a plain mass
+    fn mass_item(
+        &mut self,
+        node: Node<'a, '_>,
+        name: &str,
+        mass: f64,
+        length: f64,
+        position: f64,
+        out: &mut Vec<Component>,
+    ) {
+        let mut component =
+            Component::new(name, ComponentKind::MassItem { mass, length }).at(position);
+
+        self.override_mass(node, &mut component);
+
+        if !node.has_tag_name("masscomponent") {
+            self.log.info(
+                name,
+                format!(
+                    "{} imported as a mass item of {:.1} g",
+                    node.tag_name().name(),
+                    component.kind.mass_properties(0.0).mass * 1000.0
+                ),
+            );
+        }
+
+        out.push(component);
+    }
+
+    /// Add a shoulder's mass at one end of an external component
+    fn shoulder(
+        &mut self,
+        node: Node<'a, '_>,
+        end: &str,
+        radius: f64,
+        position: f64,
+        component: &mut Component,
+    ) {
+        let length = number(node, &format!("{}shoulderlength", end)).unwrap_or(0.0);
+        let thickness = number(node, &format!("{}shoulderthickness", end)).unwrap_or(0.0);
+
+        if length <= 0.0 {
+            return;
+        }
+
+        let density = density(node);
+        let mut mass = ring_mass(radius, radius - thickness, length, density);
+
+        if text(node, &format!("{}shouldercapped", end)) == Some("true") {
+            mass += ring_mass(radius - thickness, 0.0, thickness, density);
+        }
+
+        self.log.info(
+            &component.name,
+            format!("{} shoulder added as a mass item at its {} end", end, end),
+        );
+
+        component.children.push(
+            Component::new(
+                format!("{} shoulder", component.name),
+                ComponentKind::MassItem { mass, length: 0.0 },
+            )
+            .at(position),
+        );
+    }
+
+    /// Wall thickness of a shell, treating a filled one as solid
+    fn wall(&mut self, node: Node, name: &str, thickness: f64, radius: f64) -> f64 {
+        if text(node, "filled") == Some("true") {
+            self.log
+                .info(name, "filled, so its wall thickness was set to its radius");
+
+            radius
+        } else {
+            thickness
+        }
+    }
+
+    /// Distance from the front of the parent to the front of a component (m)
+    fn position(&mut self, node: Node, length: f64, parent: &Frame) -> f64 {
+        let (method, offset) = match child(node, "axialoffset") {
+            Some(offset) => (offset.attribute("method"), offset),
+            None => match child(node, "position") {
+                Some(offset) => (offset.attribute("type"), offset),
+                None => return 0.0,
+            },
+        };
+
+        let offset = offset
+            .text()
+            .and_then(|text| text.trim().parse().ok())
+            .unwrap_or(0.0);
+
+        let position = match method.unwrap_or("top") {
+            "top" => offset,
+            "middle" => (parent.length - length) / 2.0 + offset,
+            "bottom" => parent.length - length + offset,
+            "absolute" => offset - parent.front,
+            other => {
+                self.log.warn(
+                    &component_name(node),
+                    format!("unknown position type {}, placed from the top", other),
+                );
+
+                offset
+            }
+        };
+
+        let clamped = position.max(0.0).min((parent.length - length).max(0.0));
+
+        if (clamped - position).abs() > 1e-6 {
+            self.log.warn(
+                &component_name(node),
+                "sticks out of its parent, so it was moved inside",
+            );
+        }
+
+        clamped
+    }
+
+    fn drag_coefficient(&mut self, node: Node, name: &str, auto: f64) -> f64 {
+        match text(node, "cd").and_then(|cd| cd.parse().ok()) {
+            Some(cd) => cd,
+            None => {
+                self.log.info(
+                    name,
+                    format!("automatic drag coefficient replaced with {}", auto),
+                );
+
+                auto
+            }
+        }
+    }
+
+    /// When a recovery device opens, or `None` if it never does in impulse
+    fn deployment(&mut self, node: Node, name: &str) -> Option<Deployment> {
+        let delay = number(node, "deploydelay").unwrap_or(0.0);
+
+        match text(node, "deployevent").unwrap_or("ejection") {
+            "apogee" => Some(Deployment::Apogee { delay }),
+            "ejection" => {
+                self.log.warn(
+                    name,
+                    "deploys at motor ejection, which was replaced with apogee",
+                );
+
+                Some(Deployment::Apogee { delay })
+            }
+            "altitude" => Some(Deployment::Altitude(
+                number(node, "deployaltitude").unwrap_or(0.0),
+            )),
+            other => {
+                self.log.warn(
+                    name,
+                    format!(
+                        "deploys on {}, which is not supported, so it was left out",
+                        other
+                    ),
+                );
+
+                None
+            }
+        }
+    }
+
+    /// Apply an OpenRocket mass override by scaling the material density, or
+    /// the mass itself for components that have one
+    fn override_mass(&mut self, node: Node, component: &mut Component) {
+        if text(node, "overridecg").is_some() {
+            self.log
+                .warn(&component.name, "centre of gravity override ignored");
+        }
+
+        if text(node, "overridecd").is_some() {
+            self.log
+                .warn(&component.name, "drag coefficient override ignored");
+        }
+
+        let mass = match number(node, "overridemass") {
+            Some(mass) => mass,
+            None => return,
+        };
+
+        if text(node, "overridesubcomponentsmass") == Some("true") {
+            self.log.warn(
+                &component.name,
+                "mass override including subcomponents ignored",
+            );
+
+            return;
+        }
+
+        self.log.set_mass(component, mass);
+    }
+
+    /// Record the motor held by a mount, returning whether this is the mount
+    /// used by impulse
+    fn motor_reference(&mut self, node: Node) -> bool {
+        let name = component_name(node);
+
+        if self.motor.is_some() {
+            self.log.warn(
+                &name,
+                "only a single motor mount is supported, so this one is imported as a tube",
+            );
+
+            return false;
+        }
+
+        let motors: Vec<_> = child(node, "motormount")
+            .into_iter()
+            .flat_map(|mount| mount.children())
+            .filter(|motor| motor.has_tag_name("motor"))
+            .collect();
+
+        let motor = motors
+            .iter()
+            .find(|motor| {
+                self.configuration.is_some() && motor.attribute("configid") == self.configuration
+            })
+            .or_else(|| motors.first());
+
+        self.motor = Some(match motor {
+            Some(&motor) => MotorReference {
+                manufacturer: text(motor, "manufacturer").map(String::from),
+                designation: text(motor, "designation").unwrap_or_default().to_string(),
+                delay: text(motor, "delay").and_then(|delay| delay.parse().ok()),
+            },
+            None => MotorReference {
+                manufacturer: None,
+                designation: String::new(),
+                delay: None,
+            },
+        });
+
+        true
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(tag))
+}
+
+fn text<'a>(node: Node<'a, '_>, tag: &str) -> Option<&'a str> {
+    child(node, tag)?.text().map(str::trim)
+}
+
+/// First number in an element, skipping a leading `auto`
+fn number(node: Node, tag: &str) -> Option<f64> {
+    text(node, tag)?
+        .split_whitespace()
+        .find_map(|word| word.parse().ok())
+}
+
+/// A radius, or `None` if it is set to `auto`
+fn radius(node: Node, tag: &str) -> Option<f64> {
+    text(node, tag)
+        .filter(|text| !text.starts_with("auto"))
+        .and_then(|text| text.parse().ok())
+}
+
+fn subcomponents<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    child(node, "subcomponents")
+        .into_iter()
+        .flat_map(|subcomponents| subcomponents.children())
+        .filter(Node::is_element)
+}
+
+fn component_name(node: Node) -> String {
+    text(node, "name")
+        .unwrap_or_else(|| node.tag_name().name())
+        .to_string()
+}
+
+/// Bulk, surface or line density of a component's material, depending on
+/// what kind of component it is
+fn density(node: Node) -> f64 {
+    child(node, "material")
+        .and_then(|material| material.attribute("density"))
+        .and_then(|density| density.parse().ok())
+        .unwrap_or(0.0)
+}
+
+fn fin_count(node: Node) -> u32 {
+    text(node, "fincount")
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+    use crate::rocket::import::ImportLevel;
+
+    const DESIGN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<openrocket version="1.8" creator="OpenRocket 23.09">
+  <rocket>
+    <name>Test rocket</name>
+    <motorconfiguration configid="a" default="true"/>
+    <subcomponents>
+      <stage>
+        <name>Sustainer</name>
+        <subcomponents>
+          <nosecone>
+            <name>Nose cone</name>
+            <material type="bulk" density="1050.0">Polystyrene</material>
+            <length>0.15</length>
+            <thickness>0.002</thickness>
+            <shape>ogive</shape>
+            <aftradius>auto</aftradius>
+          </nosecone>
+          <bodytube>
+            <name>Body tube</name>
+            <material type="bulk" density="680.0">Cardboard</material>
+            <length>0.5</length>
+            <thickness>0.0005</thickness>
+            <radius>0.0245</radius>
+            <subcomponents>
+              <trapezoidfinset>
+                <name>Fins</name>
+                <material type="bulk" density="680.0">Plywood</material>
+                <position type="bottom">0.0</position>
+                <fincount>3</fincount>
+                <rootchord>0.1</rootchord>
+                <tipchord>0.05</tipchord>
+                <height>0.06</height>
+                <sweeplength>0.03</sweeplength>
+                <thickness>0.003</thickness>
+              </trapezoidfinset>
+              <innertube>
+                <name>Motor tube</name>
+                <material type="bulk" density="680.0">Cardboard</material>
+                <position type="bottom">0.0</position>
+                <length>0.2</length>
+                <thickness>0.0005</thickness>
+                <outerradius>0.015</outerradius>
+                <motormount>
+                  <motor configid="b">
+                    <manufacturer>Cesaroni</manufacturer>
+                    <designation>G79</designation>
+                  </motor>
+                  <motor configid="a">
+                    <manufacturer>AeroTech</manufacturer>
+                    <designation>H128W</designation>
+                    <delay>10.0</delay>
+                  </motor>
+                </motormount>
+              </innertube>
+              <parachute>
+                <name>Parachute</name>
+                <material type="surface" density="0.05">Ripstop nylon</material>
+                <position type="top">0.05</position>
+                <diameter>0.6</diameter>
+                <cd>auto</cd>
+                <deployevent>apogee</deployevent>
+                <deploydelay>1.0</deploydelay>
+                <packedlength>0.05</packedlength>
+              </parachute>
+              <launchlug>
+                <name>Launch lug</name>
+              </launchlug>
+            </subcomponents>
+          </bodytube>
+        </subcomponents>
+      </stage>
+    </subcomponents>
+  </rocket>
+</openrocket>
+"#;
+
+    #[test]
+    fn imports_design() {
+        let import = parse(&unpack(DESIGN.as_bytes()).unwrap()).unwrap();
+        let rocket = &import.rocket;
+
+        assert_eq!(rocket.name, "Test rocket");
+        assert_eq!(rocket.components.len(), 2);
+        assert!((rocket.length() - 0.65).abs() < 1e-9);
+
+        // The nose takes its radius from the body tube behind it
+        match rocket.components[0].kind {
+            ComponentKind::NoseCone {
+                shape, diameter, ..
+            } => {
+                assert_eq!(shape, NoseShape::Ogive);
+                assert!((diameter - 0.049).abs() < 1e-9);
+            }
+            ref kind => panic!("expected a nose cone, found {:?}", kind),
+        }
+
+        let tube = &rocket.components[1];
+        let kinds: Vec<_> = tube.children.iter().map(|c| c.kind.type_name()).collect();
+        assert_eq!(kinds, ["Fin set", "Motor mount", "Parachute"]);
+
+        match tube.children[0].kind {
+            ComponentKind::FinSet {
+                count,
+                root_chord,
+                span,
+                ..
+            } => {
+                assert_eq!(count, 3);
+                assert_eq!(root_chord, 0.1);
+                assert_eq!(span, 0.06);
+            }
+            ref kind => panic!("expected a fin set, found {:?}", kind),
+        }
+        assert!((tube.children[0].position - 0.4).abs() < 1e-9);
+        assert!((tube.children[1].position - 0.3).abs() < 1e-9);
+
+        match tube.children[2].kind {
+            ComponentKind::Parachute {
+                drag_coefficient,
+                deployment,
+                ..
+            } => {
+                assert_eq!(drag_coefficient, AUTO_PARACHUTE_CD);
+                assert_eq!(deployment, Deployment::Apogee { delay: 1.0 });
+            }
+            ref kind => panic!("expected a parachute, found {:?}", kind),
+        }
+
+        // The motor of the default configuration is used
+        assert_eq!(
+            import.motor,
+            Some(MotorReference {
+                manufacturer: Some("AeroTech".into()),
+                designation: "H128W".into(),
+                delay: Some(10.0),
+            })
+        );
+
+        assert!(import
+            .log
+            .iter()
+            .any(|message| message.component == "Launch lug"
+                && message.level == ImportLevel::Warning));
+        assert!(rocket.validate().is_empty());
+    }
+
+    #[test]
+    fn masses_filled_nose_cones_as_solids() {
+        let design = DESIGN
+            .replace("<shape>ogive</shape>", "<shape>conical</shape>")
+            .replace(
+                "<thickness>0.002</thickness>",
+                "<thickness>0.002</thickness><filled>true</filled>",
+            );
+        let import = parse(&design).unwrap();
+        let nose = &import.rocket.components[0];
+        let radius = 0.0245;
+        let solid = 1050.0 * PI * radius * radius * 0.15 / 3.0;
+
+        assert!(import
+            .log
+            .iter()
+            .any(|message| message.component == "Nose cone"));
+        assert!((nose.kind.mass_properties(0.0).mass - solid).abs() < 0.01 * solid);
+    }
+
+    #[test]
+    fn unpacks_compressed_designs() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(DESIGN.as_bytes()).unwrap();
+        let gzip = encoder.finish().unwrap();
+
+        assert_eq!(unpack(&gzip).unwrap(), DESIGN);
+
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("rocket.ork", zip::write::FileOptions::default())
+            .unwrap();
+        archive.write_all(DESIGN.as_bytes()).unwrap();
+        let zip = archive.finish().unwrap().into_inner();
+
+        assert_eq!(unpack(&zip).unwrap(), DESIGN);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(matches!(parse("<rocksim/>"), Err(ImportError::Invalid(_))));
+        assert!(matches!(
+            parse("<openrocket/>"),
+            Err(ImportError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse("<openrocket><rocket></openrocket>"),
+            Err(ImportError::Xml(_))
+        ));
+        assert!(matches!(
+            unpack(&[0xff, 0xfe, 0x00]),
+            Err(ImportError::Invalid(_))
+        ));
+    }
+}
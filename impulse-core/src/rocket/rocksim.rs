@@ -0,0 +1,802 @@
+//! RockSim designs (`.rkt`)
+//!
+//! RockSim saves plain XML with lengths in millimetres, masses in grams and
+//! densities per cubic, square or linear millimetre depending on the
+//! material. The sustainer's parts are listed under `Stage3Parts`, with any
+//! boosters below it in `Stage2Parts` and `Stage1Parts`.
+
+use std::{f64::consts::PI, fs, path::Path};
+
+use roxmltree::{Document, Node};
+
+use super::{
+    import::{ring_mass, Import, ImportError, ImportLog, Trapezoid},
+    Component, ComponentKind, NoseShape, Rocket,
+};
+use crate::sim::Deployment;
+
+/// Stages in the order they are stacked, from the nose down
+const STAGES: [&str; 3] = ["Stage3Parts", "Stage2Parts", "Stage1Parts"];
+
+/// Drag coefficient of recovery devices that do not give one
+const DEFAULT_DRAG_COEFFICIENT: f64 = 0.75;
+
+/// Wall of the motor mount added to body tubes that hold a motor directly (m)
+const MOUNT_THICKNESS: f64 = 0.0005;
+
+pub(super) fn import(path: &Path) -> Result<Import, ImportError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+fn parse(xml: &str) -> Result<Import, ImportError> {
+    let document = Document::parse(xml)?;
+    let root = document.root_element();
+
+    if !root.has_tag_name("RockSimDocument") {
+        return Err(ImportError::Invalid("not a RockSim design".into()));
+    }
+
+    let design = child(root, "DesignInformation")
+        .and_then(|information| child(information, "RocketDesign"))
+        .ok_or_else(|| ImportError::Invalid("the design has no rocket in it".into()))?;
+    let name = text(design, "Name")
+        .unwrap_or("Imported rocket")
+        .to_string();
+
+    let mut importer = Importer {
+        log: ImportLog::default(),
+        mounts: 0,
+    };
+
+    let mut components = Vec::new();
+    let mut front = 0.0;
+
+    for (index, stage) in STAGES.iter().enumerate() {
+        let parts: Vec<_> = child(design, stage)
+            .into_iter()
+            .flat_map(|parts| parts.children())
+            .filter(Node::is_element)
+            .collect();
+
+        if index > 0 && !parts.is_empty() {
+            importer.log.warn(
+                &format!("Stage {}", STAGES.len() - index),
+                "stages are flattened into a single airframe, so separation is not simulated",
+            );
+        }
+
+        for node in parts {
+            if let Some(component) = importer.external(node, front) {
+                front += component.kind.length();
+                components.push(component);
+            }
+        }
+    }
+
+    let rocket = Rocket { name, components };
+
+    for error in rocket.validate() {
+        importer.log.warn(
+            &error.component,
+            format!("{}, which needs fixing in the editor", error.message),
+        );
+    }
+
+    Ok(Import {
+        rocket,
+        // RockSim keeps motors with its simulations rather than the design
+        motor: None,
+        log: importer.log.into_messages(),
+    })
+}
+
+/// Where an internal component is being placed
+struct Frame {
+    /// Distance of the parent's front from the nose tip (m)
+    front: f64,
+    length: f64,
+    /// Radius available inside the parent (m)
+    inner_radius: f64,
+}
+
+struct Importer {
+    log: ImportLog,
+    /// Motor mounts found so far
+    mounts: usize,
+}
+
+impl Importer {
+    fn external(&mut self, node: Node, front: f64) -> Option<Component> {
+        let name = component_name(node);
+        let length = millimetres(node, "Len");
+        let density = density(node);
+
+        let (kind, inner_radius) = match node.tag_name().name() {
+            "NoseCone" => {
+                let diameter = millimetres(node, "BaseDia");
+                let shape = match text(node, "ShapeCode").unwrap_or("1") {
+                    "0" => NoseShape::Conical,
+                    "1" => NoseShape::Ogive,
+                    "2" => NoseShape::Parabolic,
+                    "3" => NoseShape::Ellipsoid,
+                    _ => {
+                        self.log.warn(&name, "profile approximated as parabolic");
+
+                        NoseShape::Parabolic
+                    }
+                };
+                let thickness = self.wall(node, &name, diameter / 2.0);
+
+                (
+                    ComponentKind::NoseCone {
+                        shape,
+                        length,
+                        diameter,
+                        thickness,
+                        density,
+                    },
+                    diameter / 2.0 - thickness,
+                )
+            }
+            "BodyTube" => {
+                let diameter = millimetres(node, "OD");
+                let inner_diameter = millimetres(node, "ID");
+
+                (
+                    ComponentKind::BodyTube {
+                        length,
+                        diameter,
+                        thickness: (diameter - inner_diameter) / 2.0,
+                        density,
+                    },
+                    inner_diameter / 2.0,
+                )
+            }
+            "Transition" => {
+                let fore_diameter = millimetres(node, "FrontDia");
+                let aft_diameter = millimetres(node, "RearDia");
+
+                if text(node, "ShapeCode").is_some_and(|shape| shape != "0") {
+                    self.log.warn(&name, "profile approximated as conical");
+                }
+
+                let thickness = self.wall(node, &name, fore_diameter.max(aft_diameter) / 2.0);
+
+                (
+                    ComponentKind::Transition {
+                        length,
+                        fore_diameter,
+                        aft_diameter,
+                        thickness,
+                        density,
+                    },
+                    fore_diameter.max(aft_diameter) / 2.0 - thickness,
+                )
+            }
+            other => {
+                self.log.warn(
+                    &name,
+                    format!("{} parts are not supported and were left out", other),
+                );
+
+                return None;
+            }
+        };
+
+        let mut component = Component::new(name, kind);
+        self.known_mass(node, &mut component);
+
+        self.shoulder(node, "ShoulderLen", "ShoulderOD", length, &mut component);
+        self.shoulder(
+            node,
+            "FrontShoulderLen",
+            "FrontShoulderDia",
+            0.0,
+            &mut component,
+        );
+        self.shoulder(
+            node,
+            "RearShoulderLen",
+            "RearShoulderDia",
+            length,
+            &mut component,
+        );
+
+        let frame = Frame {
+            front,
+            length,
+            inner_radius,
+        };
+
+        if node.has_tag_name("BodyTube") && self.is_motor_mount(node) {
+            // RockSim lets the airframe itself hold the motor, which impulse
+            // models with a motor mount the length of the tube
+            self.log.warn(
+                &component.name,
+                "holds the motor directly, so a motor mount was added inside it",
+            );
+
+            component.children.push(Component::new(
+                "Motor mount",
+                ComponentKind::MotorMount {
+                    length,
+                    diameter: (inner_radius + MOUNT_THICKNESS) * 2.0,
+                    thickness: MOUNT_THICKNESS,
+                    density,
+                },
+            ));
+        }
+
+        for node in attached_parts(node) {
+            self.internal(node, &frame, &mut component.children);
+        }
+
+        self.log.info(&component.name, "imported");
+
+        Some(component)
+    }
+
+    /// Convert an internal part, flattening anything attached to inner tubes
+    /// into their parent
+    fn internal(&mut self, node: Node, parent: &Frame, out: &mut Vec<Component>) {
+        let name = component_name(node);
+        let length = millimetres(node, "Len");
+        let thickness = millimetres(node, "Thickness");
+        let density = density(node);
+
+        let kind = match node.tag_name().name() {
+            "FinSet" => {
+                let root_chord = millimetres(node, "RootChord");
+                let (tip_chord, sweep) = match text(node, "ShapeCode").unwrap_or("0") {
+                    "0" => (
+                        millimetres(node, "TipChord"),
+                        millimetres(node, "SweepDistance"),
+                    ),
+                    shape => {
+                        // An ellipse has the area of a trapezoid with this tip,
+                        // which is all that is left of any other outline
+                        let tip_chord = root_chord * (PI / 2.0 - 1.0);
+
+                        self.log.warn(
+                            &name,
+                            if shape == "1" {
+                                "elliptical fins approximated as trapezoidal"
+                            } else {
+                                "fin outline approximated as an ellipse"
+                            },
+                        );
+
+                        (tip_chord, (root_chord - tip_chord) / 2.0)
+                    }
+                };
+
+                ComponentKind::FinSet {
+                    count: fin_count(node),
+                    root_chord,
+                    tip_chord,
+                    span: millimetres(node, "SemiSpan"),
+                    sweep,
+                    thickness,
+                    cant: number(node, "CantAngle").unwrap_or(0.0),
+                    density,
+                }
+            }
+            "CustomFinSet" => {
+                let points: Vec<(f64, f64)> = text(node, "PointList")
+                    .unwrap_or_default()
+                    .split('|')
+                    .filter_map(|point| {
+                        let mut coordinates = point.split(',');
+
+                        Some((
+                            coordinates.next()?.trim().parse::<f64>().ok()? / 1000.0,
+                            coordinates.next()?.trim().parse::<f64>().ok()? / 1000.0,
+                        ))
+                    })
+                    .collect();
+
+                if points.len() < 3 {
+                    self.log
+                        .warn(&name, "the fin outline has too few points and was left out");
+
+                    return;
+                }
+
+                let outline = Trapezoid::from_points(&points);
+
+                self.log.warn(
+                    &name,
+                    format!(
+                        "custom fins approximated as trapezoidal from their {} point outline",
+                        points.len()
+                    ),
+                );
+
+                ComponentKind::FinSet {
+                    count: fin_count(node),
+                    root_chord: outline.root_chord,
+                    tip_chord: outline.tip_chord,
+                    span: outline.span,
+                    sweep: outline.sweep,
+                    thickness,
+                    cant: number(node, "CantAngle").unwrap_or(0.0),
+                    density,
+                }
+            }
+            "InnerTube" => {
+                let diameter = millimetres(node, "OD");
+                let inner_diameter = millimetres(node, "ID");
+                let position = self.position(node, length, parent);
+
+                let frame = Frame {
+                    front: parent.front + position,
+                    length,
+                    inner_radius: inner_diameter / 2.0,
+                };
+
+                let mut inside = Vec::new();
+
+                for node in attached_parts(node) {
+                    self.internal(node, &frame, &mut inside);
+                }
+
+                out.extend(inside.into_iter().map(|child| {
+                    let position = child.position + position;
+                    child.at(position)
+                }));
+
+                let kind = if self.is_motor_mount(node) {
+                    ComponentKind::MotorMount {
+                        length,
+                        diameter,
+                        thickness: (diameter - inner_diameter) / 2.0,
+                        density,
+                    }
+                } else {
+                    let mass = ring_mass(diameter / 2.0, inner_diameter / 2.0, length, density);
+
+                    self.log.info(&name, "inner tube imported as a mass item");
+
+                    ComponentKind::MassItem { mass, length }
+                };
+
+                return self.push(node, name, kind, position, out);
+            }
+            "Ring" => {
+                let outer_radius = match millimetres(node, "OD") {
+                    diameter if diameter > 0.0 => diameter / 2.0,
+                    _ => parent.inner_radius,
+                };
+                let mass = ring_mass(outer_radius, millimetres(node, "ID") / 2.0, length, density);
+
+                self.log.info(&name, "ring imported as a mass item");
+
+                ComponentKind::MassItem { mass, length }
+            }
+            "MassObject" => ComponentKind::MassItem {
+                mass: grams(node, "KnownMass"),
+                length,
+            },
+            "Parachute" => {
+                let diameter = millimetres(node, "Dia");
+                let spill_hole = millimetres(node, "SpillHoleDia");
+                let canopy = PI * (diameter * diameter - spill_hole * spill_hole) / 4.0;
+                let lines = number(node, "ShroudLineCount").unwrap_or(0.0)
+                    * number(node, "ShroudLineLen").unwrap_or(0.0)
+                    * number(node, "ShroudLineMassPerMM").unwrap_or(0.0)
+                    / 1000.0;
+
+                ComponentKind::Parachute {
+                    diameter,
+                    drag_coefficient: self.drag_coefficient(node, &name),
+                    mass: canopy * density + lines,
+                    deployment: self.deployment(&name),
+                }
+            }
+            "Streamer" => {
+                let area = millimetres(node, "Len") * millimetres(node, "Width");
+
+                self.log.warn(
+                    &name,
+                    "streamer approximated as a parachute with the same area",
+                );
+
+                ComponentKind::Parachute {
+                    diameter: (4.0 * area / PI).sqrt(),
+                    drag_coefficient: self.drag_coefficient(node, &name),
+                    mass: area * density,
+                    deployment: self.deployment(&name),
+                }
+            }
+            "LaunchLug" | "RailButton" => {
+                self.log.warn(
+                    &name,
+                    "launch guides are not modelled, so their drag is ignored",
+                );
+
+                return;
+            }
+            other => {
+                self.log.warn(
+                    &name,
+                    format!("{} parts are not supported and were left out", other),
+                );
+
+                return;
+            }
+        };
+
+        let position = self.position(node, kind.length(), parent);
+
+        self.push(node, name, kind, position, out);
+    }
+
+    fn push(
+        &mut self,
+        node: Node,
+        name: String,
+        kind: ComponentKind,
+        position: f64,
+        out: &mut Vec<Component>,
+    ) {
+        let mut component = Component::new(name, kind).at(position);
+        self.known_mass(node, &mut component);
+        self.log.info(&component.name, "imported");
+
+        out.push(component);
+    }
+
+    /// Add a shoulder's mass at one end of an external component
+    fn shoulder(
+        &mut self,
+        node: Node,
+        length_tag: &str,
+        diameter_tag: &str,
+        position: f64,
+        component: &mut Component,
+    ) {
+        let length = millimetres(node, length_tag);
+        let radius = millimetres(node, diameter_tag) / 2.0;
+
+        if length <= 0.0 || radius <= 0.0 {
+            return;
+        }
+
+        let thickness = millimetres(node, "WallThickness").min(radius);
+        let mass = ring_mass(radius, radius - thickness, length, density(node));
+
+        self.log.info(
+            &component.name,
+            if position > 0.0 {
+                "shoulder added as a mass item at its aft end"
+            } else {
+                "shoulder added as a mass item at its front end"
+            },
+        );
+
+        component.children.push(
+            Component::new(
+                format!("{} shoulder", component.name),
+                ComponentKind::MassItem { mass, length: 0.0 },
+            )
+            .at(position),
+        );
+    }
+
+    /// Wall thickness of a shell, treating a solid one as filled
+    fn wall(&mut self, node: Node, name: &str, radius: f64) -> f64 {
+        if text(node, "ConstructionType") == Some("0") {
+            self.log
+                .info(name, "solid, so its wall thickness was set to its radius");
+
+            radius
+        } else {
+            millimetres(node, "WallThickness")
+        }
+    }
+
+    /// Whether a tube holds the motor, allowing only a single one
+    fn is_motor_mount(&mut self, node: Node) -> bool {
+        if text(node, "IsMotorMount") != Some("1") {
+            return false;
+        }
+
+        self.mounts += 1;
+
+        if self.mounts > 1 {
+            self.log.warn(
+                &component_name(node),
+                "only a single motor mount is supported, so this one is imported as a tube",
+            );
+
+            return false;
+        }
+
+        true
+    }
+
+    /// Distance from the front of the parent to the front of a part (m)
+    fn position(&mut self, node: Node, length: f64, parent: &Frame) -> f64 {
+        let offset = millimetres(node, "Xb");
+
+        let position = match text(node, "LocationMode").unwrap_or("0") {
+            "0" => offset,
+            "1" => offset - parent.front,
+            "2" => parent.length - length + offset,
+            other => {
+                self.log.warn(
+                    &component_name(node),
+                    format!("unknown location mode {}, placed from the top", other),
+                );
+
+                offset
+            }
+        };
+
+        let clamped = position.max(0.0).min((parent.length - length).max(0.0));
+
+        if (clamped - position).abs() > 1e-6 {
+            self.log.warn(
+                &component_name(node),
+                "sticks out of its parent, so it was moved inside",
+            );
+        }
+
+        clamped
+    }
+
+    fn drag_coefficient(&mut self, node: Node, name: &str) -> f64 {
+        match number(node, "DragCoefficient").filter(|&cd| cd > 0.0) {
+            Some(cd) => cd,
+            None => {
+                self.log.warn(
+                    name,
+                    format!(
+                        "no drag coefficient given, so {} was used",
+                        DEFAULT_DRAG_COEFFICIENT
+                    ),
+                );
+
+                DEFAULT_DRAG_COEFFICIENT
+            }
+        }
+    }
+
+    /// RockSim opens recovery devices at motor ejection, which impulse does
+    /// not model
+    fn deployment(&mut self, name: &str) -> Deployment {
+        self.log.warn(
+            name,
+            "deploys at motor ejection, which was replaced with apogee",
+        );
+
+        Deployment::Apogee { delay: 0.0 }
+    }
+
+    /// Use the mass entered for a part in RockSim instead of the computed one
+    fn known_mass(&mut self, node: Node, component: &mut Component) {
+        if let ComponentKind::MassItem { .. } = component.kind {
+            if node.has_tag_name("MassObject") {
+                return;
+            }
+        }
+
+        let mass = grams(node, "KnownMass");
+
+        if mass > 0.0 {
+            self.log.set_mass(component, mass);
+        }
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(tag))
+}
+
+fn text<'a>(node: Node<'a, '_>, tag: &str) -> Option<&'a str> {
+    child(node, tag)?.text().map(str::trim)
+}
+
+fn number(node: Node, tag: &str) -> Option<f64> {
+    text(node, tag)?.parse().ok()
+}
+
+/// A length given in millimetres, in metres
+fn millimetres(node: Node, tag: &str) -> f64 {
+    number(node, tag).unwrap_or(0.0) / 1000.0
+}
+
+/// A mass given in grams, in kilograms
+fn grams(node: Node, tag: &str) -> f64 {
+    number(node, tag).unwrap_or(0.0) / 1000.0
+}
+
+/// Density of a part's material in SI units, converted from grams per cubic,
+/// square or linear millimetre depending on its `DensityType`
+fn density(node: Node) -> f64 {
+    let density = number(node, "Density").unwrap_or(0.0);
+
+    match text(node, "DensityType").unwrap_or("0") {
+        "1" => density * 1e3,
+        "2" => density,
+        _ => density * 1e6,
+    }
+}
+
+fn attached_parts<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    child(node, "AttachedParts")
+        .into_iter()
+        .flat_map(|parts| parts.children())
+        .filter(Node::is_element)
+}
+
+fn component_name(node: Node) -> String {
+    text(node, "Name")
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| node.tag_name().name())
+        .to_string()
+}
+
+fn fin_count(node: Node) -> u32 {
+    text(node, "FinCount")
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rocket::import::ImportLevel;
+
+    const DESIGN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<RockSimDocument>
+  <FileVersion>4</FileVersion>
+  <DesignInformation>
+    <RocketDesign>
+      <Name>Test rocket</Name>
+      <Stage3Parts>
+        <NoseCone>
+          <Name>Nose cone</Name>
+          <Density>0.00105</Density>
+          <DensityType>0</DensityType>
+          <Len>150.</Len>
+          <BaseDia>49.</BaseDia>
+          <ShapeCode>0</ShapeCode>
+          <ConstructionType>1</ConstructionType>
+          <WallThickness>2.</WallThickness>
+        </NoseCone>
+        <BodyTube>
+          <Name>Body tube</Name>
+          <Density>0.00068</Density>
+          <DensityType>0</DensityType>
+          <Len>500.</Len>
+          <OD>49.</OD>
+          <ID>48.</ID>
+          <IsMotorMount>1</IsMotorMount>
+          <KnownMass>80.</KnownMass>
+          <AttachedParts>
+            <FinSet>
+              <Name>Fins</Name>
+              <Density>0.00068</Density>
+              <DensityType>0</DensityType>
+              <LocationMode>2</LocationMode>
+              <Xb>0.</Xb>
+              <FinCount>4</FinCount>
+              <RootChord>100.</RootChord>
+              <TipChord>50.</TipChord>
+              <SemiSpan>60.</SemiSpan>
+              <SweepDistance>30.</SweepDistance>
+              <Thickness>3.</Thickness>
+              <ShapeCode>0</ShapeCode>
+            </FinSet>
+            <MassObject>
+              <Name>Altimeter</Name>
+              <LocationMode>0</LocationMode>
+              <Xb>20.</Xb>
+              <Len>40.</Len>
+              <KnownMass>25.</KnownMass>
+            </MassObject>
+            <Streamer>
+              <Name>Streamer</Name>
+              <Density>0.00002</Density>
+              <DensityType>1</DensityType>
+              <Len>1000.</Len>
+              <Width>100.</Width>
+              <DragCoefficient>0.</DragCoefficient>
+            </Streamer>
+          </AttachedParts>
+        </BodyTube>
+      </Stage3Parts>
+      <Stage2Parts/>
+      <Stage1Parts/>
+    </RocketDesign>
+  </DesignInformation>
+</RockSimDocument>
+"#;
+
+    #[test]
+    fn imports_design() {
+        let import = parse(DESIGN).unwrap();
+        let rocket = &import.rocket;
+
+        assert_eq!(rocket.name, "Test rocket");
+        assert!(import.motor.is_none());
+        assert!((rocket.length() - 0.65).abs() < 1e-9);
+
+        match rocket.components[0].kind {
+            ComponentKind::NoseCone {
+                shape,
+                diameter,
+                thickness,
+                ..
+            } => {
+                assert_eq!(shape, NoseShape::Conical);
+                assert!((diameter - 0.049).abs() < 1e-9);
+                assert!((thickness - 0.002).abs() < 1e-9);
+            }
+            ref kind => panic!("expected a nose cone, found {:?}", kind),
+        }
+
+        // The tube holds the motor itself and keeps its known mass
+        let tube = &rocket.components[1];
+        assert!((tube.kind.mass_properties(0.0).mass - 0.08).abs() < 1e-9);
+
+        let kinds: Vec<_> = tube.children.iter().map(|c| c.kind.type_name()).collect();
+        assert_eq!(kinds, ["Motor mount", "Fin set", "Mass item", "Parachute"]);
+
+        match tube.children[1].kind {
+            ComponentKind::FinSet {
+                count,
+                root_chord,
+                span,
+                sweep,
+                ..
+            } => {
+                assert_eq!(count, 4);
+                assert!((root_chord - 0.1).abs() < 1e-9);
+                assert!((span - 0.06).abs() < 1e-9);
+                assert!((sweep - 0.03).abs() < 1e-9);
+            }
+            ref kind => panic!("expected a fin set, found {:?}", kind),
+        }
+        assert!((tube.children[1].position - 0.4).abs() < 1e-9);
+        assert!((tube.children[2].position - 0.02).abs() < 1e-9);
+        assert!((tube.children[2].kind.mass_properties(0.0).mass - 0.025).abs() < 1e-9);
+
+        // The streamer has the area of its strip and the default drag coefficient
+        match tube.children[3].kind {
+            ComponentKind::Parachute {
+                diameter,
+                drag_coefficient,
+                ..
+            } => {
+                assert!((PI * diameter * diameter / 4.0 - 0.1).abs() < 1e-9);
+                assert_eq!(drag_coefficient, DEFAULT_DRAG_COEFFICIENT);
+            }
+            ref kind => panic!("expected a parachute, found {:?}", kind),
+        }
+
+        assert!(import.log.iter().any(
+            |message| message.component == "Streamer" && message.level == ImportLevel::Warning
+        ));
+        assert!(rocket.validate().is_empty());
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(matches!(
+            parse("<openrocket/>"),
+            Err(ImportError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse("<RockSimDocument/>"),
+            Err(ImportError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse("<RockSimDocument></DesignInformation>"),
+            Err(ImportError::Xml(_))
+        ));
+    }
+}
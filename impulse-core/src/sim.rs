@@ -0,0 +1,41 @@
+//! Flight simulation of a lumped vehicle, from ignition to landing
+
+pub use self::{
+    atmosphere::{standard_atmosphere, AirProperties, STANDARD_GRAVITY},
+    dispersion::{
+        DispersionResults, DispersionRun, DispersionSettings, Distribution, Ellipse, MonteCarlo,
+        Statistics,
+    },
+    export::{
+        export_trajectory, write_trajectory, Column, ExportError, ExportFormat, ExportOptions,
+        FlightRecord, Sampling,
+    },
+    geodesy::Geodetic,
+    motor::{impulse_class, parse_delays, Motor, MotorFileError},
+    range::{FieldLimits, RangeCheck},
+    simulation::{
+        FlightEvent, FlightEventKind, FlightPhase, FlightSample, FlightUpdate, LaunchConditions,
+        SettingsError, Simulation, SimulationConfig, SimulationSettings, Updates,
+    },
+    summary::{DeploymentSummary, EjectionDelay, FlightSummary, SAFE_DELAY_WINDOW},
+    sweep::{
+        Axis, Baseline, Goal, Metric, Optimizer, Optimum, Sweep, SweepParameter, SweepResults,
+    },
+    telemetry::{
+        TelemetryError, TelemetryFrame, TelemetryPublisher, TelemetryReader, TelemetrySample,
+        DEFAULT_TELEMETRY_ADDRESS, TELEMETRY_VERSION,
+    },
+    vehicle::{Deployment, RecoveryDevice, Vehicle},
+};
+
+mod atmosphere;
+mod dispersion;
+mod export;
+mod geodesy;
+mod motor;
+mod range;
+mod simulation;
+mod summary;
+mod sweep;
+mod telemetry;
+mod vehicle;
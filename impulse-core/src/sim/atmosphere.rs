@@ -0,0 +1,62 @@
+//! International Standard Atmosphere model used to derive the air properties
+//! the vehicle flies through
+
+/// Standard gravitational acceleration (m/s²)
+pub const STANDARD_GRAVITY: f64 = 9.806_65;
+
+const SEA_LEVEL_TEMPERATURE: f64 = 288.15;
+const SEA_LEVEL_PRESSURE: f64 = 101_325.0;
+const TROPOSPHERE_LAPSE_RATE: f64 = 0.0065;
+const TROPOPAUSE_ALTITUDE: f64 = 11_000.0;
+const GAS_CONSTANT: f64 = 287.052_87;
+const HEAT_CAPACITY_RATIO: f64 = 1.4;
+
+/// Properties of the air at a given altitude
+#[derive(Debug, Clone, Copy)]
+pub struct AirProperties {
+    /// Temperature (K)
+    pub temperature: f64,
+    /// Static pressure (Pa)
+    pub pressure: f64,
+    /// Density (kg/m³)
+    pub density: f64,
+    /// Speed of sound (m/s)
+    pub speed_of_sound: f64,
+}
+
+/// Air properties at a geopotential altitude above mean sea level (m), valid up
+/// to the top of the lower stratosphere (20 km)
+pub fn standard_atmosphere(altitude: f64) -> AirProperties {
+    let altitude = altitude.max(0.0);
+
+    let (temperature, pressure) = if altitude <= TROPOPAUSE_ALTITUDE {
+        let temperature = SEA_LEVEL_TEMPERATURE - TROPOSPHERE_LAPSE_RATE * altitude;
+
+        (
+            temperature,
+            SEA_LEVEL_PRESSURE
+                * (temperature / SEA_LEVEL_TEMPERATURE)
+                    .powf(STANDARD_GRAVITY / (GAS_CONSTANT * TROPOSPHERE_LAPSE_RATE)),
+        )
+    } else {
+        let temperature = SEA_LEVEL_TEMPERATURE - TROPOSPHERE_LAPSE_RATE * TROPOPAUSE_ALTITUDE;
+        let tropopause_pressure = SEA_LEVEL_PRESSURE
+            * (temperature / SEA_LEVEL_TEMPERATURE)
+                .powf(STANDARD_GRAVITY / (GAS_CONSTANT * TROPOSPHERE_LAPSE_RATE));
+
+        (
+            temperature,
+            tropopause_pressure
+                * (-STANDARD_GRAVITY * (altitude - TROPOPAUSE_ALTITUDE)
+                    / (GAS_CONSTANT * temperature))
+                    .exp(),
+        )
+    };
+
+    AirProperties {
+        temperature,
+        pressure,
+        density: pressure / (GAS_CONSTANT * temperature),
+        speed_of_sound: (HEAT_CAPACITY_RATIO * GAS_CONSTANT * temperature).sqrt(),
+    }
+}
@@ -0,0 +1,486 @@
+//! Monte Carlo dispersion analysis, flying many copies of a flight with
+//! randomly perturbed parameters to see how far apogee and the landing point
+//! can stray from the nominal flight
+//!
+//! Each flight draws its perturbations from its own random number stream,
+//! derived from the seed and the index of the flight, so an analysis gives the
+//! same results however the flights are spread across threads.
+
+use cgmath::{InnerSpace, Matrix2, Vector2, Vector3};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    simulation::{FlightEventKind, SettingsError, Simulation, SimulationConfig},
+    vehicle::Deployment,
+};
+
+/// Smallest scale a parameter is perturbed by, so that however wide the
+/// distribution, no flight loses all of its mass or thrust
+const MIN_SCALE: f64 = 0.01;
+
+/// How a parameter varies between flights, relative to its nominal value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Distribution {
+    /// Always the nominal value
+    Fixed,
+    /// Normally distributed with the given standard deviation
+    Normal(f64),
+    /// Uniformly distributed up to the given distance either side
+    Uniform(f64),
+}
+
+impl Distribution {
+    /// Draw a deviation from the nominal value
+    fn sample(self, rng: &mut impl Rng) -> f64 {
+        match self {
+            Distribution::Fixed => 0.0,
+            Distribution::Normal(std_dev) => std_dev * rng.sample::<f64, _>(StandardNormal),
+            Distribution::Uniform(half_width) if half_width > 0.0 => {
+                rng.gen_range(-half_width..=half_width)
+            }
+            Distribution::Uniform(_) => 0.0,
+        }
+    }
+}
+
+/// The parameters of a dispersion analysis. Scales are relative, so a
+/// deviation of 0.05 is 5%, while the rest are absolute in SI units.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DispersionSettings {
+    /// Number of flights to simulate
+    pub runs: usize,
+    /// Seed of the random number generator, so that analyses can be repeated
+    pub seed: u64,
+    /// Scale of the motor thrust, and so its total impulse
+    pub thrust_scale: Distribution,
+    /// Scale of the dry mass
+    pub mass_scale: Distribution,
+    /// Scale of the drag coefficient
+    pub drag_scale: Distribution,
+    /// Wind speed (m/s)
+    pub wind_speed: Distribution,
+    /// Direction the wind blows from (rad)
+    pub wind_direction: Distribution,
+    /// Angle of the launch rail from vertical (rad)
+    pub rail_angle: Distribution,
+    /// Direction the launch rail is tilted towards (rad)
+    pub rail_heading: Distribution,
+    /// Delay of recovery devices deployed after apogee (s)
+    pub deploy_delay: Distribution,
+    /// Fin incidence, which is zero for the nominal flight (rad)
+    pub fin_misalignment: Distribution,
+}
+
+impl Default for DispersionSettings {
+    fn default() -> Self {
+        Self {
+            runs: 200,
+            seed: 1,
+            thrust_scale: Distribution::Normal(0.03),
+            mass_scale: Distribution::Normal(0.02),
+            drag_scale: Distribution::Normal(0.05),
+            wind_speed: Distribution::Normal(1.0),
+            wind_direction: Distribution::Normal(15f64.to_radians()),
+            rail_angle: Distribution::Normal(1f64.to_radians()),
+            rail_heading: Distribution::Normal(5f64.to_radians()),
+            deploy_delay: Distribution::Normal(0.5),
+            fin_misalignment: Distribution::Normal(0.25f64.to_radians()),
+        }
+    }
+}
+
+/// Outcome of one of the flights of a dispersion analysis
+#[derive(Debug, Clone, Copy)]
+pub struct DispersionRun {
+    /// Highest altitude above the launch site (m)
+    pub apogee: f64,
+    /// Where the flight came down, or where it was when the simulation time
+    /// limit was reached (m)
+    pub landing: Vector3<f64>,
+    /// Time from ignition to landing (s)
+    pub flight_time: f64,
+    /// Whether the flight landed before the simulation time limit
+    pub landed: bool,
+}
+
+/// Mean, spread and extremes of a set of values
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Statistics {
+    pub mean: f64,
+    /// Sample standard deviation
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Statistics {
+    pub fn of(values: impl Iterator<Item = f64> + Clone) -> Self {
+        let count = values.clone().count() as f64;
+
+        if count == 0.0 {
+            return Self::default();
+        }
+
+        let mean = values.clone().sum::<f64>() / count;
+        let variance = if count > 1.0 {
+            values
+                .clone()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / (count - 1.0)
+        } else {
+            0.0
+        };
+
+        Self {
+            mean,
+            std_dev: variance.sqrt(),
+            min: values.clone().fold(f64::INFINITY, f64::min),
+            max: values.fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// An ellipse on the ground, in the east-north frame of the launch site
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipse {
+    /// East and north position of the center (m)
+    pub center: Vector2<f64>,
+    pub semi_major: f64,
+    pub semi_minor: f64,
+    /// Compass direction of the major axis, clockwise from north (rad)
+    pub bearing: f64,
+}
+
+impl Ellipse {
+    /// Points around the edge of the ellipse, starting and ending at the same
+    /// end of the major axis
+    pub fn outline(&self, segments: usize) -> impl Iterator<Item = Vector2<f64>> + '_ {
+        let major = Vector2::new(self.bearing.sin(), self.bearing.cos());
+        let minor = Vector2::new(major.y, -major.x);
+
+        (0..=segments).map(move |index| {
+            let angle = index as f64 / segments as f64 * 2.0 * std::f64::consts::PI;
+
+            self.center
+                + major * (self.semi_major * angle.cos())
+                + minor * (self.semi_minor * angle.sin())
+        })
+    }
+}
+
+/// Results of a dispersion analysis
+#[derive(Debug, Clone)]
+pub struct DispersionResults {
+    pub runs: Vec<DispersionRun>,
+    pub apogee: Statistics,
+    pub flight_time: Statistics,
+    /// Horizontal distance from the launch rail to the landing point (m)
+    pub landing_distance: Statistics,
+    /// Mean east and north position of the landing points (m)
+    pub landing_mean: Vector2<f64>,
+    /// Covariance of the east and north position of the landing points (m²)
+    pub landing_covariance: Matrix2<f64>,
+}
+
+impl DispersionResults {
+    pub fn new(runs: Vec<DispersionRun>) -> Self {
+        let landings = runs.iter().map(|run| run.landing.truncate());
+        let count = runs.len() as f64;
+
+        let landing_mean = if runs.is_empty() {
+            Vector2::new(0.0, 0.0)
+        } else {
+            landings
+                .clone()
+                .fold(Vector2::new(0.0, 0.0), |sum, point| sum + point)
+                / count
+        };
+
+        let landing_covariance = if runs.len() > 1 {
+            let sum = landings.fold(Matrix2::new(0.0, 0.0, 0.0, 0.0), |sum, point| {
+                let offset = point - landing_mean;
+
+                sum + Matrix2::new(
+                    offset.x * offset.x,
+                    offset.x * offset.y,
+                    offset.x * offset.y,
+                    offset.y * offset.y,
+                )
+            });
+
+            sum / (count - 1.0)
+        } else {
+            Matrix2::new(0.0, 0.0, 0.0, 0.0)
+        };
+
+        Self {
+            apogee: Statistics::of(runs.iter().map(|run| run.apogee)),
+            flight_time: Statistics::of(runs.iter().map(|run| run.flight_time)),
+            landing_distance: Statistics::of(
+                runs.iter().map(|run| run.landing.truncate().magnitude()),
+            ),
+            landing_mean,
+            landing_covariance,
+            runs,
+        }
+    }
+
+    /// Ellipse around the landing points at a number of standard deviations,
+    /// treating them as normally distributed
+    pub fn landing_ellipse(&self, sigmas: f64) -> Ellipse {
+        let covariance = self.landing_covariance;
+        let (xx, xy, yy) = (covariance.x.x, covariance.x.y, covariance.y.y);
+
+        // Eigenvalues of the symmetric covariance matrix are the variances
+        // along the principal axes
+        let mean = (xx + yy) / 2.0;
+        let spread = (((xx - yy) / 2.0).powi(2) + xy * xy).sqrt();
+        let major = (mean + spread).max(0.0);
+        let minor = (mean - spread).max(0.0);
+
+        // Angle of the major axis from east, counterclockwise
+        let angle = 0.5 * (2.0 * xy).atan2(xx - yy);
+
+        Ellipse {
+            center: self.landing_mean,
+            semi_major: sigmas * major.sqrt(),
+            semi_minor: sigmas * minor.sqrt(),
+            bearing: (std::f64::consts::FRAC_PI_2 - angle).rem_euclid(std::f64::consts::PI),
+        }
+    }
+
+    /// Key statistics as labels and formatted values, in the order they are
+    /// shown
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let statistics = |statistics: Statistics, unit: &str| {
+            format!(
+                "{:.1} ± {:.1} {} ({:.1} to {:.1})",
+                statistics.mean, statistics.std_dev, unit, statistics.min, statistics.max
+            )
+        };
+
+        let landed = self.runs.iter().filter(|run| run.landed).count();
+        let mut rows = vec![
+            (
+                "Flights landed".to_string(),
+                format!("{} of {}", landed, self.runs.len()),
+            ),
+            ("Apogee".into(), statistics(self.apogee, "m")),
+            ("Flight time".into(), statistics(self.flight_time, "s")),
+            (
+                "Landing distance".into(),
+                statistics(self.landing_distance, "m"),
+            ),
+            (
+                "Mean landing point".into(),
+                format!(
+                    "{:.0} m east, {:.0} m north",
+                    self.landing_mean.x, self.landing_mean.y
+                ),
+            ),
+        ];
+
+        rows.extend((1..=3).map(|sigmas| {
+            let ellipse = self.landing_ellipse(sigmas as f64);
+
+            (
+                format!("{}σ landing ellipse", sigmas),
+                format!(
+                    "{:.0} × {:.0} m along {:.0}°",
+                    2.0 * ellipse.semi_major,
+                    2.0 * ellipse.semi_minor,
+                    ellipse.bearing.to_degrees()
+                ),
+            )
+        }));
+
+        rows
+    }
+
+    /// Landing points of every flight, for placing on a map
+    pub fn landing_points(&self) -> Vec<Vector3<f64>> {
+        self.runs.iter().map(|run| run.landing).collect()
+    }
+}
+
+/// A dispersion analysis in progress, simulating its flights in batches so
+/// that the caller can report progress and stop early
+pub struct MonteCarlo {
+    config: SimulationConfig,
+    settings: DispersionSettings,
+    runs: Vec<DispersionRun>,
+}
+
+impl MonteCarlo {
+    /// Analysis of the flights of a configuration, which fails if its
+    /// settings would never let a flight finish
+    pub fn new(
+        config: SimulationConfig,
+        settings: DispersionSettings,
+    ) -> Result<Self, SettingsError> {
+        config.settings.validate()?;
+
+        Ok(Self {
+            config,
+            settings,
+            runs: Vec::with_capacity(settings.runs),
+        })
+    }
+
+    /// Number of flights simulated so far
+    pub fn completed(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Number of flights in the analysis
+    pub fn total(&self) -> usize {
+        self.settings.runs
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed() >= self.total()
+    }
+
+    /// Simulate up to `count` more flights in parallel
+    pub fn run_batch(&mut self, count: usize) {
+        let start = self.completed();
+        let end = (start + count).min(self.total());
+
+        let batch: Vec<_> = (start..end)
+            .into_par_iter()
+            .map(|index| self.fly(index))
+            .collect();
+
+        self.runs.extend(batch);
+    }
+
+    /// Simulate every remaining flight
+    pub fn run_to_completion(mut self) -> DispersionResults {
+        self.run_batch(self.total());
+
+        self.results()
+    }
+
+    /// Results of the flights simulated so far
+    pub fn results(&self) -> DispersionResults {
+        DispersionResults::new(self.runs.clone())
+    }
+
+    /// Configuration of the flight with the given index, for simulating it in
+    /// full rather than only recording where it went
+    pub fn run_config(&self, index: usize) -> SimulationConfig {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.settings.seed);
+        rng.set_stream(index as u64);
+
+        self.perturb(&mut rng)
+    }
+
+    /// Simulate the flight with the given index
+    fn fly(&self, index: usize) -> DispersionRun {
+        let mut simulation =
+            Simulation::new(self.run_config(index)).expect("settings are checked by new");
+        let mut apogee: f64 = 0.0;
+        let mut sample = simulation.sample();
+
+        while !simulation.is_complete() {
+            sample = simulation.step();
+            apogee = apogee.max(sample.altitude());
+        }
+
+        DispersionRun {
+            apogee,
+            landing: sample.position,
+            flight_time: sample.time,
+            landed: simulation
+                .events()
+                .iter()
+                .any(|event| event.kind == FlightEventKind::Landing),
+        }
+    }
+
+    /// Configuration of the nominal flight with every parameter perturbed
+    fn perturb(&self, rng: &mut impl Rng) -> SimulationConfig {
+        let settings = &self.settings;
+        let mut config = self.config.clone();
+
+        let scale = |distribution: Distribution, rng: &mut _| {
+            (1.0 + distribution.sample(rng)).max(MIN_SCALE)
+        };
+
+        let vehicle = &mut config.vehicle;
+
+        if let Some((motor, _)) = &mut vehicle.motor {
+            let thrust_scale = scale(settings.thrust_scale, rng);
+
+            for (_, thrust) in &mut motor.thrust_curve {
+                *thrust *= thrust_scale;
+            }
+        }
+
+        let mass_scale = scale(settings.mass_scale, rng);
+        vehicle.dry_mass *= mass_scale;
+        vehicle.dry_longitudinal_inertia *= mass_scale;
+        vehicle.dry_roll_inertia *= mass_scale;
+
+        vehicle.drag_coefficient *= scale(settings.drag_scale, rng);
+        vehicle.fin_misalignment += settings.fin_misalignment.sample(rng);
+
+        for device in &mut vehicle.recovery {
+            if let Deployment::Apogee { delay } = &mut device.deployment {
+                *delay = (*delay + settings.deploy_delay.sample(rng)).max(0.0);
+            }
+        }
+
+        let launch = &mut config.launch;
+        launch.wind_speed = (launch.wind_speed + settings.wind_speed.sample(rng)).max(0.0);
+        launch.wind_direction += settings.wind_direction.sample(rng);
+        launch.rail_angle = (launch.rail_angle + settings.rail_angle.sample(rng)).abs();
+        launch.rail_heading += settings.rail_heading.sample(rng);
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+
+    #[test]
+    fn scales_stay_positive() {
+        let config = Project::default().config();
+        let settings = DispersionSettings {
+            thrust_scale: Distribution::Uniform(5.0),
+            mass_scale: Distribution::Normal(10.0),
+            drag_scale: Distribution::Uniform(5.0),
+            ..DispersionSettings::default()
+        };
+        let analysis = MonteCarlo::new(config, settings).unwrap();
+
+        for index in 0..settings.runs {
+            let vehicle = analysis.run_config(index).vehicle;
+            let (motor, _) = vehicle.motor.as_ref().unwrap();
+
+            assert!(vehicle.dry_mass > 0.0);
+            assert!(vehicle.drag_coefficient > 0.0);
+            assert!(motor.total_impulse() > 0.0);
+        }
+    }
+
+    #[test]
+    fn runs_are_repeatable() {
+        let analysis =
+            MonteCarlo::new(Project::default().config(), DispersionSettings::default()).unwrap();
+        let first = analysis.run_config(7);
+        let second = analysis.run_config(7);
+
+        assert_eq!(first.vehicle.dry_mass, second.vehicle.dry_mass);
+        assert_eq!(first.launch, second.launch);
+        assert_ne!(first.launch, analysis.run_config(8).launch);
+    }
+}
@@ -0,0 +1,601 @@
+//! Trajectory export for post-processing flights in other tools
+//!
+//! Trajectories are written either as CSV, with a header naming each column
+//! and its unit, or as JSON holding the column names and units, the samples
+//! as rows of values and the flight events. The flight path can also be
+//! placed on a map as KML or GeoJSON, using the launch site's location.
+
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use cgmath::{InnerSpace, Quaternion, Vector3};
+use serde::Serialize;
+use thiserror::Error;
+
+use super::simulation::{FlightEvent, FlightEventKind, FlightSample, LaunchConditions, Simulation};
+
+mod map;
+
+/// Most samples a trajectory is resampled to, which is far more than any
+/// tool needs and keeps a mistyped rate from filling the disk
+const MAX_RESAMPLED: usize = 10_000_000;
+
+/// Every sample and event recorded during a flight
+#[derive(Debug, Clone, Default)]
+pub struct FlightRecord {
+    /// Samples after each integrator step, in chronological order
+    pub samples: Vec<FlightSample>,
+    pub events: Vec<FlightEvent>,
+    /// Names of the recovery devices, indexed as in [`FlightEventKind::Deployment`]
+    pub recovery: Vec<String>,
+    pub launch: LaunchConditions,
+}
+
+impl FlightRecord {
+    /// Record of a simulated flight from every sample taken during it
+    pub fn new(simulation: &Simulation, samples: Vec<FlightSample>) -> Self {
+        let config = simulation.config();
+
+        Self {
+            samples,
+            events: simulation.events().to_vec(),
+            recovery: config
+                .vehicle
+                .recovery
+                .iter()
+                .map(|device| device.name.clone())
+                .collect(),
+            launch: config.launch,
+        }
+    }
+
+    /// State of the vehicle at a time since ignition, interpolated between
+    /// integrator steps and held at the ends of the flight
+    pub fn sample_at(&self, time: f64) -> Option<FlightSample> {
+        let index = self.samples.partition_point(|sample| sample.time < time);
+
+        match (index.checked_sub(1), self.samples.get(index)) {
+            (Some(before), Some(after)) => Some(interpolate(&self.samples[before], after, time)),
+            (None, Some(first)) => Some(*first),
+            (_, None) => self.samples.last().copied(),
+        }
+    }
+}
+
+/// A group of related values that can be included in an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Time,
+    Position,
+    Velocity,
+    Acceleration,
+    Attitude,
+    Mach,
+    DragCoefficient,
+    Thrust,
+    Mass,
+    Cg,
+    Cp,
+    Stability,
+}
+
+impl Column {
+    pub const ALL: [Column; 12] = [
+        Column::Time,
+        Column::Position,
+        Column::Velocity,
+        Column::Acceleration,
+        Column::Attitude,
+        Column::Mach,
+        Column::DragCoefficient,
+        Column::Thrust,
+        Column::Mass,
+        Column::Cg,
+        Column::Cp,
+        Column::Stability,
+    ];
+
+    /// Names and units of the values making up the column, in the order they
+    /// are written
+    pub fn fields(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Column::Time => &[("time", "s")],
+            Column::Position => &[("east", "m"), ("north", "m"), ("up", "m")],
+            Column::Velocity => &[
+                ("velocity_east", "m/s"),
+                ("velocity_north", "m/s"),
+                ("velocity_up", "m/s"),
+            ],
+            Column::Acceleration => &[
+                ("acceleration_east", "m/s^2"),
+                ("acceleration_north", "m/s^2"),
+                ("acceleration_up", "m/s^2"),
+            ],
+            Column::Attitude => &[
+                ("attitude_w", "1"),
+                ("attitude_x", "1"),
+                ("attitude_y", "1"),
+                ("attitude_z", "1"),
+            ],
+            Column::Mach => &[("mach", "1")],
+            Column::DragCoefficient => &[("drag_coefficient", "1")],
+            Column::Thrust => &[("thrust", "N")],
+            Column::Mass => &[("mass", "kg")],
+            Column::Cg => &[("cg", "m")],
+            Column::Cp => &[("cp", "m")],
+            Column::Stability => &[("stability", "cal")],
+        }
+    }
+
+    /// Values of the column for a sample, matching [`Column::fields`]
+    fn values(self, sample: &FlightSample) -> Vec<f64> {
+        match self {
+            Column::Time => vec![sample.time],
+            Column::Position => vec![sample.position.x, sample.position.y, sample.position.z],
+            Column::Velocity => vec![sample.velocity.x, sample.velocity.y, sample.velocity.z],
+            Column::Acceleration => vec![
+                sample.acceleration.x,
+                sample.acceleration.y,
+                sample.acceleration.z,
+            ],
+            Column::Attitude => vec![
+                sample.attitude.s,
+                sample.attitude.v.x,
+                sample.attitude.v.y,
+                sample.attitude.v.z,
+            ],
+            Column::Mach => vec![sample.mach],
+            Column::DragCoefficient => vec![sample.drag_coefficient],
+            Column::Thrust => vec![sample.thrust],
+            Column::Mass => vec![sample.mass],
+            Column::Cg => vec![sample.cg],
+            Column::Cp => vec![sample.cp],
+            Column::Stability => vec![sample.stability],
+        }
+    }
+}
+
+impl Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Column::Time => "Time",
+            Column::Position => "Position (ENU)",
+            Column::Velocity => "Velocity",
+            Column::Acceleration => "Acceleration",
+            Column::Attitude => "Attitude",
+            Column::Mach => "Mach",
+            Column::DragCoefficient => "Drag coefficient",
+            Column::Thrust => "Thrust",
+            Column::Mass => "Mass",
+            Column::Cg => "CG",
+            Column::Cp => "CP",
+            Column::Stability => "Stability",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Kml,
+    GeoJson,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Csv,
+        ExportFormat::Json,
+        ExportFormat::Kml,
+        ExportFormat::GeoJson,
+    ];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Kml => "kml",
+            ExportFormat::GeoJson => "geojson",
+        }
+    }
+
+    /// Format written to files with an extension, ignoring case
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+    }
+
+    /// Whether the format only holds the flight path, ignoring the columns
+    pub fn is_map(self) -> bool {
+        matches!(self, ExportFormat::Kml | ExportFormat::GeoJson)
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Kml => "KML",
+            ExportFormat::GeoJson => "GeoJSON",
+        })
+    }
+}
+
+/// Which samples of a flight are written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    /// Every integrator step, exactly as simulated
+    EveryStep,
+    /// Interpolated at a fixed rate (Hz)
+    FixedRate(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub sampling: Sampling,
+    /// Columns to write, in order
+    pub columns: Vec<Column>,
+    /// Landing points of other flights to mark on maps, such as those of a
+    /// Monte Carlo analysis, in the launch site's east-north-up frame (m)
+    pub landing_points: Vec<Vector3<f64>>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            sampling: Sampling::EveryStep,
+            columns: Column::ALL.to_vec(),
+            landing_points: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("failed to write trajectory: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to encode trajectory: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the sample rate must be a finite number greater than zero")]
+    InvalidRate,
+    #[error("resampling at {0} Hz would write more than {} samples", MAX_RESAMPLED)]
+    TooManySamples(f64),
+}
+
+/// Layout of JSON exports, with each sample holding the values of every
+/// column in order
+#[derive(Serialize)]
+struct JsonTrajectory<'a> {
+    columns: Vec<JsonColumn>,
+    samples: Vec<Vec<f64>>,
+    events: Vec<JsonEvent<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonColumn {
+    name: &'static str,
+    unit: &'static str,
+}
+
+#[derive(Serialize)]
+struct JsonEvent<'a> {
+    time: f64,
+    event: &'static str,
+    /// Name of the deployed recovery device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device: Option<&'a str>,
+}
+
+/// Write a trajectory to a file
+pub fn export_trajectory(
+    path: &Path,
+    record: &FlightRecord,
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    write_trajectory(&mut writer, record, options)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Write a trajectory in the chosen format
+pub fn write_trajectory(
+    writer: impl Write,
+    record: &FlightRecord,
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let samples = resample(&record.samples, options.sampling)?;
+
+    match options.format {
+        ExportFormat::Csv => write_csv(writer, &samples, &options.columns),
+        ExportFormat::Json => write_json(writer, &samples, record, &options.columns),
+        ExportFormat::Kml => map::write_kml(writer, &samples, record, &options.landing_points),
+        ExportFormat::GeoJson => {
+            map::write_geojson(writer, &samples, record, &options.landing_points)
+        }
+    }
+}
+
+fn write_csv(
+    mut writer: impl Write,
+    samples: &[FlightSample],
+    columns: &[Column],
+) -> Result<(), ExportError> {
+    let header: Vec<_> = columns
+        .iter()
+        .flat_map(|column| column.fields())
+        .map(|(name, unit)| format!("{} ({})", name, unit))
+        .collect();
+
+    writeln!(writer, "{}", header.join(","))?;
+
+    for sample in samples {
+        let values: Vec<_> = columns
+            .iter()
+            .flat_map(|column| column.values(sample))
+            .map(|value| value.to_string())
+            .collect();
+
+        writeln!(writer, "{}", values.join(","))?;
+    }
+
+    Ok(())
+}
+
+fn write_json(
+    writer: impl Write,
+    samples: &[FlightSample],
+    record: &FlightRecord,
+    columns: &[Column],
+) -> Result<(), ExportError> {
+    let trajectory = JsonTrajectory {
+        columns: columns
+            .iter()
+            .flat_map(|column| column.fields())
+            .map(|&(name, unit)| JsonColumn { name, unit })
+            .collect(),
+        samples: samples
+            .iter()
+            .map(|sample| {
+                columns
+                    .iter()
+                    .flat_map(|column| column.values(sample))
+                    .collect()
+            })
+            .collect(),
+        events: record
+            .events
+            .iter()
+            .map(|event| JsonEvent {
+                time: event.time,
+                event: event_name(event.kind),
+                device: match event.kind {
+                    FlightEventKind::Deployment(index) => {
+                        record.recovery.get(index).map(String::as_str)
+                    }
+                    _ => None,
+                },
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer(writer, &trajectory)?;
+
+    Ok(())
+}
+
+fn event_name(kind: FlightEventKind) -> &'static str {
+    match kind {
+        FlightEventKind::Ignition => "ignition",
+        FlightEventKind::RailExit => "rail_exit",
+        FlightEventKind::Burnout => "burnout",
+        FlightEventKind::Apogee => "apogee",
+        FlightEventKind::Deployment(_) => "deployment",
+        FlightEventKind::Landing => "landing",
+    }
+}
+
+/// Samples at the chosen rate, interpolating linearly between integrator
+/// steps and always ending with the final sample
+fn resample(
+    samples: &[FlightSample],
+    sampling: Sampling,
+) -> Result<Vec<FlightSample>, ExportError> {
+    let rate = match sampling {
+        Sampling::EveryStep => return Ok(samples.to_vec()),
+        Sampling::FixedRate(rate) if !rate.is_finite() || rate <= 0.0 => {
+            return Err(ExportError::InvalidRate)
+        }
+        Sampling::FixedRate(rate) => rate,
+    };
+
+    let (first, last) = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(Vec::new()),
+    };
+
+    // A negative count saturates to no samples before the final one
+    let count = ((last.time - first.time) * rate).ceil();
+    if count.is_nan() || count >= MAX_RESAMPLED as f64 {
+        return Err(ExportError::TooManySamples(rate));
+    }
+
+    let mut resampled = Vec::with_capacity(count as usize + 1);
+    let mut index = 0;
+
+    for step in 0..count as usize {
+        let time = first.time + step as f64 / rate;
+
+        if time >= last.time {
+            break;
+        }
+
+        while samples[index + 1].time < time {
+            index += 1;
+        }
+
+        resampled.push(interpolate(&samples[index], &samples[index + 1], time));
+    }
+
+    resampled.push(*last);
+
+    Ok(resampled)
+}
+
+/// Sample between two consecutive samples at a given time
+fn interpolate(before: &FlightSample, after: &FlightSample, time: f64) -> FlightSample {
+    let span = after.time - before.time;
+    let fraction = if span > 0.0 {
+        (time - before.time) / span
+    } else {
+        0.0
+    };
+    let lerp = |a: f64, b: f64| a + (b - a) * fraction;
+
+    FlightSample {
+        time,
+        phase: if fraction < 1.0 {
+            before.phase
+        } else {
+            after.phase
+        },
+        position: before.position + (after.position - before.position) * fraction,
+        velocity: before.velocity + (after.velocity - before.velocity) * fraction,
+        acceleration: before.acceleration + (after.acceleration - before.acceleration) * fraction,
+        attitude: nlerp(before.attitude, after.attitude, fraction),
+        mach: lerp(before.mach, after.mach),
+        thrust: lerp(before.thrust, after.thrust),
+        drag: lerp(before.drag, after.drag),
+        drag_coefficient: lerp(before.drag_coefficient, after.drag_coefficient),
+        angle_of_attack: lerp(before.angle_of_attack, after.angle_of_attack),
+        mass: lerp(before.mass, after.mass),
+        cg: lerp(before.cg, after.cg),
+        cp: lerp(before.cp, after.cp),
+        stability: lerp(before.stability, after.stability),
+    }
+}
+
+/// Normalized linear interpolation between two orientations, which is
+/// accurate enough over a single integrator step
+fn nlerp(a: Quaternion<f64>, b: Quaternion<f64>, fraction: f64) -> Quaternion<f64> {
+    // Take the shorter way around
+    let b = if a.dot(b) < 0.0 { -b } else { b };
+
+    (a * (1.0 - fraction) + b * fraction).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::FlightPhase;
+
+    /// Samples at some times, rising at 10 m/s
+    fn samples(times: &[f64]) -> Vec<FlightSample> {
+        times
+            .iter()
+            .map(|&time| FlightSample {
+                time,
+                phase: FlightPhase::Coast,
+                position: Vector3::new(0.0, 0.0, 10.0 * time),
+                velocity: Vector3::new(0.0, 0.0, 10.0),
+                acceleration: Vector3::new(0.0, 0.0, 0.0),
+                attitude: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+                mach: 0.0,
+                thrust: 0.0,
+                drag: 0.0,
+                drag_coefficient: 0.0,
+                angle_of_attack: 0.0,
+                mass: 1.0,
+                cg: 0.5,
+                cp: 0.6,
+                stability: 1.0,
+            })
+            .collect()
+    }
+
+    fn times(samples: &[FlightSample]) -> Vec<f64> {
+        samples.iter().map(|sample| sample.time).collect()
+    }
+
+    #[test]
+    fn keeps_every_step() {
+        let steps = samples(&[0.0, 0.3, 0.7]);
+
+        assert_eq!(
+            times(&resample(&steps, Sampling::EveryStep).unwrap()),
+            [0.0, 0.3, 0.7]
+        );
+    }
+
+    #[test]
+    fn interpolates_at_fixed_rate() {
+        let steps = samples(&[0.0, 0.3, 0.7, 1.0]);
+        let resampled = resample(&steps, Sampling::FixedRate(4.0)).unwrap();
+
+        assert_eq!(times(&resampled), [0.0, 0.25, 0.5, 0.75, 1.0]);
+        for sample in &resampled {
+            assert!((sample.position.z - 10.0 * sample.time).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn ends_with_final_sample() {
+        let steps = samples(&[0.0, 0.4, 0.9]);
+
+        assert_eq!(
+            times(&resample(&steps, Sampling::FixedRate(2.0)).unwrap()),
+            [0.0, 0.5, 0.9]
+        );
+        // Slower than the flight is long
+        assert_eq!(
+            times(&resample(&steps, Sampling::FixedRate(0.1)).unwrap()),
+            [0.0, 0.9]
+        );
+    }
+
+    #[test]
+    fn resamples_short_flights() {
+        assert!(resample(&[], Sampling::FixedRate(10.0)).unwrap().is_empty());
+        assert_eq!(
+            times(&resample(&samples(&[2.0]), Sampling::FixedRate(10.0)).unwrap()),
+            [2.0]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rates() {
+        let steps = samples(&[0.0, 1.0]);
+
+        for &rate in &[0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(
+                resample(&steps, Sampling::FixedRate(rate)),
+                Err(ExportError::InvalidRate)
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_too_many_samples() {
+        let steps = samples(&[0.0, 600.0]);
+
+        assert!(matches!(
+            resample(&steps, Sampling::FixedRate(1e300)),
+            Err(ExportError::TooManySamples(_))
+        ));
+        assert!(matches!(
+            resample(&samples(&[0.0, f64::NAN]), Sampling::FixedRate(10.0)),
+            Err(ExportError::TooManySamples(_))
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+//! Flight paths on a map, as KML for Google Earth and GeoJSON for GIS tools
+
+use std::io::Write;
+
+use cgmath::Vector3;
+use serde_json::{json, Value};
+
+use super::{event_name, ExportError, FlightRecord};
+use crate::sim::{
+    geodesy::Geodetic,
+    simulation::{FlightEventKind, FlightSample},
+};
+
+/// A point of interest along the flight path
+struct Placemark {
+    name: String,
+    event: &'static str,
+    time: f64,
+    /// Height above the launch site (m)
+    altitude: f64,
+    location: Geodetic,
+}
+
+/// Placemarks for the launch site and the apogee, deployment and landing
+/// events of a flight
+fn placemarks(record: &FlightRecord) -> Vec<Placemark> {
+    let site = record.launch.site();
+    let sample_at = |time: f64| {
+        let index = record.samples.partition_point(|sample| sample.time < time);
+
+        record.samples.get(index).or_else(|| record.samples.last())
+    };
+
+    record
+        .events
+        .iter()
+        .filter_map(|event| {
+            let name = match event.kind {
+                FlightEventKind::Ignition => "Launch".to_string(),
+                FlightEventKind::Apogee => "Apogee".to_string(),
+                FlightEventKind::Deployment(index) => format!(
+                    "{} deployment",
+                    record
+                        .recovery
+                        .get(index)
+                        .map(String::as_str)
+                        .unwrap_or("Recovery device")
+                ),
+                FlightEventKind::Landing => "Landing".to_string(),
+                FlightEventKind::RailExit | FlightEventKind::Burnout => return None,
+            };
+            let sample = sample_at(event.time)?;
+
+            Some(Placemark {
+                name,
+                event: event_name(event.kind),
+                time: event.time,
+                altitude: sample.altitude(),
+                location: site.offset(sample.position),
+            })
+        })
+        .collect()
+}
+
+pub(super) fn write_kml(
+    mut writer: impl Write,
+    samples: &[FlightSample],
+    record: &FlightRecord,
+    landing_points: &[Vector3<f64>],
+) -> Result<(), ExportError> {
+    let site = record.launch.site();
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+    writeln!(writer, "<Document>")?;
+    writeln!(writer, "<name>Simulated flight</name>")?;
+    writeln!(
+        writer,
+        r#"<Style id="path"><LineStyle><color>ff0000ff</color><width>3</width></LineStyle></Style>"#
+    )?;
+    writeln!(
+        writer,
+        r#"<Style id="landing"><IconStyle><scale>0.5</scale></IconStyle></Style>"#
+    )?;
+
+    writeln!(writer, "<Placemark>")?;
+    writeln!(writer, "<name>Flight path</name>")?;
+    writeln!(writer, "<styleUrl>#path</styleUrl>")?;
+    writeln!(writer, "<LineString>")?;
+    writeln!(writer, "<altitudeMode>absolute</altitudeMode>")?;
+    writeln!(writer, "<coordinates>")?;
+    for sample in samples {
+        writeln!(writer, "{}", kml_coordinates(site.offset(sample.position)))?;
+    }
+    writeln!(writer, "</coordinates>")?;
+    writeln!(writer, "</LineString>")?;
+    writeln!(writer, "</Placemark>")?;
+
+    for placemark in placemarks(record) {
+        writeln!(writer, "<Placemark>")?;
+        writeln!(writer, "<name>{}</name>", escape(&placemark.name))?;
+        writeln!(
+            writer,
+            "<description>{:.2} s, {:.1} m above the launch site</description>",
+            placemark.time, placemark.altitude
+        )?;
+        writeln!(
+            writer,
+            "<Point><altitudeMode>absolute</altitudeMode><coordinates>{}</coordinates></Point>",
+            kml_coordinates(placemark.location)
+        )?;
+        writeln!(writer, "</Placemark>")?;
+    }
+
+    if !landing_points.is_empty() {
+        writeln!(writer, "<Folder>")?;
+        writeln!(writer, "<name>Dispersed landings</name>")?;
+        for &point in landing_points {
+            writeln!(
+                writer,
+                "<Placemark><styleUrl>#landing</styleUrl><Point><coordinates>{}</coordinates></Point></Placemark>",
+                kml_coordinates(site.offset(point))
+            )?;
+        }
+        writeln!(writer, "</Folder>")?;
+    }
+
+    writeln!(writer, "</Document>")?;
+    writeln!(writer, "</kml>")?;
+
+    Ok(())
+}
+
+pub(super) fn write_geojson(
+    writer: impl Write,
+    samples: &[FlightSample],
+    record: &FlightRecord,
+    landing_points: &[Vector3<f64>],
+) -> Result<(), ExportError> {
+    let site = record.launch.site();
+
+    let path = json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": samples
+                .iter()
+                .map(|sample| geojson_position(site.offset(sample.position)))
+                .collect::<Vec<_>>(),
+        },
+        "properties": { "name": "Flight path" },
+    });
+
+    let events = placemarks(record).into_iter().map(|placemark| {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": geojson_position(placemark.location),
+            },
+            "properties": {
+                "name": placemark.name,
+                "event": placemark.event,
+                "time": placemark.time,
+                "altitude_agl": placemark.altitude,
+            },
+        })
+    });
+
+    let landings = landing_points.iter().map(|&point| {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": geojson_position(site.offset(point)),
+            },
+            "properties": { "event": "dispersed_landing" },
+        })
+    });
+
+    let features: Vec<Value> = std::iter::once(path)
+        .chain(events)
+        .chain(landings)
+        .collect();
+
+    serde_json::to_writer(
+        writer,
+        &json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }),
+    )?;
+
+    Ok(())
+}
+
+/// Longitude, latitude and altitude as written in KML coordinate lists
+fn kml_coordinates(location: Geodetic) -> String {
+    format!(
+        "{:.8},{:.8},{:.2}",
+        location.longitude.to_degrees(),
+        location.latitude.to_degrees(),
+        location.altitude
+    )
+}
+
+/// GeoJSON position, which puts the longitude first
+fn geojson_position(location: Geodetic) -> [f64; 3] {
+    [
+        location.longitude.to_degrees(),
+        location.latitude.to_degrees(),
+        location.altitude,
+    ]
+}
+
+/// Escape text for use in XML element content
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,87 @@
+//! Conversion of the local east-north-up frame simulated around the launch
+//! site to WGS84 geodetic coordinates
+
+use cgmath::Vector3;
+
+/// Semi-major axis of the WGS84 ellipsoid (m)
+const SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+/// Flattening of the WGS84 ellipsoid
+const FLATTENING: f64 = 1.0 / 298.257_223_563;
+/// Square of the first eccentricity of the WGS84 ellipsoid
+const ECCENTRICITY_SQUARED: f64 = FLATTENING * (2.0 - FLATTENING);
+
+/// A point on or above the WGS84 ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    /// Positive north (rad)
+    pub latitude: f64,
+    /// Positive east (rad)
+    pub longitude: f64,
+    /// Height above the ellipsoid (m)
+    pub altitude: f64,
+}
+
+impl Geodetic {
+    /// Earth-centered, earth-fixed coordinates of the point (m)
+    pub fn to_ecef(self) -> Vector3<f64> {
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.sin_cos();
+        let normal = prime_vertical_radius(sin_lat);
+
+        Vector3::new(
+            (normal + self.altitude) * cos_lat * cos_lon,
+            (normal + self.altitude) * cos_lat * sin_lon,
+            (normal * (1.0 - ECCENTRICITY_SQUARED) + self.altitude) * sin_lat,
+        )
+    }
+
+    /// Point at earth-centered, earth-fixed coordinates (m)
+    pub fn from_ecef(ecef: Vector3<f64>) -> Self {
+        let longitude = ecef.y.atan2(ecef.x);
+        let distance = (ecef.x * ecef.x + ecef.y * ecef.y).sqrt();
+
+        // Fixed point iteration on the latitude, which converges to well
+        // below a millimetre within a few steps near the surface
+        let mut latitude = ecef.z.atan2(distance * (1.0 - ECCENTRICITY_SQUARED));
+        let mut altitude = 0.0;
+
+        for _ in 0..5 {
+            let (sin_lat, cos_lat) = latitude.sin_cos();
+            let normal = prime_vertical_radius(sin_lat);
+
+            altitude = if cos_lat.abs() > 1e-9 {
+                distance / cos_lat - normal
+            } else {
+                ecef.z.abs() - normal * (1.0 - ECCENTRICITY_SQUARED)
+            };
+            latitude = ecef
+                .z
+                .atan2(distance * (1.0 - ECCENTRICITY_SQUARED * normal / (normal + altitude)));
+        }
+
+        Self {
+            latitude,
+            longitude,
+            altitude,
+        }
+    }
+
+    /// Point at a position in the east-north-up frame centered here (m)
+    pub fn offset(self, enu: Vector3<f64>) -> Self {
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        let (sin_lon, cos_lon) = self.longitude.sin_cos();
+
+        let delta = Vector3::new(
+            -sin_lon * enu.x - sin_lat * cos_lon * enu.y + cos_lat * cos_lon * enu.z,
+            cos_lon * enu.x - sin_lat * sin_lon * enu.y + cos_lat * sin_lon * enu.z,
+            cos_lat * enu.y + sin_lat * enu.z,
+        );
+
+        Self::from_ecef(self.to_ecef() + delta)
+    }
+}
+
+/// Radius of curvature of the ellipsoid in the prime vertical (m)
+fn prime_vertical_radius(sin_latitude: f64) -> f64 {
+    SEMI_MAJOR_AXIS / (1.0 - ECCENTRICITY_SQUARED * sin_latitude * sin_latitude).sqrt()
+}
@@ -0,0 +1,251 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+mod eng;
+mod rse;
+
+/// A rocket motor described by its thrust curve and mass properties
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Motor {
+    /// Common name of the motor, such as `H128W`
+    pub designation: String,
+    /// Outer diameter of the motor casing (m)
+    pub diameter: f64,
+    /// Length of the motor casing (m)
+    pub length: f64,
+    /// Mass of the loaded motor before ignition (kg)
+    pub total_mass: f64,
+    /// Mass of propellant burned over the thrust curve (kg)
+    pub propellant_mass: f64,
+    /// Thrust curve as `(time (s), thrust (N))` pairs sorted by time
+    pub thrust_curve: Vec<(f64, f64)>,
+    /// Ejection delays the motor is available with, shortest first. Empty for
+    /// plugged motors and when the delays are unknown (s)
+    #[serde(default)]
+    pub delays: Vec<f64>,
+}
+
+/// Failure to read a motor from a file
+#[derive(Debug, Error)]
+pub enum MotorFileError {
+    #[error("failed to read motor file: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to parse motor file XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("unsupported motor file extension, expected .eng or .rse")]
+    UnknownFormat,
+}
+
+impl Motor {
+    /// Load the first motor defined in a RASP (`.eng`) or RockSim (`.rse`)
+    /// motor file
+    pub fn load(path: &Path) -> Result<Self, MotorFileError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("eng") => eng::parse(&fs::read_to_string(path)?),
+            Some("rse") => rse::parse(&fs::read_to_string(path)?),
+            _ => Err(MotorFileError::UnknownFormat),
+        }
+    }
+
+    /// A 29mm H class reload used while no motor has been selected
+    pub fn demo() -> Self {
+        Self {
+            designation: "H128W".into(),
+            diameter: 0.029,
+            length: 0.194,
+            total_mass: 0.206,
+            propellant_mass: 0.094,
+            thrust_curve: vec![
+                (0.0, 0.0),
+                (0.02, 182.0),
+                (0.1, 175.0),
+                (0.4, 160.0),
+                (0.8, 135.0),
+                (1.1, 112.0),
+                (1.3, 70.0),
+                (1.45, 18.0),
+                (1.5, 0.0),
+            ],
+            delays: vec![6.0, 10.0, 14.0],
+        }
+    }
+
+    /// Time from ignition until the end of the thrust curve (s)
+    pub fn burn_time(&self) -> f64 {
+        self.thrust_curve.last().map(|&(t, _)| t).unwrap_or(0.0)
+    }
+
+    /// Thrust at a given time since ignition, linearly interpolated (N)
+    pub fn thrust(&self, time: f64) -> f64 {
+        let mut previous = (0.0, 0.0);
+
+        for &(t, thrust) in &self.thrust_curve {
+            if time < t {
+                let (t0, thrust0) = previous;
+
+                return thrust0 + (thrust - thrust0) * (time - t0) / (t - t0);
+            }
+
+            previous = (t, thrust);
+        }
+
+        0.0
+    }
+
+    /// Total impulse delivered over the whole burn (N·s)
+    pub fn total_impulse(&self) -> f64 {
+        self.impulse_until(self.burn_time())
+    }
+
+    /// Impulse delivered from ignition up to a given time (N·s)
+    pub fn impulse_until(&self, time: f64) -> f64 {
+        let mut impulse = 0.0;
+        let mut previous = (0.0, 0.0);
+
+        for &(t, thrust) in &self.thrust_curve {
+            let (t0, thrust0) = previous;
+
+            if time <= t {
+                let thrust_at_time = self.thrust(time);
+
+                return impulse + (thrust0 + thrust_at_time) * 0.5 * (time - t0).max(0.0);
+            }
+
+            impulse += (thrust0 + thrust) * 0.5 * (t - t0);
+            previous = (t, thrust);
+        }
+
+        impulse
+    }
+
+    /// Mass of the motor at a given time, assuming propellant is consumed in
+    /// proportion to the impulse delivered (kg)
+    pub fn mass(&self, time: f64) -> f64 {
+        let total_impulse = self.total_impulse();
+
+        if total_impulse <= 0.0 {
+            return self.total_mass;
+        }
+
+        let burned = (self.impulse_until(time) / total_impulse).clamp(0.0, 1.0);
+
+        self.total_mass - self.propellant_mass * burned
+    }
+
+    /// Check the thrust curve and masses for problems that would make the
+    /// simulation misleading, returning a description of each
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.thrust_curve.len() < 2 {
+            warnings.push("the thrust curve has fewer than two points".into());
+        }
+        if self
+            .thrust_curve
+            .windows(2)
+            .any(|pair| pair[1].0 <= pair[0].0)
+        {
+            warnings.push("thrust curve times are not strictly increasing".into());
+        }
+        if self.thrust_curve.iter().any(|&(_, thrust)| thrust < 0.0) {
+            warnings.push("the thrust curve contains negative thrust".into());
+        }
+        if let Some(&(time, thrust)) = self.thrust_curve.last() {
+            if thrust != 0.0 {
+                warnings.push(format!(
+                    "the thrust curve ends at {:.1} N instead of zero at {:.2} s",
+                    thrust, time
+                ));
+            }
+        }
+        if self.propellant_mass <= 0.0 || self.propellant_mass > self.total_mass {
+            warnings.push(format!(
+                "propellant mass of {:.1} g is not between zero and the total mass of {:.1} g",
+                self.propellant_mass * 1000.0,
+                self.total_mass * 1000.0
+            ));
+        }
+
+        let total_impulse = self.total_impulse();
+        if let Some(class) = impulse_class(total_impulse) {
+            if !self.designation.to_ascii_uppercase().starts_with(&class) {
+                warnings.push(format!(
+                    "a total impulse of {:.1} N·s is class {}, which does not match the designation {}",
+                    total_impulse, class, self.designation
+                ));
+            }
+        }
+
+        warnings
+    }
+}
+
+/// Parse a list of ejection delays such as `6-10-14` or `6,10,14`, skipping
+/// plugged (`P`) and zero delays and any given as letters (s)
+pub fn parse_delays(text: &str) -> Vec<f64> {
+    let mut delays: Vec<f64> = text
+        .split(|c: char| c == '-' || c == ',' || c.is_whitespace())
+        .filter_map(|delay| delay.trim().parse::<f64>().ok())
+        .filter(|&delay| delay.is_finite() && delay > 0.0)
+        .collect();
+
+    delays.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    delays.dedup();
+    delays
+}
+
+/// Letter class of a total impulse (N·s), where each class spans double the
+/// impulse of the previous one, starting with class A up to 2.5 N·s
+pub fn impulse_class(total_impulse: f64) -> Option<String> {
+    if !total_impulse.is_finite() || total_impulse <= 0.0 {
+        return None;
+    }
+
+    Some(match total_impulse {
+        impulse if impulse <= 0.3125 => "1/8A".into(),
+        impulse if impulse <= 0.625 => "1/4A".into(),
+        impulse if impulse <= 1.25 => "1/2A".into(),
+        impulse => {
+            let index = (impulse / 2.5).log2().ceil().max(0.0) as u8;
+
+            match b'A'.checked_add(index).map(char::from) {
+                Some(letter) if letter.is_ascii_uppercase() => letter.to_string(),
+                _ => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_impulses() {
+        assert_eq!(impulse_class(0.3).as_deref(), Some("1/8A"));
+        assert_eq!(impulse_class(1.0).as_deref(), Some("1/2A"));
+        assert_eq!(impulse_class(2.5).as_deref(), Some("A"));
+        assert_eq!(impulse_class(8.8).as_deref(), Some("C"));
+        assert_eq!(impulse_class(320.0).as_deref(), Some("H"));
+        assert_eq!(impulse_class(0.0), None);
+        assert_eq!(impulse_class(f64::NAN), None);
+    }
+
+    #[test]
+    fn rejects_impulses_beyond_z() {
+        assert_eq!(impulse_class(1e300), None);
+        assert_eq!(impulse_class(f64::MAX), None);
+        assert_eq!(impulse_class(f64::INFINITY), None);
+    }
+}
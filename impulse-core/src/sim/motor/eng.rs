@@ -0,0 +1,182 @@
+//! RASP motor files, as used by most simulators and ThrustCurve
+//!
+//! ```text
+//! ; comment
+//! H128W 29 194 6-10-14 0.094 0.206 AT
+//!    0.02 182.0
+//!    1.5 0.0
+//! ;
+//! ```
+
+use super::{parse_delays, Motor, MotorFileError};
+
+pub(super) fn parse(contents: &str) -> Result<Motor, MotorFileError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    // Comments may only precede the header
+    let (line, header) = lines
+        .find(|(_, line)| !line.starts_with(';'))
+        .ok_or_else(|| MotorFileError::Invalid("the file does not define a motor".into()))?;
+
+    let fields = header.split_whitespace().collect::<Vec<_>>();
+    let (designation, diameter, length, delays, propellant_mass, total_mass) =
+        match fields.as_slice() {
+            [designation, diameter, length, delays, propellant_mass, total_mass, ..] => (
+                designation.to_string(),
+                number(line, diameter, "diameter")?,
+                number(line, length, "length")?,
+                parse_delays(delays),
+                number(line, propellant_mass, "propellant mass")?,
+                number(line, total_mass, "total mass")?,
+            ),
+            _ => {
+                return Err(MotorFileError::Parse {
+                    line,
+                    message: "expected a header of designation, diameter, length, delays, \
+                          propellant mass, total mass and manufacturer"
+                        .into(),
+                })
+            }
+        };
+
+    let mut thrust_curve = Vec::new();
+
+    for (line, data) in lines {
+        // Data ends at a comment or at the header of the next motor, which
+        // starts with its designation rather than a number, and any following
+        // motors are ignored
+        let fields = data.split_whitespace().collect::<Vec<_>>();
+        if data.starts_with(';') || fields[0].parse::<f64>().is_err() {
+            break;
+        }
+
+        match fields.as_slice() {
+            [time, thrust] => {
+                thrust_curve.push((number(line, time, "time")?, number(line, thrust, "thrust")?))
+            }
+            _ => {
+                return Err(MotorFileError::Parse {
+                    line,
+                    message: format!("expected a time and thrust, found '{}'", data),
+                })
+            }
+        }
+    }
+
+    if thrust_curve.first().is_some_and(|&(time, _)| time > 0.0) {
+        thrust_curve.insert(0, (0.0, 0.0));
+    }
+
+    Ok(Motor {
+        designation,
+        diameter: diameter / 1000.0,
+        length: length / 1000.0,
+        total_mass,
+        propellant_mass,
+        thrust_curve,
+        delays,
+    })
+}
+
+fn number(line: usize, text: &str, name: &str) -> Result<f64, MotorFileError> {
+    text.parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+        .ok_or_else(|| MotorFileError::Parse {
+            line,
+            message: format!("invalid {} '{}'", name, text),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H128W: &str = "\
+; AeroTech H128W
+H128W 29 194 6-10-14 0.094 0.206 AT
+   0.02 182.0
+   0.8 135.0
+   1.5 0.0
+;
+";
+
+    #[test]
+    fn parses_motor() {
+        let motor = parse(H128W).unwrap();
+
+        assert_eq!(motor.designation, "H128W");
+        assert!((motor.diameter - 0.029).abs() < 1e-12);
+        assert!((motor.length - 0.194).abs() < 1e-12);
+        assert_eq!(motor.propellant_mass, 0.094);
+        assert_eq!(motor.total_mass, 0.206);
+        assert_eq!(motor.delays, vec![6.0, 10.0, 14.0]);
+        // The curve starts from zero thrust at ignition
+        assert_eq!(
+            motor.thrust_curve,
+            vec![(0.0, 0.0), (0.02, 182.0), (0.8, 135.0), (1.5, 0.0)]
+        );
+    }
+
+    #[test]
+    fn parses_first_of_several_motors() {
+        let contents = "\
+; Two motors without terminating semicolons
+G80T 29 124 4-7-10 0.0625 0.1128 AT
+   0.0 0.0
+   0.5 95.0
+   1.4 0.0
+H128W 29 194 6-10-14 0.094 0.206 AT
+   0.02 182.0
+   1.5 0.0
+";
+        let motor = parse(contents).unwrap();
+
+        assert_eq!(motor.designation, "G80T");
+        assert_eq!(
+            motor.thrust_curve,
+            vec![(0.0, 0.0), (0.5, 95.0), (1.4, 0.0)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        assert!(matches!(
+            parse("; only a comment\n"),
+            Err(MotorFileError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse("H128W 29 194\n"),
+            Err(MotorFileError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse("H128W 29 194 P 0.094 0.206 AT\n  0.02 182.0 5\n"),
+            Err(MotorFileError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse("H128W 29 194 P 0.094 0.206 AT\n  0.02 lots\n"),
+            Err(MotorFileError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_non_finite_numbers() {
+        for thrust in &["inf", "NaN", "1e309"] {
+            assert!(matches!(
+                parse(&format!(
+                    "H128W 29 194 P 0.094 0.206 AT\n  0.02 {}\n",
+                    thrust
+                )),
+                Err(MotorFileError::Parse { line: 2, .. })
+            ));
+        }
+        assert!(matches!(
+            parse("H128W inf 194 P 0.094 0.206 AT\n  0.02 182.0\n"),
+            Err(MotorFileError::Parse { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+//! RockSim motor files, an XML format which may define several motors
+//!
+//! ```xml
+//! <engine-database>
+//!   <engine-list>
+//!     <engine code="H128W" dia="29." len="194." propWt="94." initWt="206." delays="6,10,14" ...>
+//!       <data>
+//!         <eng-data t="0.02" f="182." m="93.1"/>
+//!       </data>
+//!     </engine>
+//!   </engine-list>
+//! </engine-database>
+//! ```
+
+use roxmltree::{Document, Node};
+
+use super::{parse_delays, Motor, MotorFileError};
+
+pub(super) fn parse(contents: &str) -> Result<Motor, MotorFileError> {
+    let document = Document::parse(contents)?;
+    let engine = document
+        .descendants()
+        .find(|node| node.has_tag_name("engine"))
+        .ok_or_else(|| MotorFileError::Invalid("the file does not define a motor".into()))?;
+
+    let mut thrust_curve = engine
+        .descendants()
+        .filter(|node| node.has_tag_name("eng-data"))
+        .map(|point| Ok((attribute(point, "t")?, attribute(point, "f")?)))
+        .collect::<Result<Vec<_>, MotorFileError>>()?;
+
+    if thrust_curve.first().is_some_and(|&(time, _)| time > 0.0) {
+        thrust_curve.insert(0, (0.0, 0.0));
+    }
+
+    Ok(Motor {
+        designation: engine
+            .attribute("code")
+            .ok_or_else(|| MotorFileError::Invalid("the motor has no code".into()))?
+            .to_string(),
+        diameter: attribute(engine, "dia")? / 1000.0,
+        length: attribute(engine, "len")? / 1000.0,
+        total_mass: attribute(engine, "initWt")? / 1000.0,
+        propellant_mass: attribute(engine, "propWt")? / 1000.0,
+        thrust_curve,
+        delays: engine
+            .attribute("delays")
+            .map(parse_delays)
+            .unwrap_or_default(),
+    })
+}
+
+/// A numeric attribute of an element
+fn attribute(node: Node<'_, '_>, name: &str) -> Result<f64, MotorFileError> {
+    let position = node.document().text_pos_at(node.range().start);
+    let invalid = |message: String| MotorFileError::Parse {
+        line: position.row as usize,
+        message,
+    };
+
+    node.attribute(name)
+        .ok_or_else(|| {
+            invalid(format!(
+                "<{}> is missing '{}'",
+                node.tag_name().name(),
+                name
+            ))
+        })?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+        .ok_or_else(|| {
+            invalid(format!(
+                "invalid '{}' on <{}>",
+                name,
+                node.tag_name().name()
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOTORS: &str = r#"
+<engine-database>
+  <engine-list>
+    <engine code="H128W" dia="29." len="194." propWt="94." initWt="206." delays="6,10,14" mfg="AT">
+      <data>
+        <eng-data t="0.02" f="182." m="93.1"/>
+        <eng-data t="0.8" f="135." m="40.2"/>
+        <eng-data t="1.5" f="0." m="0."/>
+      </data>
+    </engine>
+    <engine code="G80T" dia="29." len="124." propWt="62.5" initWt="112.8" delays="P" mfg="AT">
+      <data>
+        <eng-data t="0." f="0." m="62.5"/>
+        <eng-data t="1.4" f="0." m="0."/>
+      </data>
+    </engine>
+  </engine-list>
+</engine-database>
+"#;
+
+    #[test]
+    fn parses_first_motor() {
+        let motor = parse(MOTORS).unwrap();
+
+        assert_eq!(motor.designation, "H128W");
+        assert!((motor.diameter - 0.029).abs() < 1e-12);
+        assert!((motor.length - 0.194).abs() < 1e-12);
+        assert!((motor.propellant_mass - 0.094).abs() < 1e-12);
+        assert!((motor.total_mass - 0.206).abs() < 1e-12);
+        assert_eq!(motor.delays, vec![6.0, 10.0, 14.0]);
+        assert_eq!(
+            motor.thrust_curve,
+            vec![(0.0, 0.0), (0.02, 182.0), (0.8, 135.0), (1.5, 0.0)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        assert!(matches!(
+            parse("<engine-database/>"),
+            Err(MotorFileError::Invalid(_))
+        ));
+        assert!(matches!(parse("<engine"), Err(MotorFileError::Xml(_))));
+        assert!(matches!(
+            parse("<engine code=\"H128W\" dia=\"29\">\n</engine>"),
+            Err(MotorFileError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse(
+                r#"<engine code="X" dia="29" len="1" initWt="1" propWt="1"><eng-data t="a" f="1"/></engine>"#
+            ),
+            Err(MotorFileError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_non_finite_numbers() {
+        for thrust in &["inf", "NaN", "1e309"] {
+            assert!(matches!(
+                parse(&MOTORS.replace(r#"f="135.""#, &format!(r#"f="{}""#, thrust))),
+                Err(MotorFileError::Parse { line: 7, .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Range safety limits of a launch field, and checks of dispersion analyses
+//! against them
+
+use std::fmt::{self, Display};
+
+use cgmath::{InnerSpace, Vector2};
+use serde::{Deserialize, Serialize};
+
+use super::dispersion::DispersionResults;
+
+/// Standard normal quantile of the confidence level of [`RangeCheck::upper_bound`]
+const CONFIDENCE_Z: f64 = 1.959_964;
+
+/// Where flights are allowed to land, in the east-north frame of the launch
+/// site
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldLimits {
+    /// Corners of the recovery area as east and north positions, in order
+    /// around its edge. Fewer than three corners leave the area unbounded (m)
+    pub boundary: Vec<(f64, f64)>,
+    /// Radius around the launch rail that flights must land within, as
+    /// granted by the waiver (m)
+    pub waiver_radius: Option<f64>,
+}
+
+impl FieldLimits {
+    /// Whether any limit is set
+    pub fn is_limited(&self) -> bool {
+        self.has_boundary() || self.waiver_radius.is_some()
+    }
+
+    /// Whether there are enough corners to enclose an area
+    pub fn has_boundary(&self) -> bool {
+        self.boundary.len() >= 3
+    }
+
+    /// Whether a landing point is within every limit
+    pub fn contains(&self, point: Vector2<f64>) -> bool {
+        let within_waiver = self
+            .waiver_radius
+            .is_none_or(|radius| point.magnitude() <= radius);
+
+        within_waiver && (!self.has_boundary() || self.boundary_contains(point))
+    }
+
+    /// Even-odd test of whether the boundary polygon contains a point
+    fn boundary_contains(&self, point: Vector2<f64>) -> bool {
+        let corners = &self.boundary;
+        let mut inside = false;
+
+        for (index, &(x1, y1)) in corners.iter().enumerate() {
+            let (x2, y2) = corners[(index + 1) % corners.len()];
+
+            if (y1 > point.y) != (y2 > point.y)
+                && point.x < x1 + (point.y - y1) / (y2 - y1) * (x2 - x1)
+            {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+}
+
+/// Which flights of a dispersion analysis landed outside the field limits
+#[derive(Debug, Clone)]
+pub struct RangeCheck {
+    /// Whether each flight landed outside, in the order of the analysis
+    pub outside: Vec<bool>,
+    /// Fraction of flights that landed outside
+    pub probability: f64,
+    /// Upper bound of the 95% confidence interval of the probability, which
+    /// stays meaningful when no flights land outside
+    pub upper_bound: f64,
+}
+
+impl RangeCheck {
+    pub fn new(results: &DispersionResults, limits: &FieldLimits) -> Self {
+        let outside: Vec<_> = results
+            .runs
+            .iter()
+            .map(|run| !limits.contains(run.landing.truncate()))
+            .collect();
+
+        let total = outside.len() as f64;
+        let count = outside.iter().filter(|&&outside| outside).count() as f64;
+
+        let (probability, upper_bound) = if total > 0.0 {
+            let probability = count / total;
+
+            // Wilson score interval, which behaves at probabilities near zero
+            // unlike the normal approximation
+            let z2 = CONFIDENCE_Z * CONFIDENCE_Z;
+            let center = probability + z2 / (2.0 * total);
+            let spread = CONFIDENCE_Z
+                * (probability * (1.0 - probability) / total + z2 / (4.0 * total * total)).sqrt();
+
+            (
+                probability,
+                ((center + spread) / (1.0 + z2 / total)).min(1.0),
+            )
+        } else {
+            (0.0, 1.0)
+        };
+
+        Self {
+            outside,
+            probability,
+            upper_bound,
+        }
+    }
+
+    /// Number of flights that landed outside
+    pub fn count(&self) -> usize {
+        self.outside.iter().filter(|&&outside| outside).count()
+    }
+}
+
+impl Display for RangeCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} flights ({:.1}%, at most {:.1}% with 95% confidence)",
+            self.count(),
+            self.outside.len(),
+            self.probability * 100.0,
+            self.upper_bound * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::*;
+    use crate::sim::dispersion::DispersionRun;
+
+    /// An L shaped field, with its top right corner cut away
+    fn field() -> FieldLimits {
+        FieldLimits {
+            boundary: vec![
+                (0.0, 0.0),
+                (100.0, 0.0),
+                (100.0, 40.0),
+                (40.0, 40.0),
+                (40.0, 100.0),
+                (0.0, 100.0),
+            ],
+            waiver_radius: None,
+        }
+    }
+
+    fn results(landings: &[(f64, f64)]) -> DispersionResults {
+        DispersionResults::new(
+            landings
+                .iter()
+                .map(|&(east, north)| DispersionRun {
+                    apogee: 100.0,
+                    landing: Vector3::new(east, north, 0.0),
+                    flight_time: 30.0,
+                    landed: true,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn contains_points_inside_concave_boundary() {
+        let field = field();
+
+        for &(east, north) in &[(20.0, 20.0), (80.0, 20.0), (20.0, 80.0)] {
+            assert!(
+                field.contains(Vector2::new(east, north)),
+                "{} {}",
+                east,
+                north
+            );
+        }
+        for &(east, north) in &[(80.0, 80.0), (-10.0, 50.0), (150.0, 20.0), (20.0, 120.0)] {
+            assert!(
+                !field.contains(Vector2::new(east, north)),
+                "{} {}",
+                east,
+                north
+            );
+        }
+    }
+
+    #[test]
+    fn applies_waiver_radius() {
+        let mut limits = FieldLimits {
+            boundary: Vec::new(),
+            waiver_radius: Some(50.0),
+        };
+
+        assert!(limits.contains(Vector2::new(30.0, 30.0)));
+        assert!(!limits.contains(Vector2::new(-40.0, -40.0)));
+
+        // Both limits apply together
+        limits.boundary = field().boundary;
+        assert!(limits.contains(Vector2::new(30.0, 30.0)));
+        assert!(!limits.contains(Vector2::new(-30.0, -30.0)));
+        assert!(!limits.contains(Vector2::new(90.0, 20.0)));
+    }
+
+    #[test]
+    fn bounds_probability_when_no_flights_land_outside() {
+        let check = RangeCheck::new(&results(&[(20.0, 20.0); 100]), &field());
+        let z2 = CONFIDENCE_Z * CONFIDENCE_Z;
+
+        assert_eq!(check.count(), 0);
+        assert_eq!(check.probability, 0.0);
+        assert!(check.upper_bound > 0.0);
+        assert!((check.upper_bound - z2 / (100.0 + z2)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn counts_flights_outside() {
+        let check = RangeCheck::new(&results(&[(20.0, 20.0), (80.0, 80.0)]), &field());
+
+        assert_eq!(check.outside, vec![false, true]);
+        assert_eq!(check.probability, 0.5);
+        assert!(check.upper_bound > 0.5 && check.upper_bound < 1.0);
+
+        let check = RangeCheck::new(&results(&[(80.0, 80.0); 10]), &field());
+        assert!((check.upper_bound - 1.0).abs() < 1e-12);
+
+        let check = RangeCheck::new(&results(&[]), &field());
+        assert_eq!((check.probability, check.upper_bound), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,761 @@
+use std::collections::VecDeque;
+
+use cgmath::{InnerSpace, Quaternion, Rotation, Vector3, Zero};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    atmosphere::{standard_atmosphere, STANDARD_GRAVITY},
+    export::FlightRecord,
+    geodesy::Geodetic,
+    vehicle::{Deployment, Vehicle},
+};
+
+/// Everything needed to run a single flight
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub vehicle: Vehicle,
+    pub launch: LaunchConditions,
+    pub settings: SimulationSettings,
+}
+
+/// The launch rail and the weather at the launch site
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LaunchConditions {
+    /// Length of the launch rail (m)
+    pub rail_length: f64,
+    /// Angle of the launch rail away from vertical (rad)
+    pub rail_angle: f64,
+    /// Compass direction the launch rail is tilted towards, clockwise from north (rad)
+    pub rail_heading: f64,
+    /// Speed of the wind (m/s)
+    pub wind_speed: f64,
+    /// Compass direction the wind blows from, clockwise from north (rad)
+    pub wind_direction: f64,
+    /// Altitude of the launch site above mean sea level (m)
+    pub site_altitude: f64,
+    /// Geodetic latitude of the launch site, positive north (rad)
+    pub latitude: f64,
+    /// Longitude of the launch site, positive east (rad)
+    pub longitude: f64,
+}
+
+impl Default for LaunchConditions {
+    fn default() -> Self {
+        Self {
+            rail_length: 1.5,
+            rail_angle: 5f64.to_radians(),
+            rail_heading: 0.0,
+            wind_speed: 3.0,
+            wind_direction: 270f64.to_radians(),
+            site_altitude: 0.0,
+            latitude: 0.0,
+            longitude: 0.0,
+        }
+    }
+}
+
+impl LaunchConditions {
+    /// Unit vector pointing up the launch rail
+    pub fn rail_direction(&self) -> Vector3<f64> {
+        Vector3::new(
+            self.rail_angle.sin() * self.rail_heading.sin(),
+            self.rail_angle.sin() * self.rail_heading.cos(),
+            self.rail_angle.cos(),
+        )
+    }
+
+    /// Where the base of the launch rail is, treating the site altitude as a
+    /// height above the WGS84 ellipsoid
+    pub fn site(&self) -> Geodetic {
+        Geodetic {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            altitude: self.site_altitude,
+        }
+    }
+
+    /// Velocity of the air relative to the ground (m/s)
+    pub fn wind(&self) -> Vector3<f64> {
+        -Vector3::new(self.wind_direction.sin(), self.wind_direction.cos(), 0.0) * self.wind_speed
+    }
+}
+
+/// Parameters of the numerical integration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimulationSettings {
+    /// Integrator time step (s)
+    pub time_step: f64,
+    /// Simulated time after which the flight is abandoned (s)
+    pub max_time: f64,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            time_step: 0.01,
+            max_time: 600.0,
+        }
+    }
+}
+
+/// Simulation settings with which a flight would never finish
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum SettingsError {
+    #[error("the time step must be a positive number of seconds, not {0}")]
+    TimeStep(f64),
+    #[error("the time limit must be a positive number of seconds, not {0}")]
+    MaxTime(f64),
+}
+
+impl SimulationSettings {
+    /// Check that the time step and limit are positive and finite
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !self.time_step.is_finite() || self.time_step <= 0.0 {
+            return Err(SettingsError::TimeStep(self.time_step));
+        }
+        if !self.max_time.is_finite() || self.max_time <= 0.0 {
+            return Err(SettingsError::MaxTime(self.max_time));
+        }
+
+        Ok(())
+    }
+}
+
+/// The part of the flight the vehicle is currently in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlightPhase {
+    /// Guided by the launch rail
+    Rail,
+    /// Free flight under thrust
+    Boost,
+    /// Free flight after burnout
+    Coast,
+    /// Descending with at least one recovery device deployed
+    Recovery,
+    /// On the ground
+    Landed,
+}
+
+/// A discrete event that happened during the flight
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FlightEvent {
+    /// Time since ignition (s)
+    pub time: f64,
+    pub kind: FlightEventKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlightEventKind {
+    Ignition,
+    RailExit,
+    Burnout,
+    Apogee,
+    /// Deployment of the recovery device with the given index
+    Deployment(usize),
+    Landing,
+}
+
+/// A snapshot of the vehicle state after an integrator step. Positions are in
+/// a local east-north-up frame centered on the base of the launch rail.
+#[derive(Debug, Clone, Copy)]
+pub struct FlightSample {
+    /// Time since ignition (s)
+    pub time: f64,
+    pub phase: FlightPhase,
+    /// Position (m)
+    pub position: Vector3<f64>,
+    /// Velocity relative to the ground (m/s)
+    pub velocity: Vector3<f64>,
+    /// Acceleration (m/s²)
+    pub acceleration: Vector3<f64>,
+    /// Rotation from the body frame, whose z axis points out the nose, to the world frame
+    pub attitude: Quaternion<f64>,
+    /// Speed relative to the air divided by the local speed of sound
+    pub mach: f64,
+    /// Thrust (N)
+    pub thrust: f64,
+    /// Aerodynamic drag (N)
+    pub drag: f64,
+    /// Drag coefficient
+    pub drag_coefficient: f64,
+    /// Angle between the body axis and the relative airflow (rad)
+    pub angle_of_attack: f64,
+    /// Mass (kg)
+    pub mass: f64,
+    /// Center of gravity from the nose tip (m)
+    pub cg: f64,
+    /// Center of pressure from the nose tip (m)
+    pub cp: f64,
+    /// Static stability margin (calibers)
+    pub stability: f64,
+}
+
+impl FlightSample {
+    /// Height above the launch site (m)
+    pub fn altitude(&self) -> f64 {
+        self.position.z
+    }
+
+    /// Magnitude of the velocity (m/s)
+    pub fn speed(&self) -> f64 {
+        self.velocity.magnitude()
+    }
+}
+
+/// Position, velocity and orientation of the vehicle being integrated
+#[derive(Debug, Clone, Copy)]
+struct State {
+    position: Vector3<f64>,
+    velocity: Vector3<f64>,
+    attitude: Quaternion<f64>,
+    /// Angular velocity in the body frame (rad/s)
+    angular_velocity: Vector3<f64>,
+}
+
+/// Time derivative of a [`State`]
+#[derive(Debug, Clone, Copy)]
+struct Derivative {
+    velocity: Vector3<f64>,
+    acceleration: Vector3<f64>,
+    attitude: Quaternion<f64>,
+    angular_acceleration: Vector3<f64>,
+}
+
+impl State {
+    fn advance(&self, derivative: &Derivative, dt: f64) -> Self {
+        Self {
+            position: self.position + derivative.velocity * dt,
+            velocity: self.velocity + derivative.acceleration * dt,
+            attitude: self.attitude + derivative.attitude * dt,
+            angular_velocity: self.angular_velocity + derivative.angular_acceleration * dt,
+        }
+    }
+}
+
+/// Forces and flight conditions evaluated at a state
+#[derive(Debug, Clone, Copy)]
+struct Evaluation {
+    derivative: Derivative,
+    mach: f64,
+    thrust: f64,
+    drag: f64,
+    drag_coefficient: f64,
+    angle_of_attack: f64,
+    mass: f64,
+    cg: f64,
+}
+
+/// A single flight of a vehicle, advanced one integrator step at a time
+pub struct Simulation {
+    config: SimulationConfig,
+    time: f64,
+    state: State,
+    phase: FlightPhase,
+    events: Vec<FlightEvent>,
+    deployed: Vec<bool>,
+    apogee: Option<f64>,
+    complete: bool,
+}
+
+impl Simulation {
+    /// Simulation of a flight from ignition, which fails if the settings would
+    /// never let the flight finish
+    pub fn new(config: SimulationConfig) -> Result<Self, SettingsError> {
+        config.settings.validate()?;
+
+        let attitude = Quaternion::from_arc(
+            Vector3::unit_z(),
+            config.launch.rail_direction(),
+            Some(Vector3::unit_x()),
+        );
+
+        Ok(Self {
+            deployed: vec![false; config.vehicle.recovery.len()],
+            config,
+            time: 0.0,
+            state: State {
+                position: Vector3::zero(),
+                velocity: Vector3::zero(),
+                attitude,
+                angular_velocity: Vector3::zero(),
+            },
+            phase: FlightPhase::Rail,
+            events: vec![FlightEvent {
+                time: 0.0,
+                kind: FlightEventKind::Ignition,
+            }],
+            apogee: None,
+            complete: false,
+        })
+    }
+
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Time since ignition (s)
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Events that have happened so far, in order
+    pub fn events(&self) -> &[FlightEvent] {
+        &self.events
+    }
+
+    /// Whether the vehicle has landed or the time limit has been reached
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Sample of the current state, without advancing the simulation
+    pub fn sample(&self) -> FlightSample {
+        let evaluation = self.evaluate(self.time, &self.state);
+
+        FlightSample {
+            time: self.time,
+            phase: self.phase,
+            position: self.state.position,
+            velocity: self.state.velocity,
+            acceleration: evaluation.derivative.acceleration,
+            attitude: self.state.attitude,
+            mach: evaluation.mach,
+            thrust: evaluation.thrust,
+            drag: evaluation.drag,
+            drag_coefficient: evaluation.drag_coefficient,
+            angle_of_attack: evaluation.angle_of_attack,
+            mass: evaluation.mass,
+            cg: evaluation.cg,
+            cp: self.config.vehicle.cp,
+            stability: self.config.vehicle.stability_margin(self.time),
+        }
+    }
+
+    /// Advance the simulation by one time step using fourth order Runge-Kutta
+    /// integration, returning the sample at the end of the step
+    pub fn step(&mut self) -> FlightSample {
+        if self.complete {
+            return self.sample();
+        }
+
+        let dt = self.config.settings.time_step;
+        let t = self.time;
+        let state = self.state;
+
+        let k1 = self.evaluate(t, &state).derivative;
+        let k2 = self
+            .evaluate(t + dt / 2.0, &state.advance(&k1, dt / 2.0))
+            .derivative;
+        let k3 = self
+            .evaluate(t + dt / 2.0, &state.advance(&k2, dt / 2.0))
+            .derivative;
+        let k4 = self.evaluate(t + dt, &state.advance(&k3, dt)).derivative;
+
+        let previous = self.state;
+
+        self.state = State {
+            position: state.position
+                + (k1.velocity + k2.velocity * 2.0 + k3.velocity * 2.0 + k4.velocity) * dt / 6.0,
+            velocity: state.velocity
+                + (k1.acceleration
+                    + k2.acceleration * 2.0
+                    + k3.acceleration * 2.0
+                    + k4.acceleration)
+                    * dt
+                    / 6.0,
+            attitude: (state.attitude
+                + (k1.attitude + k2.attitude * 2.0 + k3.attitude * 2.0 + k4.attitude) * (dt / 6.0))
+                .normalize(),
+            angular_velocity: state.angular_velocity
+                + (k1.angular_acceleration
+                    + k2.angular_acceleration * 2.0
+                    + k3.angular_acceleration * 2.0
+                    + k4.angular_acceleration)
+                    * dt
+                    / 6.0,
+        };
+        self.time += dt;
+
+        self.detect_events(&previous);
+
+        self.sample()
+    }
+
+    /// Run the rest of the flight, returning every sample from the current
+    /// state onwards along with the events
+    pub fn run_to_completion(&mut self) -> FlightRecord {
+        let mut samples = vec![self.sample()];
+
+        while !self.complete {
+            samples.push(self.step());
+        }
+
+        FlightRecord::new(self, samples)
+    }
+
+    /// Iterate over the rest of the flight, starting with the events so far
+    /// and the current state, then one integrator step at a time
+    pub fn updates(&mut self) -> Updates<'_> {
+        Updates {
+            simulation: self,
+            pending: VecDeque::new(),
+            reported: 0,
+            started: false,
+        }
+    }
+
+    fn push_event(&mut self, kind: FlightEventKind) {
+        self.events.push(FlightEvent {
+            time: self.time,
+            kind,
+        });
+    }
+
+    fn detect_events(&mut self, previous: &State) {
+        let vehicle = &self.config.vehicle;
+        let burnt_out = self.time >= vehicle.burn_time();
+
+        if self.phase == FlightPhase::Rail {
+            let rail = self.config.launch.rail_direction();
+
+            // The pad stops a vehicle sliding back down the rail
+            if self.state.position.dot(rail) < 0.0 {
+                self.state.position = Vector3::zero();
+                self.state.velocity = Vector3::zero();
+            }
+
+            if self.state.position.magnitude() >= self.config.launch.rail_length {
+                self.phase = if burnt_out {
+                    FlightPhase::Coast
+                } else {
+                    FlightPhase::Boost
+                };
+                self.push_event(FlightEventKind::RailExit);
+            } else if burnt_out && self.state.velocity.dot(rail) <= 0.0 {
+                // The motor never produced enough thrust to leave the rail
+                self.phase = FlightPhase::Landed;
+                self.complete = true;
+                self.push_event(FlightEventKind::Landing);
+
+                return;
+            }
+        }
+
+        if burnt_out
+            && !self
+                .events
+                .iter()
+                .any(|event| event.kind == FlightEventKind::Burnout)
+        {
+            self.push_event(FlightEventKind::Burnout);
+
+            if self.phase == FlightPhase::Boost {
+                self.phase = FlightPhase::Coast;
+            }
+        }
+
+        if self.phase != FlightPhase::Rail
+            && self.apogee.is_none()
+            && previous.velocity.z > 0.0
+            && self.state.velocity.z <= 0.0
+        {
+            self.apogee = Some(self.time);
+            self.push_event(FlightEventKind::Apogee);
+        }
+
+        for index in 0..self.deployed.len() {
+            if self.deployed[index] {
+                continue;
+            }
+
+            let deploy = match (self.config.vehicle.recovery[index].deployment, self.apogee) {
+                (Deployment::Apogee { delay }, Some(apogee)) => self.time >= apogee + delay,
+                (Deployment::Altitude(altitude), Some(_)) => self.state.position.z <= altitude,
+                (_, None) => false,
+            };
+
+            if deploy {
+                self.deployed[index] = true;
+                self.phase = FlightPhase::Recovery;
+                self.state.angular_velocity = Vector3::zero();
+                self.push_event(FlightEventKind::Deployment(index));
+            }
+        }
+
+        if self.phase != FlightPhase::Rail
+            && self.state.position.z <= 0.0
+            && self.state.velocity.z < 0.0
+        {
+            // Interpolate back to the moment of impact
+            let fraction = previous.position.z / (previous.position.z - self.state.position.z);
+
+            self.time -= self.config.settings.time_step * (1.0 - fraction);
+            self.state.position =
+                previous.position + (self.state.position - previous.position) * fraction;
+            self.state.position.z = 0.0;
+            self.state.velocity = Vector3::zero();
+            self.state.angular_velocity = Vector3::zero();
+            self.phase = FlightPhase::Landed;
+            self.complete = true;
+            self.push_event(FlightEventKind::Landing);
+        } else if self.time >= self.config.settings.max_time {
+            self.complete = true;
+        }
+    }
+
+    /// Evaluate the forces acting on the vehicle in a given state
+    fn evaluate(&self, time: f64, state: &State) -> Evaluation {
+        let vehicle = &self.config.vehicle;
+        let launch = &self.config.launch;
+
+        let mass = vehicle.mass(time);
+        let cg = vehicle.cg(time);
+        let air = standard_atmosphere(launch.site_altitude + state.position.z);
+
+        let axis = state.attitude.rotate_vector(Vector3::unit_z());
+        let relative_velocity = state.velocity - launch.wind();
+        let airspeed = relative_velocity.magnitude();
+        let mach = airspeed / air.speed_of_sound;
+        let dynamic_pressure = 0.5 * air.density * airspeed * airspeed;
+        let reference_area = vehicle.reference_area();
+
+        let thrust = if self.phase == FlightPhase::Landed {
+            0.0
+        } else {
+            vehicle.thrust(time)
+        };
+        let gravity = Vector3::new(0.0, 0.0, -STANDARD_GRAVITY * mass);
+
+        let recovery_drag_area: f64 = vehicle
+            .recovery
+            .iter()
+            .zip(&self.deployed)
+            .filter(|(_, &deployed)| deployed)
+            .map(|(device, _)| device.drag_area)
+            .sum();
+        let drag_coefficient = vehicle.drag_coefficient(mach);
+
+        let (flow, angle_of_attack) = if airspeed > f64::EPSILON {
+            let flow = relative_velocity / airspeed;
+
+            (flow, flow.dot(axis).clamp(-1.0, 1.0).acos())
+        } else {
+            (axis, 0.0)
+        };
+
+        let drag = dynamic_pressure * (drag_coefficient * reference_area + recovery_drag_area);
+        let mut force = axis * thrust - flow * drag + gravity;
+        let mut moment = Vector3::zero();
+
+        // Aerodynamic normal force acting at the center of pressure, only
+        // meaningful while the airframe is flying nose first
+        let crossflow = flow - axis * flow.dot(axis);
+        if self.phase != FlightPhase::Recovery && crossflow.magnitude() > f64::EPSILON {
+            let normal_force = -crossflow.normalize()
+                * dynamic_pressure
+                * reference_area
+                * vehicle.normal_force_slope
+                * angle_of_attack.sin();
+            let lever = axis * (cg - vehicle.cp);
+
+            force += normal_force;
+            moment += lever.cross(normal_force);
+        }
+
+        // Misaligned fins push the tail sideways even at zero angle of attack
+        if self.phase != FlightPhase::Recovery
+            && vehicle.fin_misalignment != 0.0
+            && airspeed > f64::EPSILON
+        {
+            let side = state.attitude.rotate_vector(Vector3::unit_y());
+            let fin_force = side
+                * dynamic_pressure
+                * reference_area
+                * vehicle.normal_force_slope
+                * vehicle.fin_misalignment.sin();
+
+            force += fin_force;
+            moment += (axis * (cg - vehicle.cp)).cross(fin_force);
+        }
+
+        let (longitudinal_inertia, roll_inertia) = vehicle.inertia(time);
+        let world_angular_velocity = state.attitude.rotate_vector(state.angular_velocity);
+
+        // Pitch damping from the fins sweeping through the air
+        let pitch_rate = world_angular_velocity - axis * world_angular_velocity.dot(axis);
+        moment -= pitch_rate
+            * (0.5
+                * air.density
+                * airspeed
+                * reference_area
+                * vehicle.normal_force_slope
+                * (vehicle.cp - cg).powi(2));
+
+        let (acceleration, angular_acceleration) = match self.phase {
+            FlightPhase::Rail => {
+                let rail = launch.rail_direction();
+                let along_rail = force.dot(rail) / mass;
+
+                // The vehicle rests on the rail until the thrust overcomes gravity
+                if along_rail <= 0.0 && state.velocity.dot(rail) <= 0.0 {
+                    (Vector3::zero(), Vector3::zero())
+                } else {
+                    (rail * along_rail, Vector3::zero())
+                }
+            }
+            FlightPhase::Landed => (Vector3::zero(), Vector3::zero()),
+            FlightPhase::Recovery => (force / mass, Vector3::zero()),
+            FlightPhase::Boost | FlightPhase::Coast => {
+                let body_moment = state.attitude.conjugate().rotate_vector(moment);
+                let omega = state.angular_velocity;
+                let momentum = Vector3::new(
+                    longitudinal_inertia * omega.x,
+                    longitudinal_inertia * omega.y,
+                    roll_inertia * omega.z,
+                );
+                let net = body_moment - omega.cross(momentum);
+
+                (
+                    force / mass,
+                    Vector3::new(
+                        net.x / longitudinal_inertia,
+                        net.y / longitudinal_inertia,
+                        net.z / roll_inertia,
+                    ),
+                )
+            }
+        };
+
+        Evaluation {
+            derivative: Derivative {
+                velocity: state.velocity,
+                acceleration,
+                attitude: state.attitude * Quaternion::from_sv(0.0, state.angular_velocity) * 0.5,
+                angular_acceleration,
+            },
+            mach,
+            thrust,
+            drag,
+            drag_coefficient,
+            angle_of_attack,
+            mass,
+            cg,
+        }
+    }
+}
+
+/// Something that happened while advancing a [`Simulation`]
+#[derive(Debug, Clone, Copy)]
+pub enum FlightUpdate {
+    Event(FlightEvent),
+    Sample(FlightSample),
+}
+
+/// Iterator over the rest of a flight, created by [`Simulation::updates`].
+/// The events of each step are yielded before the sample at its end.
+pub struct Updates<'a> {
+    simulation: &'a mut Simulation,
+    pending: VecDeque<FlightUpdate>,
+    /// Number of the simulation's events that have been queued
+    reported: usize,
+    started: bool,
+}
+
+impl Iterator for Updates<'_> {
+    type Item = FlightUpdate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            let sample = if !self.started {
+                self.started = true;
+                self.simulation.sample()
+            } else if self.simulation.is_complete() {
+                return None;
+            } else {
+                self.simulation.step()
+            };
+
+            let events = &self.simulation.events[self.reported..];
+
+            self.reported += events.len();
+            self.pending
+                .extend(events.iter().copied().map(FlightUpdate::Event));
+            self.pending.push_back(FlightUpdate::Sample(sample));
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+
+    #[test]
+    fn rejects_settings_that_never_finish() {
+        for &(time_step, max_time) in &[
+            (0.0, 600.0),
+            (-0.01, 600.0),
+            (f64::NAN, 600.0),
+            (f64::INFINITY, 600.0),
+            (0.01, 0.0),
+            (0.01, f64::NAN),
+            (0.01, f64::INFINITY),
+        ] {
+            let mut config = Project::default().config();
+            config.settings = SimulationSettings {
+                time_step,
+                max_time,
+            };
+
+            assert!(
+                Simulation::new(config).is_err(),
+                "time step {} and limit {}",
+                time_step,
+                max_time
+            );
+        }
+    }
+
+    #[test]
+    fn flies_until_landing() {
+        let mut simulation = Simulation::new(Project::default().config()).unwrap();
+        let record = simulation.run_to_completion();
+        let kinds: Vec<_> = record.events.iter().map(|event| event.kind).collect();
+
+        assert!(simulation.is_complete());
+        assert_eq!(kinds.first(), Some(&FlightEventKind::Ignition));
+        assert_eq!(kinds.last(), Some(&FlightEventKind::Landing));
+        assert!(kinds.contains(&FlightEventKind::Apogee));
+        assert_eq!(record.samples.last().unwrap().phase, FlightPhase::Landed);
+    }
+
+    #[test]
+    fn underpowered_flight_ends_on_the_rail() {
+        let mut project = Project::default();
+        project.motor.thrust_curve = vec![(0.0, 0.0), (0.02, 30.0), (0.04, 0.0)];
+
+        let mut simulation = Simulation::new(project.config()).unwrap();
+        let record = simulation.run_to_completion();
+        let rail = project.launch.rail_direction();
+
+        assert!(simulation.is_complete());
+        assert!(simulation.time() < 10.0);
+        assert!(record
+            .events
+            .iter()
+            .all(|event| event.kind != FlightEventKind::RailExit));
+        assert_eq!(
+            record.events.last().map(|event| event.kind),
+            Some(FlightEventKind::Landing)
+        );
+        assert!(record
+            .samples
+            .iter()
+            .all(|sample| sample.position.dot(rail) >= 0.0));
+    }
+}
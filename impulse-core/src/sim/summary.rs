@@ -0,0 +1,441 @@
+use cgmath::InnerSpace;
+
+use super::motor::Motor;
+use super::simulation::{
+    FlightEvent, FlightEventKind, FlightPhase, FlightSample, SimulationConfig,
+};
+
+/// Largest difference between a motor's ejection delay and the ideal delay
+/// that is considered safe (s)
+pub const SAFE_DELAY_WINDOW: f64 = 2.0;
+
+/// Key figures of a completed flight
+#[derive(Debug, Clone)]
+pub struct FlightSummary {
+    /// Highest altitude above the launch site (m)
+    pub apogee: f64,
+    /// Time from ignition to apogee (s)
+    pub time_to_apogee: f64,
+    /// Highest speed (m/s)
+    pub max_velocity: f64,
+    /// Highest mach number
+    pub max_mach: f64,
+    /// Highest acceleration (m/s²)
+    pub max_acceleration: f64,
+    /// Speed when leaving the launch rail (m/s)
+    pub rail_exit_velocity: Option<f64>,
+    /// Static stability margin when leaving the launch rail (calibers)
+    pub rail_exit_stability: Option<f64>,
+    /// Conditions at each recovery device deployment, in the order they happened
+    pub deployments: Vec<DeploymentSummary>,
+    /// Vertical speed just before touching down (m/s)
+    pub descent_rate: Option<f64>,
+    /// Horizontal distance from the launch rail to the landing point (m)
+    pub landing_distance: Option<f64>,
+    /// Compass direction from the launch rail to the landing point, clockwise from north (rad)
+    pub landing_bearing: Option<f64>,
+    /// Time from ignition to landing, or to the end of the simulation (s)
+    pub flight_time: f64,
+    /// Comparison of the motor's ejection delays against the coast to apogee,
+    /// if the vehicle has a motor
+    pub ejection: Option<EjectionDelay>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeploymentSummary {
+    /// Name of the deployed recovery device
+    pub name: String,
+    /// Time since ignition (s)
+    pub time: f64,
+    /// Altitude above the launch site (m)
+    pub altitude: f64,
+    /// Speed at deployment (m/s)
+    pub velocity: f64,
+}
+
+/// The ejection delay of the motor that fires closest to apogee
+#[derive(Debug, Clone)]
+pub struct EjectionDelay {
+    /// Time from burnout to apogee (s)
+    pub ideal: f64,
+    /// Each delay the motor is available with, and the speed when its
+    /// ejection charge fires, if that is before landing (s, m/s)
+    pub options: Vec<(f64, Option<f64>)>,
+    /// Index into the options of the delay closest to the ideal
+    pub recommended: Option<usize>,
+}
+
+impl EjectionDelay {
+    fn new(motor: &Motor, samples: &[FlightSample], time_to_apogee: f64) -> Self {
+        let burn_time = motor.burn_time();
+        let ideal = (time_to_apogee - burn_time).max(0.0);
+
+        let options: Vec<_> = motor
+            .delays
+            .iter()
+            .map(|&delay| {
+                let time = burn_time + delay;
+                let index = samples.partition_point(|sample| sample.time < time);
+                let velocity = samples
+                    .get(index)
+                    .filter(|sample| sample.phase != FlightPhase::Landed)
+                    .map(FlightSample::speed);
+
+                (delay, velocity)
+            })
+            .collect();
+
+        // Delays are sorted, so the earlier of two equally close delays wins,
+        // which deploys while the vehicle is still slow
+        let recommended = options
+            .iter()
+            .enumerate()
+            .min_by(|(_, (a, _)), (_, (b, _))| (a - ideal).abs().total_cmp(&(b - ideal).abs()))
+            .map(|(index, _)| index);
+
+        Self {
+            ideal,
+            options,
+            recommended,
+        }
+    }
+
+    /// Recommended delay and the speed when it fires (s, m/s)
+    pub fn recommendation(&self) -> Option<(f64, Option<f64>)> {
+        self.recommended.map(|index| self.options[index])
+    }
+
+    /// Whether the recommended delay fires within [`SAFE_DELAY_WINDOW`] of
+    /// apogee
+    pub fn is_safe(&self) -> bool {
+        match self.recommendation() {
+            Some((delay, _)) => (delay - self.ideal).abs() <= SAFE_DELAY_WINDOW,
+            None => false,
+        }
+    }
+
+    /// Warning to show when no available delay fires near apogee
+    pub fn warning(&self) -> Option<String> {
+        match self.recommendation() {
+            None => None,
+            Some(_) if self.is_safe() => None,
+            Some(_) => Some(format!(
+                "no available delay is within {:.0} s of the ideal {:.1} s",
+                SAFE_DELAY_WINDOW, self.ideal
+            )),
+        }
+    }
+}
+
+impl FlightSummary {
+    /// Summarize a flight from every sample recorded during it, in
+    /// chronological order, and the events it produced
+    pub fn new(
+        config: &SimulationConfig,
+        samples: &[FlightSample],
+        events: &[FlightEvent],
+    ) -> Self {
+        let sample_at = |time: f64| {
+            let index = samples.partition_point(|sample| sample.time < time);
+
+            samples.get(index).or_else(|| samples.last())
+        };
+        let event_time = |kind: FlightEventKind| {
+            events
+                .iter()
+                .find(|event| event.kind == kind)
+                .map(|event| event.time)
+        };
+
+        // A degenerate design can produce non-finite samples, which say
+        // nothing about the apogee
+        let apogee_sample = samples
+            .iter()
+            .filter(|sample| sample.altitude().is_finite())
+            .max_by(|a, b| a.altitude().total_cmp(&b.altitude()));
+        let max = |value: fn(&FlightSample) -> f64| samples.iter().map(value).fold(0.0, f64::max);
+
+        let rail_exit = event_time(FlightEventKind::RailExit).and_then(sample_at);
+
+        let deployments = events
+            .iter()
+            .filter_map(|event| match event.kind {
+                FlightEventKind::Deployment(index) => {
+                    let sample = sample_at(event.time)?;
+
+                    Some(DeploymentSummary {
+                        name: config.vehicle.recovery[index].name.clone(),
+                        time: event.time,
+                        altitude: sample.altitude(),
+                        velocity: sample.speed(),
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        let time_to_apogee = apogee_sample.map(|sample| sample.time).unwrap_or(0.0);
+        let ejection = config
+            .vehicle
+            .motor
+            .as_ref()
+            .map(|(motor, _)| EjectionDelay::new(motor, samples, time_to_apogee));
+
+        let landing = event_time(FlightEventKind::Landing).and_then(|_| samples.last());
+        let touchdown = samples
+            .iter()
+            .rev()
+            .find(|sample| sample.phase != FlightPhase::Landed);
+
+        Self {
+            apogee: apogee_sample.map(FlightSample::altitude).unwrap_or(0.0),
+            time_to_apogee,
+            max_velocity: max(FlightSample::speed),
+            max_mach: max(|sample| sample.mach),
+            max_acceleration: max(|sample| sample.acceleration.magnitude()),
+            rail_exit_velocity: rail_exit.map(FlightSample::speed),
+            rail_exit_stability: rail_exit.map(|sample| sample.stability),
+            deployments,
+            descent_rate: landing.and(touchdown).map(|sample| -sample.velocity.z),
+            landing_distance: landing.map(|sample| sample.position.truncate().magnitude()),
+            landing_bearing: landing.map(|sample| {
+                sample
+                    .position
+                    .x
+                    .atan2(sample.position.y)
+                    .rem_euclid(std::f64::consts::TAU)
+            }),
+            flight_time: samples.last().map(|sample| sample.time).unwrap_or(0.0),
+            ejection,
+        }
+    }
+
+    /// Key figures as labels and formatted values, in the order they are shown
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let optional = |value: Option<f64>, format: fn(f64) -> String| {
+            value.map(format).unwrap_or_else(|| "N/A".into())
+        };
+
+        let mut rows = vec![
+            (
+                "Apogee".to_string(),
+                format!("{:.1} m at {:.2} s", self.apogee, self.time_to_apogee),
+            ),
+            (
+                "Max velocity".into(),
+                format!("{:.1} m/s (Mach {:.2})", self.max_velocity, self.max_mach),
+            ),
+            (
+                "Max acceleration".into(),
+                format!("{:.1} m/s²", self.max_acceleration),
+            ),
+            (
+                "Rail exit velocity".into(),
+                optional(self.rail_exit_velocity, |v| format!("{:.1} m/s", v)),
+            ),
+            (
+                "Rail exit stability".into(),
+                optional(self.rail_exit_stability, |s| format!("{:.2} cal", s)),
+            ),
+        ];
+
+        rows.extend(self.deployments.iter().map(|deployment| {
+            (
+                format!("{} deployment", deployment.name),
+                format!(
+                    "{:.1} m/s at {:.1} m",
+                    deployment.velocity, deployment.altitude
+                ),
+            )
+        }));
+
+        rows.extend(vec![
+            (
+                "Descent rate".into(),
+                optional(self.descent_rate, |v| format!("{:.1} m/s", v)),
+            ),
+            (
+                "Landing distance".into(),
+                optional(self.landing_distance, |d| format!("{:.0} m", d)),
+            ),
+            (
+                "Landing bearing".into(),
+                optional(self.landing_bearing, |b| {
+                    format!("{:.0}° {}", b.to_degrees(), compass_point(b))
+                }),
+            ),
+            ("Flight time".into(), format!("{:.1} s", self.flight_time)),
+        ]);
+
+        if let Some(ejection) = &self.ejection {
+            rows.push((
+                "Ideal ejection delay".into(),
+                format!("{:.1} s", ejection.ideal),
+            ));
+            rows.push((
+                "Recommended delay".into(),
+                match ejection.recommendation() {
+                    Some((delay, Some(velocity))) => {
+                        format!("{:.0} s, {:.1} m/s at ejection", delay, velocity)
+                    }
+                    Some((delay, None)) => format!("{:.0} s, fires after landing", delay),
+                    None => "no delays known".into(),
+                },
+            ));
+
+            if let Some(warning) = ejection.warning() {
+                rows.push(("Delay warning".into(), warning));
+            }
+        }
+
+        rows
+    }
+}
+
+/// Nearest of the eight principal compass directions to a bearing (rad)
+fn compass_point(bearing: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+    POINTS[((bearing.to_degrees() / 45.0).round() as usize) % POINTS.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Quaternion, Vector3};
+
+    use super::*;
+    use crate::{rocket::Rocket, sim::LaunchConditions, sim::SimulationSettings};
+
+    /// Ballistic flight launched at 50 m/s up and 5 m/s across, under 10 m/s²
+    /// of gravity, landing 50 m from the rail after 10 s
+    fn ballistic(motor: Option<Motor>) -> (SimulationConfig, Vec<FlightSample>, Vec<FlightEvent>) {
+        let config = SimulationConfig {
+            vehicle: Rocket::default().to_vehicle(motor),
+            launch: LaunchConditions::default(),
+            settings: SimulationSettings::default(),
+        };
+
+        let samples = (0..=20)
+            .map(|step| {
+                let time = step as f64 * 0.5;
+
+                FlightSample {
+                    time,
+                    phase: if step == 20 {
+                        FlightPhase::Landed
+                    } else {
+                        FlightPhase::Coast
+                    },
+                    position: Vector3::new(3.0 * time, 4.0 * time, 50.0 * time - 5.0 * time * time),
+                    velocity: Vector3::new(3.0, 4.0, 50.0 - 10.0 * time),
+                    acceleration: Vector3::new(0.0, 0.0, -10.0),
+                    attitude: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+                    mach: 0.1,
+                    thrust: 0.0,
+                    drag: 0.0,
+                    drag_coefficient: 0.0,
+                    angle_of_attack: 0.0,
+                    mass: 1.0,
+                    cg: 0.5,
+                    cp: 0.6,
+                    stability: 1.0,
+                }
+            })
+            .collect();
+
+        let events = vec![
+            FlightEvent {
+                time: 0.0,
+                kind: FlightEventKind::Ignition,
+            },
+            FlightEvent {
+                time: 5.0,
+                kind: FlightEventKind::Apogee,
+            },
+            FlightEvent {
+                time: 10.0,
+                kind: FlightEventKind::Landing,
+            },
+        ];
+
+        (config, samples, events)
+    }
+
+    #[test]
+    fn summarizes_ballistic_flight() {
+        let (config, samples, events) = ballistic(None);
+        let summary = FlightSummary::new(&config, &samples, &events);
+
+        assert!((summary.apogee - 125.0).abs() < 1e-9);
+        assert!((summary.time_to_apogee - 5.0).abs() < 1e-9);
+        assert!((summary.max_velocity - 2525f64.sqrt()).abs() < 1e-9);
+        assert!((summary.max_acceleration - 10.0).abs() < 1e-9);
+        assert!((summary.descent_rate.unwrap() - 45.0).abs() < 1e-9);
+        assert!((summary.landing_distance.unwrap() - 50.0).abs() < 1e-9);
+        assert!((summary.landing_bearing.unwrap() - 3f64.atan2(4.0)).abs() < 1e-9);
+        assert!((summary.flight_time - 10.0).abs() < 1e-9);
+        assert!(summary.rail_exit_velocity.is_none());
+        assert!(summary.ejection.is_none());
+    }
+
+    #[test]
+    fn unlanded_flight_has_no_landing() {
+        let (config, samples, mut events) = ballistic(None);
+        events.pop();
+        let summary = FlightSummary::new(&config, &samples[..15], &events);
+
+        assert!(summary.landing_distance.is_none());
+        assert!(summary.descent_rate.is_none());
+        assert!((summary.flight_time - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_non_finite_altitudes() {
+        let (config, mut samples, events) = ballistic(None);
+        samples[3].position = Vector3::new(f64::NAN, f64::NAN, f64::NAN);
+        samples[4].position.z = f64::INFINITY;
+        let summary = FlightSummary::new(&config, &samples, &events);
+
+        assert!((summary.apogee - 125.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_flight() {
+        let (config, _, _) = ballistic(None);
+        let summary = FlightSummary::new(&config, &[], &[]);
+
+        assert_eq!(summary.apogee, 0.0);
+        assert_eq!(summary.flight_time, 0.0);
+        assert!(summary.landing_distance.is_none());
+    }
+
+    #[test]
+    fn recommends_closest_delay() {
+        let (config, samples, events) = ballistic(Some(Motor::demo()));
+        let ejection = FlightSummary::new(&config, &samples, &events)
+            .ejection
+            .unwrap();
+
+        // Burnout at 1.5 s and apogee at 5 s
+        assert!((ejection.ideal - 3.5).abs() < 1e-9);
+        assert_eq!(ejection.recommendation().map(|(delay, _)| delay), Some(6.0));
+        assert!(!ejection.is_safe());
+        assert!(ejection.warning().is_some());
+
+        // The 14 s delay fires after landing
+        assert_eq!(ejection.options[2].1, None);
+    }
+
+    #[test]
+    fn ignores_non_finite_delays() {
+        let mut motor = Motor::demo();
+        motor.delays = vec![f64::NAN, 4.0];
+        let (config, samples, events) = ballistic(Some(motor));
+        let ejection = FlightSummary::new(&config, &samples, &events)
+            .ejection
+            .unwrap();
+
+        assert_eq!(ejection.recommendation().map(|(delay, _)| delay), Some(4.0));
+        assert!(ejection.is_safe());
+    }
+}
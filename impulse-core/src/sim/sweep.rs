@@ -0,0 +1,600 @@
+//! Parameter sweeps, flying a grid of variations of a flight to plot how its
+//! key figures respond, and optimization of a single parameter to hit a
+//! target figure
+
+use std::fmt::{self, Display};
+
+use rayon::prelude::*;
+
+use super::{
+    simulation::{SettingsError, Simulation, SimulationConfig},
+    summary::FlightSummary,
+    vehicle::Deployment,
+};
+
+/// Inverse of the golden ratio, by which golden section search narrows its
+/// interval each step
+const INVERSE_PHI: f64 = 0.618_033_988_749_894_9;
+
+/// Width of the final interval of an optimization, relative to the range
+/// searched
+const RELATIVE_TOLERANCE: f64 = 1e-3;
+
+/// A value of the flight that sweeps and optimizations vary. Values are in SI
+/// units and replace the nominal value rather than adding to it, except for
+/// nose weight which is added to the design.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SweepParameter {
+    /// Mass added at the nose (kg)
+    NoseWeight,
+    /// Delay after apogee of devices deployed at apogee (s)
+    DeployDelay,
+    /// Altitude of devices deployed at an altitude (m)
+    DeployAltitude,
+    /// Angle of the launch rail from vertical (rad)
+    RailAngle,
+    /// Wind speed (m/s)
+    WindSpeed,
+}
+
+impl SweepParameter {
+    pub const ALL: [SweepParameter; 5] = [
+        SweepParameter::NoseWeight,
+        SweepParameter::DeployDelay,
+        SweepParameter::DeployAltitude,
+        SweepParameter::RailAngle,
+        SweepParameter::WindSpeed,
+    ];
+}
+
+impl Display for SweepParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SweepParameter::NoseWeight => "Nose weight",
+            SweepParameter::DeployDelay => "Deployment delay",
+            SweepParameter::DeployAltitude => "Deployment altitude",
+            SweepParameter::RailAngle => "Rail angle",
+            SweepParameter::WindSpeed => "Wind speed",
+        })
+    }
+}
+
+/// A key figure of a flight that sweeps plot and optimizations aim for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    Apogee,
+    MaxVelocity,
+    /// Speed at the first recovery device deployment
+    DeployVelocity,
+    RailExitStability,
+    DescentRate,
+    LandingDistance,
+    FlightTime,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 7] = [
+        Metric::Apogee,
+        Metric::MaxVelocity,
+        Metric::DeployVelocity,
+        Metric::RailExitStability,
+        Metric::DescentRate,
+        Metric::LandingDistance,
+        Metric::FlightTime,
+    ];
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Metric::Apogee | Metric::LandingDistance => "m",
+            Metric::MaxVelocity | Metric::DeployVelocity | Metric::DescentRate => "m/s",
+            Metric::RailExitStability => "cal",
+            Metric::FlightTime => "s",
+        }
+    }
+
+    /// Value of the figure for a flight, if it happened
+    pub fn value(self, summary: &FlightSummary) -> Option<f64> {
+        match self {
+            Metric::Apogee => Some(summary.apogee),
+            Metric::MaxVelocity => Some(summary.max_velocity),
+            Metric::DeployVelocity => summary
+                .deployments
+                .first()
+                .map(|deployment| deployment.velocity),
+            Metric::RailExitStability => summary.rail_exit_stability,
+            Metric::DescentRate => summary.descent_rate,
+            Metric::LandingDistance => summary.landing_distance,
+            Metric::FlightTime => Some(summary.flight_time),
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metric::Apogee => "Apogee",
+            Metric::MaxVelocity => "Max velocity",
+            Metric::DeployVelocity => "Deployment velocity",
+            Metric::RailExitStability => "Rail exit stability",
+            Metric::DescentRate => "Descent rate",
+            Metric::LandingDistance => "Landing distance",
+            Metric::FlightTime => "Flight time",
+        })
+    }
+}
+
+/// The nominal flight that sweeps and optimizations vary
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    config: SimulationConfig,
+    /// Where nose weight is added, from the nose tip (m)
+    nose_weight_position: f64,
+}
+
+impl Baseline {
+    /// Baseline of a configuration, which fails if its settings would never
+    /// let a flight finish
+    pub fn new(config: SimulationConfig, nose_weight_position: f64) -> Result<Self, SettingsError> {
+        config.settings.validate()?;
+
+        Ok(Self {
+            config,
+            nose_weight_position,
+        })
+    }
+
+    /// Configuration of the flight with parameters set to the given values
+    pub fn configure(&self, values: &[(SweepParameter, f64)]) -> SimulationConfig {
+        let mut config = self.config.clone();
+
+        for &(parameter, value) in values {
+            match parameter {
+                SweepParameter::NoseWeight => config
+                    .vehicle
+                    .add_point_mass(value, self.nose_weight_position),
+                SweepParameter::DeployDelay | SweepParameter::DeployAltitude => {
+                    for device in &mut config.vehicle.recovery {
+                        match (&mut device.deployment, parameter) {
+                            (Deployment::Apogee { delay }, SweepParameter::DeployDelay) => {
+                                *delay = value
+                            }
+                            (Deployment::Altitude(altitude), SweepParameter::DeployAltitude) => {
+                                *altitude = value
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                SweepParameter::RailAngle => config.launch.rail_angle = value,
+                SweepParameter::WindSpeed => config.launch.wind_speed = value,
+            }
+        }
+
+        config
+    }
+
+    /// Simulate the flight with parameters set to the given values
+    pub fn fly(&self, values: &[(SweepParameter, f64)]) -> FlightSummary {
+        let mut simulation =
+            Simulation::new(self.configure(values)).expect("settings are checked by new");
+        let record = simulation.run_to_completion();
+
+        FlightSummary::new(simulation.config(), &record.samples, &record.events)
+    }
+}
+
+/// Evenly spaced values of a parameter, including both ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Axis {
+    pub parameter: SweepParameter,
+    pub start: f64,
+    pub end: f64,
+    /// Number of values, at least two
+    pub steps: usize,
+}
+
+impl Axis {
+    pub fn value(&self, index: usize) -> f64 {
+        let steps = self.steps.max(2);
+
+        self.start + (self.end - self.start) * index as f64 / (steps - 1) as f64
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.steps.max(2)).map(move |index| self.value(index))
+    }
+}
+
+/// A sweep over one or two parameters in progress, simulating its flights in
+/// batches so that the caller can report progress and stop early
+pub struct Sweep {
+    baseline: Baseline,
+    x: Axis,
+    y: Option<Axis>,
+    summaries: Vec<FlightSummary>,
+}
+
+impl Sweep {
+    pub fn new(baseline: Baseline, x: Axis, y: Option<Axis>) -> Self {
+        Self {
+            baseline,
+            x,
+            y,
+            summaries: Vec::new(),
+        }
+    }
+
+    /// Number of flights simulated so far
+    pub fn completed(&self) -> usize {
+        self.summaries.len()
+    }
+
+    /// Number of flights in the sweep
+    pub fn total(&self) -> usize {
+        self.x.steps.max(2) * self.y.map_or(1, |y| y.steps.max(2))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed() >= self.total()
+    }
+
+    /// Parameter values of the flight with the given index, with the first
+    /// parameter varying fastest
+    fn values(&self, index: usize) -> Vec<(SweepParameter, f64)> {
+        let columns = self.x.steps.max(2);
+        let mut values = vec![(self.x.parameter, self.x.value(index % columns))];
+
+        if let Some(y) = &self.y {
+            values.push((y.parameter, y.value(index / columns)));
+        }
+
+        values
+    }
+
+    /// Simulate up to `count` more flights in parallel
+    pub fn run_batch(&mut self, count: usize) {
+        let start = self.completed();
+        let end = (start + count).min(self.total());
+
+        let batch: Vec<_> = (start..end)
+            .into_par_iter()
+            .map(|index| self.baseline.fly(&self.values(index)))
+            .collect();
+
+        self.summaries.extend(batch);
+    }
+
+    /// Simulate every remaining flight
+    pub fn run_to_completion(mut self) -> SweepResults {
+        self.run_batch(self.total());
+
+        self.results()
+    }
+
+    /// Results of the flights simulated so far
+    pub fn results(&self) -> SweepResults {
+        SweepResults {
+            x: self.x,
+            y: self.y,
+            summaries: self.summaries.clone(),
+        }
+    }
+}
+
+/// Summaries of the flights of a sweep
+#[derive(Debug, Clone)]
+pub struct SweepResults {
+    pub x: Axis,
+    pub y: Option<Axis>,
+    /// Summary of each flight, with the first parameter varying fastest
+    pub summaries: Vec<FlightSummary>,
+}
+
+impl SweepResults {
+    /// Value of a figure of the flight at the given step of each parameter
+    pub fn value(&self, metric: Metric, x: usize, y: usize) -> Option<f64> {
+        self.summaries
+            .get(y * self.x.steps.max(2) + x)
+            .and_then(|summary| metric.value(summary))
+    }
+
+    /// Smallest and largest value of a figure across the sweep
+    pub fn range(&self, metric: Metric) -> Option<(f64, f64)> {
+        self.summaries
+            .iter()
+            .filter_map(|summary| metric.value(summary))
+            .fold(None, |range, value| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
+    }
+}
+
+/// What an optimization aims for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Goal {
+    /// Get as close as possible to a value
+    Target(f64),
+    Maximize,
+    Minimize,
+}
+
+impl Goal {
+    /// Cost of a value of the figure, which the optimization minimizes
+    fn cost(self, value: Option<f64>) -> f64 {
+        match (self, value) {
+            (Goal::Target(target), Some(value)) => (value - target).abs(),
+            (Goal::Maximize, Some(value)) => -value,
+            (Goal::Minimize, Some(value)) => value,
+            (_, None) => f64::INFINITY,
+        }
+    }
+}
+
+/// Best value of a parameter found by an optimization
+#[derive(Debug, Clone)]
+pub struct Optimum {
+    pub parameter: SweepParameter,
+    pub value: f64,
+    pub metric: Metric,
+    /// Value of the figure at the best value of the parameter
+    pub metric_value: Option<f64>,
+    /// Every parameter value tried, and the figure it gave, in order
+    pub evaluations: Vec<(f64, Option<f64>)>,
+}
+
+/// Golden section search for the value of one parameter within a range that
+/// best meets a goal, one flight per step. The cost must have a single
+/// minimum within the range, as it does when aiming for a figure that rises
+/// or falls steadily with the parameter.
+pub struct Optimizer {
+    baseline: Baseline,
+    parameter: SweepParameter,
+    metric: Metric,
+    goal: Goal,
+    /// Interval known to contain the minimum
+    bounds: (f64, f64),
+    /// Interior points and their costs, once flown
+    lower: (f64, Option<f64>),
+    upper: (f64, Option<f64>),
+    tolerance: f64,
+    evaluations: Vec<(f64, Option<f64>)>,
+}
+
+impl Optimizer {
+    pub fn new(
+        baseline: Baseline,
+        parameter: SweepParameter,
+        range: (f64, f64),
+        metric: Metric,
+        goal: Goal,
+    ) -> Self {
+        let (start, end) = (range.0.min(range.1), range.0.max(range.1));
+        let width = end - start;
+
+        Self {
+            baseline,
+            parameter,
+            metric,
+            goal,
+            bounds: (start, end),
+            lower: (end - INVERSE_PHI * width, None),
+            upper: (start + INVERSE_PHI * width, None),
+            tolerance: width * RELATIVE_TOLERANCE,
+            evaluations: Vec::new(),
+        }
+    }
+
+    /// Number of flights simulated so far
+    pub fn completed(&self) -> usize {
+        self.evaluations.len()
+    }
+
+    /// Number of flights needed to narrow the range to the tolerance
+    pub fn total(&self) -> usize {
+        // Two flights to start, then one per step
+        2 + (RELATIVE_TOLERANCE.ln() / INVERSE_PHI.ln()).ceil() as usize
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.bounds.1 - self.bounds.0 <= self.tolerance
+    }
+
+    /// Fly a parameter value, returning its cost
+    fn evaluate(&mut self, value: f64) -> f64 {
+        let result = self
+            .metric
+            .value(&self.baseline.fly(&[(self.parameter, value)]));
+
+        self.evaluations.push((value, result));
+        self.goal.cost(result)
+    }
+
+    /// Narrow the range by one flight
+    pub fn step(&mut self) {
+        if self.is_complete() {
+            return;
+        }
+
+        let lower_cost = match self.lower.1 {
+            Some(cost) => cost,
+            None => {
+                let cost = self.evaluate(self.lower.0);
+                self.lower.1 = Some(cost);
+
+                return;
+            }
+        };
+        let upper_cost = match self.upper.1 {
+            Some(cost) => cost,
+            None => {
+                let cost = self.evaluate(self.upper.0);
+                self.upper.1 = Some(cost);
+
+                return;
+            }
+        };
+
+        if lower_cost <= upper_cost {
+            // The minimum is below the upper point, which the lower point
+            // takes the place of
+            self.bounds.1 = self.upper.0;
+            self.upper = self.lower;
+
+            let value = self.bounds.1 - INVERSE_PHI * (self.bounds.1 - self.bounds.0);
+            let cost = self.evaluate(value);
+            self.lower = (value, Some(cost));
+        } else {
+            self.bounds.0 = self.lower.0;
+            self.lower = self.upper;
+
+            let value = self.bounds.0 + INVERSE_PHI * (self.bounds.1 - self.bounds.0);
+            let cost = self.evaluate(value);
+            self.upper = (value, Some(cost));
+        }
+    }
+
+    /// Step until the range is narrowed to the tolerance
+    pub fn run_to_completion(mut self) -> Optimum {
+        while !self.is_complete() {
+            self.step();
+        }
+
+        self.result()
+    }
+
+    /// Best parameter value tried so far
+    pub fn result(&self) -> Optimum {
+        let goal = self.goal;
+        let best = self.evaluations.iter().min_by(|a, b| {
+            goal.cost(a.1)
+                .partial_cmp(&goal.cost(b.1))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Optimum {
+            parameter: self.parameter,
+            value: best.map_or((self.bounds.0 + self.bounds.1) / 2.0, |best| best.0),
+            metric: self.metric,
+            metric_value: best.and_then(|best| best.1),
+            evaluations: self.evaluations.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+
+    /// Baseline of the default project, flown with coarse steps to keep the
+    /// tests quick
+    fn baseline() -> Baseline {
+        let mut project = Project::default();
+        project.settings.time_step = 0.05;
+
+        Baseline::new(project.config(), project.rocket.nose_weight_position()).unwrap()
+    }
+
+    fn axis(parameter: SweepParameter, start: f64, end: f64, steps: usize) -> Axis {
+        Axis {
+            parameter,
+            start,
+            end,
+            steps,
+        }
+    }
+
+    #[test]
+    fn axes_include_both_ends() {
+        let values: Vec<_> = axis(SweepParameter::WindSpeed, 2.0, 4.0, 5)
+            .values()
+            .collect();
+
+        assert_eq!(values, vec![2.0, 2.5, 3.0, 3.5, 4.0]);
+        assert_eq!(
+            axis(SweepParameter::WindSpeed, 2.0, 4.0, 0)
+                .values()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn varies_x_fastest() {
+        let x = axis(SweepParameter::WindSpeed, 0.0, 8.0, 3);
+        let y = axis(SweepParameter::RailAngle, 0.0, 0.1, 2);
+        let sweep = Sweep::new(baseline(), x, Some(y));
+
+        assert_eq!(sweep.total(), 6);
+        assert_eq!(
+            (0..sweep.total())
+                .map(|index| sweep.values(index))
+                .collect::<Vec<_>>(),
+            [
+                (0.0, 0.0),
+                (4.0, 0.0),
+                (8.0, 0.0),
+                (0.0, 0.1),
+                (4.0, 0.1),
+                (8.0, 0.1)
+            ]
+            .iter()
+            .map(|&(wind, angle)| vec![
+                (SweepParameter::WindSpeed, wind),
+                (SweepParameter::RailAngle, angle)
+            ])
+            .collect::<Vec<_>>()
+        );
+
+        let results = sweep.run_to_completion();
+        let flown = baseline().fly(&[
+            (SweepParameter::WindSpeed, 8.0),
+            (SweepParameter::RailAngle, 0.0),
+        ]);
+
+        assert_eq!(
+            results.value(Metric::LandingDistance, 2, 0),
+            flown.landing_distance
+        );
+        assert_ne!(
+            results.value(Metric::LandingDistance, 0, 1),
+            flown.landing_distance
+        );
+    }
+
+    #[test]
+    fn costs_meet_goals() {
+        assert_eq!(Goal::Target(100.0).cost(Some(90.0)), 10.0);
+        assert_eq!(Goal::Target(100.0).cost(Some(110.0)), 10.0);
+        assert!(Goal::Maximize.cost(Some(2.0)) < Goal::Maximize.cost(Some(1.0)));
+        assert!(Goal::Minimize.cost(Some(1.0)) < Goal::Minimize.cost(Some(2.0)));
+        assert_eq!(Goal::Minimize.cost(None), f64::INFINITY);
+    }
+
+    #[test]
+    fn finds_nose_weight_for_apogee() {
+        let nose_weight = 0.1;
+        let target = baseline()
+            .fly(&[(SweepParameter::NoseWeight, nose_weight)])
+            .apogee;
+
+        let optimizer = Optimizer::new(
+            baseline(),
+            SweepParameter::NoseWeight,
+            (0.3, 0.0),
+            Metric::Apogee,
+            Goal::Target(target),
+        );
+        let total = optimizer.total();
+        let optimum = optimizer.run_to_completion();
+
+        assert!(optimum.evaluations.len() <= total);
+        assert!(
+            (optimum.value - nose_weight).abs() < 0.3 * RELATIVE_TOLERANCE * 2.0,
+            "found {} kg",
+            optimum.value
+        );
+        assert!((optimum.metric_value.unwrap() - target).abs() < 0.5);
+    }
+}
@@ -0,0 +1,463 @@
+//! Live telemetry of a running simulation, streamed to other processes such
+//! as the 3D visualization
+//!
+//! # Frame format
+//!
+//! Telemetry is sent over TCP, normally on localhost, as frames of UTF-8 JSON
+//! with one frame per line. Each frame is an object whose `type` field tells
+//! what it holds:
+//!
+//! - `start` begins a flight. It holds the protocol `version`, which is
+//!   [`TELEMETRY_VERSION`], and the `rocket`, `motor`, `launch` conditions and
+//!   simulation `settings` being flown, laid out as in project files.
+//! - `event` is a [`FlightEvent`], with its `time` and its `kind` such as
+//!   `"apogee"` or `{"deployment": 0}`.
+//! - `sample` is the state of the vehicle after an integrator step, holding
+//!   the fields of a [`FlightSample`] in SI units and radians. Vectors are
+//!   `[east, north, up]` arrays in the frame of the launch site, and the
+//!   `attitude` is a `[w, x, y, z]` quaternion.
+//! - `end` follows the last sample of a flight.
+//!
+//! The events of each integrator step are sent before its sample:
+//!
+//! ```text
+//! {"type":"start","version":1,"rocket":{"name":"Demo",...},"motor":{...},...}
+//! {"type":"event","time":0.0,"kind":"ignition"}
+//! {"type":"sample","time":0.0,"phase":"rail","position":[0.0,0.0,0.0],...}
+//! ...
+//! {"type":"end"}
+//! ```
+//!
+//! Subscribers that connect partway through a flight are first sent every
+//! frame of it so far, so they can drop and reconnect while it is being
+//! published. Connections are only accepted as frames are published, so
+//! nothing is served once the flight has ended. Frames of types added in
+//! later versions are skipped by [`TelemetryReader`].
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use cgmath::{Quaternion, Vector3};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    motor::Motor,
+    simulation::{
+        FlightEvent, FlightPhase, FlightSample, FlightUpdate, LaunchConditions, SimulationConfig,
+        SimulationSettings,
+    },
+};
+use crate::rocket::Rocket;
+
+/// Version of the frame format, raised when frames change incompatibly
+pub const TELEMETRY_VERSION: u32 = 1;
+
+/// Address telemetry is published on unless another is chosen
+pub const DEFAULT_TELEMETRY_ADDRESS: &str = "127.0.0.1:7437";
+
+/// Longest a subscriber may hold up the simulation before it is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Error)]
+pub enum TelemetryError {
+    #[error("telemetry connection failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid telemetry frame: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(
+        "telemetry version {0} is newer than the supported version {}",
+        TELEMETRY_VERSION
+    )]
+    UnsupportedVersion(u32),
+}
+
+/// One line of the telemetry stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryFrame {
+    Start {
+        version: u32,
+        rocket: Rocket,
+        motor: Motor,
+        launch: LaunchConditions,
+        settings: SimulationSettings,
+    },
+    Event(FlightEvent),
+    Sample(TelemetrySample),
+    End,
+    /// Frame of a type added in a later version
+    #[serde(other)]
+    Unknown,
+}
+
+impl TelemetryFrame {
+    /// Frame beginning a flight of a rocket and motor
+    pub fn start(
+        rocket: Rocket,
+        motor: Motor,
+        launch: LaunchConditions,
+        settings: SimulationSettings,
+    ) -> Self {
+        TelemetryFrame::Start {
+            version: TELEMETRY_VERSION,
+            rocket,
+            motor,
+            launch,
+            settings,
+        }
+    }
+
+    /// Configuration of the flight begun by a `start` frame
+    pub fn config(&self) -> Option<SimulationConfig> {
+        match self {
+            TelemetryFrame::Start {
+                rocket,
+                motor,
+                launch,
+                settings,
+                ..
+            } => Some(SimulationConfig {
+                vehicle: rocket.to_vehicle(Some(motor.clone())),
+                launch: *launch,
+                settings: *settings,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<FlightUpdate> for TelemetryFrame {
+    fn from(update: FlightUpdate) -> Self {
+        match update {
+            FlightUpdate::Event(event) => TelemetryFrame::Event(event),
+            FlightUpdate::Sample(sample) => TelemetryFrame::Sample(sample.into()),
+        }
+    }
+}
+
+/// A [`FlightSample`] as laid out in `sample` frames
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TelemetrySample {
+    pub time: f64,
+    pub phase: FlightPhase,
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+    pub acceleration: [f64; 3],
+    pub attitude: [f64; 4],
+    pub mach: f64,
+    pub thrust: f64,
+    pub drag: f64,
+    pub drag_coefficient: f64,
+    pub angle_of_attack: f64,
+    pub mass: f64,
+    pub cg: f64,
+    pub cp: f64,
+    pub stability: f64,
+}
+
+impl From<FlightSample> for TelemetrySample {
+    fn from(sample: FlightSample) -> Self {
+        let attitude = sample.attitude;
+
+        Self {
+            time: sample.time,
+            phase: sample.phase,
+            position: sample.position.into(),
+            velocity: sample.velocity.into(),
+            acceleration: sample.acceleration.into(),
+            attitude: [attitude.s, attitude.v.x, attitude.v.y, attitude.v.z],
+            mach: sample.mach,
+            thrust: sample.thrust,
+            drag: sample.drag,
+            drag_coefficient: sample.drag_coefficient,
+            angle_of_attack: sample.angle_of_attack,
+            mass: sample.mass,
+            cg: sample.cg,
+            cp: sample.cp,
+            stability: sample.stability,
+        }
+    }
+}
+
+impl From<TelemetrySample> for FlightSample {
+    fn from(sample: TelemetrySample) -> Self {
+        let [w, x, y, z] = sample.attitude;
+
+        Self {
+            time: sample.time,
+            phase: sample.phase,
+            position: Vector3::from(sample.position),
+            velocity: Vector3::from(sample.velocity),
+            acceleration: Vector3::from(sample.acceleration),
+            attitude: Quaternion::new(w, x, y, z),
+            mach: sample.mach,
+            thrust: sample.thrust,
+            drag: sample.drag,
+            drag_coefficient: sample.drag_coefficient,
+            angle_of_attack: sample.angle_of_attack,
+            mass: sample.mass,
+            cg: sample.cg,
+            cp: sample.cp,
+            stability: sample.stability,
+        }
+    }
+}
+
+/// Sends telemetry to every subscriber connected to an address
+pub struct TelemetryPublisher {
+    listener: TcpListener,
+    subscribers: Vec<TcpStream>,
+    /// Encoded frames of the current flight, replayed to new subscribers
+    history: Vec<String>,
+}
+
+impl TelemetryPublisher {
+    /// Listen for subscribers on an address, such as
+    /// [`DEFAULT_TELEMETRY_ADDRESS`]
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self, TelemetryError> {
+        let listener = TcpListener::bind(address)?;
+
+        // Subscribers are picked up between frames rather than waited for
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener,
+            subscribers: Vec::new(),
+            history: Vec::new(),
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, TelemetryError> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Number of subscribers currently connected
+    pub fn subscribers(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Send a frame to every subscriber, after picking up any new ones.
+    /// Subscribers that disconnect or stop reading are dropped.
+    pub fn publish(&mut self, frame: &TelemetryFrame) -> Result<(), TelemetryError> {
+        let mut line = serde_json::to_string(frame)?;
+        line.push('\n');
+
+        if let TelemetryFrame::Start { .. } = frame {
+            self.history.clear();
+        }
+
+        self.accept()?;
+        self.subscribers
+            .retain(|mut subscriber| subscriber.write_all(line.as_bytes()).is_ok());
+        self.history.push(line);
+
+        Ok(())
+    }
+
+    /// Accept every pending subscriber, bringing it up to date with the
+    /// current flight
+    fn accept(&mut self) -> Result<(), TelemetryError> {
+        loop {
+            let mut subscriber = match self.listener.accept() {
+                Ok((subscriber, _)) => subscriber,
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(error) => return Err(error.into()),
+            };
+
+            subscriber.set_nonblocking(false)?;
+            subscriber.set_nodelay(true)?;
+            subscriber.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+            if self
+                .history
+                .iter()
+                .all(|line| subscriber.write_all(line.as_bytes()).is_ok())
+            {
+                self.subscribers.push(subscriber);
+            }
+        }
+    }
+}
+
+/// Reads telemetry frames from a stream
+pub struct TelemetryReader<R> {
+    reader: R,
+    line: String,
+}
+
+impl TelemetryReader<BufReader<TcpStream>> {
+    /// Subscribe to the telemetry published on an address
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self, TelemetryError> {
+        Ok(Self::new(BufReader::new(TcpStream::connect(address)?)))
+    }
+}
+
+impl<R: BufRead> TelemetryReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+        }
+    }
+
+    /// Next frame, or `None` once the stream ends. Blank lines and frames of
+    /// unknown types are skipped.
+    pub fn read_frame(&mut self) -> Result<Option<TelemetryFrame>, TelemetryError> {
+        loop {
+            self.line.clear();
+
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+
+            if self.line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(&self.line)? {
+                TelemetryFrame::Unknown => continue,
+                TelemetryFrame::Start { version, .. } if version > TELEMETRY_VERSION => {
+                    return Err(TelemetryError::UnsupportedVersion(version))
+                }
+                frame => return Ok(Some(frame)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{project::Project, sim::Simulation};
+
+    /// Frames of the first moments of a flight of the default project, one of
+    /// each type
+    fn flight() -> Vec<TelemetryFrame> {
+        let project = Project::default();
+        let mut simulation = Simulation::new(project.config()).unwrap();
+        let mut frames = vec![TelemetryFrame::start(
+            project.rocket,
+            project.motor,
+            project.launch,
+            project.settings,
+        )];
+
+        frames.extend(simulation.updates().take(20).map(TelemetryFrame::from));
+        frames.push(TelemetryFrame::End);
+
+        frames
+    }
+
+    fn encode(frames: &[TelemetryFrame]) -> String {
+        frames
+            .iter()
+            .map(|frame| serde_json::to_string(frame).unwrap() + "\n")
+            .collect()
+    }
+
+    /// Check that frames were received as sent, allowing for the last bit of
+    /// floats, which the JSON parser may round differently
+    fn assert_received(received: &[TelemetryFrame], sent: &[TelemetryFrame]) {
+        fn close(a: &Value, b: &Value) -> bool {
+            match (a, b) {
+                (Value::Number(a), Value::Number(b)) => {
+                    let (a, b) = (a.as_f64().unwrap(), b.as_f64().unwrap());
+
+                    (a - b).abs() <= 1e-15 * a.abs().max(b.abs())
+                }
+                (Value::Array(a), Value::Array(b)) => {
+                    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| close(a, b))
+                }
+                (Value::Object(a), Value::Object(b)) => {
+                    a.len() == b.len()
+                        && a.iter()
+                            .all(|(key, a)| b.get(key).is_some_and(|b| close(a, b)))
+                }
+                (a, b) => a == b,
+            }
+        }
+
+        let received = serde_json::to_value(received).unwrap();
+        let sent = serde_json::to_value(sent).unwrap();
+
+        assert!(close(&received, &sent), "{}\n{}", received, sent);
+    }
+
+    fn read_all<R: BufRead>(mut reader: TelemetryReader<R>) -> Vec<TelemetryFrame> {
+        let mut frames = Vec::new();
+
+        while let Some(frame) = reader.read_frame().unwrap() {
+            frames.push(frame);
+        }
+
+        frames
+    }
+
+    #[test]
+    fn round_trips_every_frame() {
+        let frames = flight();
+        let encoded = encode(&frames);
+
+        assert!(frames
+            .iter()
+            .any(|frame| matches!(frame, TelemetryFrame::Event(_))));
+        assert!(frames
+            .iter()
+            .any(|frame| matches!(frame, TelemetryFrame::Sample(_))));
+        assert_received(&read_all(TelemetryReader::new(encoded.as_bytes())), &frames);
+    }
+
+    #[test]
+    fn skips_unknown_frames() {
+        let stream = "{\"type\":\"wind\",\"speed\":3.0}\n\n{\"type\":\"end\"}\n";
+
+        assert_eq!(
+            read_all(TelemetryReader::new(stream.as_bytes())),
+            vec![TelemetryFrame::End]
+        );
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut frames = flight();
+        if let TelemetryFrame::Start { version, .. } = &mut frames[0] {
+            *version = TELEMETRY_VERSION + 1;
+        }
+        let encoded = encode(&frames);
+        let mut reader = TelemetryReader::new(encoded.as_bytes());
+
+        assert!(matches!(
+            reader.read_frame(),
+            Err(TelemetryError::UnsupportedVersion(version)) if version == TELEMETRY_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn replays_flight_to_late_subscribers() {
+        let frames = flight();
+        let (before, after) = frames.split_at(frames.len() / 2);
+        let mut publisher = TelemetryPublisher::bind("127.0.0.1:0").unwrap();
+
+        for frame in before {
+            publisher.publish(frame).unwrap();
+        }
+
+        let reader = TelemetryReader::connect(publisher.local_addr().unwrap()).unwrap();
+
+        for frame in after {
+            publisher.publish(frame).unwrap();
+        }
+        assert_eq!(publisher.subscribers(), 1);
+        drop(publisher);
+
+        let received = read_all(reader);
+        assert!(matches!(received[0], TelemetryFrame::Start { .. }));
+        assert_received(&received, &frames);
+    }
+}
@@ -0,0 +1,157 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::motor::Motor;
+
+/// Lumped mass and aerodynamic properties of a rocket, as consumed by the
+/// simulation. Axial positions are measured from the nose tip towards the tail.
+#[derive(Debug, Clone)]
+pub struct Vehicle {
+    /// Mass of the vehicle without a motor (kg)
+    pub dry_mass: f64,
+    /// Center of gravity of the vehicle without a motor (m)
+    pub dry_cg: f64,
+    /// Longitudinal (pitch/yaw) moment of inertia without a motor, about the dry CG (kg·m²)
+    pub dry_longitudinal_inertia: f64,
+    /// Roll moment of inertia without a motor (kg·m²)
+    pub dry_roll_inertia: f64,
+    /// Overall length (m)
+    pub length: f64,
+    /// Diameter used as the aerodynamic reference (m)
+    pub reference_diameter: f64,
+    /// Zero angle of attack drag coefficient at subsonic speeds
+    pub drag_coefficient: f64,
+    /// Normal force coefficient slope (per radian)
+    pub normal_force_slope: f64,
+    /// Center of pressure (m)
+    pub cp: f64,
+    /// Incidence of the fins relative to the airframe axis, pitching the
+    /// vehicle about its body x axis (rad)
+    pub fin_misalignment: f64,
+    /// Motor and the position of its aft end (m)
+    pub motor: Option<(Motor, f64)>,
+    /// Recovery devices in the order they were defined
+    pub recovery: Vec<RecoveryDevice>,
+}
+
+/// A parachute or streamer deployed during descent
+#[derive(Debug, Clone)]
+pub struct RecoveryDevice {
+    pub name: String,
+    /// Drag coefficient multiplied by the reference area (m²)
+    pub drag_area: f64,
+    pub deployment: Deployment,
+}
+
+/// The condition under which a recovery device is deployed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Deployment {
+    /// A fixed delay after apogee (s)
+    Apogee { delay: f64 },
+    /// Once the vehicle descends through an altitude above the launch site (m)
+    Altitude(f64),
+}
+
+impl Vehicle {
+    /// Cross sectional area used as the aerodynamic reference (m²)
+    pub fn reference_area(&self) -> f64 {
+        PI * self.reference_diameter * self.reference_diameter / 4.0
+    }
+
+    /// Add a point mass to the dry vehicle, such as nose weight, at a
+    /// position from the nose tip (m)
+    pub fn add_point_mass(&mut self, mass: f64, position: f64) {
+        let total = self.dry_mass + mass;
+
+        if total <= 0.0 {
+            return;
+        }
+
+        let cg = (self.dry_mass * self.dry_cg + mass * position) / total;
+
+        self.dry_longitudinal_inertia +=
+            self.dry_mass * (self.dry_cg - cg).powi(2) + mass * (position - cg).powi(2);
+        self.dry_mass = total;
+        self.dry_cg = cg;
+    }
+
+    /// Total mass at a given time since ignition (kg)
+    pub fn mass(&self, time: f64) -> f64 {
+        self.dry_mass
+            + self
+                .motor
+                .as_ref()
+                .map(|(motor, _)| motor.mass(time))
+                .unwrap_or(0.0)
+    }
+
+    /// Center of gravity at a given time since ignition (m)
+    pub fn cg(&self, time: f64) -> f64 {
+        match &self.motor {
+            Some((motor, aft)) => {
+                let motor_mass = motor.mass(time);
+
+                (self.dry_mass * self.dry_cg + motor_mass * (aft - motor.length / 2.0))
+                    / (self.dry_mass + motor_mass)
+            }
+            None => self.dry_cg,
+        }
+    }
+
+    /// Longitudinal and roll moments of inertia about the current center of
+    /// gravity (kg·m²)
+    pub fn inertia(&self, time: f64) -> (f64, f64) {
+        let cg = self.cg(time);
+        let mut longitudinal =
+            self.dry_longitudinal_inertia + self.dry_mass * (self.dry_cg - cg).powi(2);
+        let mut roll = self.dry_roll_inertia;
+
+        if let Some((motor, aft)) = &self.motor {
+            let mass = motor.mass(time);
+            let radius = motor.diameter / 2.0;
+
+            longitudinal += mass * (3.0 * radius * radius + motor.length * motor.length) / 12.0
+                + mass * (aft - motor.length / 2.0 - cg).powi(2);
+            roll += mass * radius * radius / 2.0;
+        }
+
+        (longitudinal, roll)
+    }
+
+    /// Drag coefficient adjusted for compressibility at a given mach number
+    pub fn drag_coefficient(&self, mach: f64) -> f64 {
+        let factor = if mach < 0.8 {
+            1.0
+        } else if mach < 1.1 {
+            // Transonic drag rise, peaking just past mach 1
+            1.0 + 0.8 * ((mach - 0.8) / 0.3).powi(2)
+        } else {
+            1.8 / (mach * mach - 1.1 * 1.1 + 1.0).sqrt()
+        };
+
+        self.drag_coefficient * factor
+    }
+
+    /// Distance between the center of pressure and the center of gravity,
+    /// in calibers
+    pub fn stability_margin(&self, time: f64) -> f64 {
+        (self.cp - self.cg(time)) / self.reference_diameter
+    }
+
+    /// Time from ignition until the motor burns out (s)
+    pub fn burn_time(&self) -> f64 {
+        self.motor
+            .as_ref()
+            .map(|(motor, _)| motor.burn_time())
+            .unwrap_or(0.0)
+    }
+
+    /// Thrust at a given time since ignition (N)
+    pub fn thrust(&self, time: f64) -> f64 {
+        self.motor
+            .as_ref()
+            .map(|(motor, _)| motor.thrust(time))
+            .unwrap_or(0.0)
+    }
+}
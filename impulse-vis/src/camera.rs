@@ -0,0 +1,293 @@
+//! Ways of moving the camera around the scene, and smooth transitions between
+//! them
+
+use std::{error::Error, str::FromStr};
+
+use cgmath::{Rotation, Vector3};
+use impulse_core::sim::FlightSample;
+use three_d::{
+    egui::{DragValue, Ui},
+    *,
+};
+
+use crate::flight::to_scene;
+
+/// Time taken to move between the views of two modes (s)
+const TRANSITION_TIME: f32 = 1.0;
+
+/// Height of the eyes of the ground observer above the ground (m)
+const EYE_HEIGHT: f32 = 1.7;
+
+/// Margin left around the flight when framing it with the camera
+const FRAMING_MARGIN: f32 = 1.2;
+
+/// How the camera is placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Orbiting around a fixed point, controlled with the mouse
+    Orbit,
+    /// Behind the rocket, looking along its body axis
+    Chase,
+    /// Standing on the ground near the launch rail, watching the rocket
+    Ground,
+    /// Flying freely, controlled with the mouse and keyboard
+    FreeFly,
+}
+
+impl CameraMode {
+    pub const ALL: [CameraMode; 4] = [
+        CameraMode::Orbit,
+        CameraMode::Chase,
+        CameraMode::Ground,
+        CameraMode::FreeFly,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            CameraMode::Orbit => "Orbit",
+            CameraMode::Chase => "Chase",
+            CameraMode::Ground => "Ground",
+            CameraMode::FreeFly => "Free fly",
+        }
+    }
+}
+
+impl FromStr for CameraMode {
+    type Err = String;
+
+    /// Mode named by its label in lower case, with dashes for spaces
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|mode| mode.label().to_lowercase().replace(' ', "-") == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown camera mode {:?}, expected orbit, chase, ground or free-fly",
+                    name
+                )
+            })
+    }
+}
+
+/// Where the rocket is in the scene
+#[derive(Debug, Clone, Copy)]
+pub struct Subject {
+    /// Middle of the rocket
+    pub center: Vec3,
+    /// Unit vector out the nose
+    pub axis: Vec3,
+}
+
+impl Subject {
+    /// Place of a rocket of some length (m) in a sample of its flight
+    pub fn new(sample: &FlightSample, length: f64) -> Self {
+        let axis = to_scene(sample.attitude.rotate_vector(Vector3::unit_z()));
+
+        Self {
+            center: to_scene(sample.position) + axis * (length / 2.0) as f32,
+            axis,
+        }
+    }
+}
+
+/// Camera taking in a sphere around the flight from the south east, and its
+/// distance from the center of the sphere (m)
+pub fn overview(
+    context: &Context,
+    viewport: Viewport,
+    center: Vec3,
+    radius: f32,
+) -> Result<(Camera, f32), Box<dyn Error>> {
+    let distance = FRAMING_MARGIN * radius / 22.5f32.to_radians().sin();
+    let camera = Camera::new_perspective(
+        context,
+        viewport,
+        center + vec3(1.0, 0.6, 1.0).normalize() * distance,
+        center,
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        (distance * 0.001).min(0.1),
+        distance * 20.0,
+    )?;
+
+    Ok((camera, distance))
+}
+
+/// Camera position and target
+type View = (Vec3, Vec3);
+
+/// Moves the camera in one of several modes
+pub struct CameraRig {
+    pub mode: CameraMode,
+    /// Position of the ground observer east and north of the launch rail (m)
+    pub observer: (f32, f32),
+    /// Distance of the chase camera behind the rocket (m)
+    pub chase_distance: f32,
+    orbit: OrbitControl,
+    fly: FlyControl,
+    /// Orbit view to come back to, from when the orbit mode was last left
+    orbit_view: View,
+    /// Mode whose view is being faded into, and the view being faded from
+    transition: Option<(View, f32)>,
+    /// Closest and furthest the orbit camera can be from its target
+    orbit_range: (f32, f32),
+}
+
+impl CameraRig {
+    /// Start orbiting around the camera's target, keeping within a range of
+    /// distances
+    pub fn new(camera: &Camera, orbit_range: (f32, f32), rocket_length: f32) -> Self {
+        Self {
+            mode: CameraMode::Orbit,
+            observer: (30.0, -60.0),
+            chase_distance: (4.0 * rocket_length).max(2.0),
+            orbit: OrbitControl::new(*camera.target(), orbit_range.0, orbit_range.1),
+            fly: FlyControl::new(0.01 * orbit_range.1),
+            orbit_view: (*camera.position(), *camera.target()),
+            transition: None,
+            orbit_range,
+        }
+    }
+
+    fn set_mode(&mut self, mode: CameraMode, camera: &Camera) {
+        if mode == self.mode {
+            return;
+        }
+
+        let current = (*camera.position(), *camera.target());
+
+        if self.mode == CameraMode::Orbit {
+            self.orbit_view = current;
+        }
+
+        if mode == CameraMode::Orbit {
+            self.orbit =
+                OrbitControl::new(self.orbit_view.1, self.orbit_range.0, self.orbit_range.1);
+        }
+
+        self.mode = mode;
+        self.transition = Some((current, 0.0));
+    }
+
+    /// View the current mode wants, if it places the camera itself
+    fn desired_view(&self, subject: Subject) -> Option<View> {
+        let up = vec3(0.0, 1.0, 0.0);
+
+        match self.mode {
+            CameraMode::Orbit => Some(self.orbit_view),
+            CameraMode::Chase => {
+                // Sit a little to the side so the view never lines up with the
+                // up direction while the rocket is vertical
+                let side = subject.axis.cross(up);
+                let side = if side.magnitude2() > 1e-6 {
+                    side.normalize()
+                } else {
+                    vec3(1.0, 0.0, 0.0)
+                };
+                let position = subject.center - subject.axis * self.chase_distance
+                    + side * (0.5 * self.chase_distance);
+
+                Some((position, subject.center))
+            }
+            CameraMode::Ground => {
+                let (east, north) = self.observer;
+
+                Some((vec3(east, EYE_HEIGHT, -north), subject.center))
+            }
+            CameraMode::FreeFly => None,
+        }
+    }
+
+    /// Move the camera for a frame lasting some time (s), following the
+    /// rocket if the mode tracks it
+    pub fn update(
+        &mut self,
+        camera: &mut Camera,
+        events: &mut [Event],
+        elapsed: f32,
+        subject: Subject,
+    ) -> Result<(), Box<dyn Error>> {
+        let up = vec3(0.0, 1.0, 0.0);
+        let desired = self.desired_view(subject);
+
+        if let Some((from, progress)) = self.transition {
+            let progress = progress + elapsed / TRANSITION_TIME;
+
+            match desired {
+                Some((position, target)) if progress < 1.0 => {
+                    let t = progress * progress * (3.0 - 2.0 * progress);
+
+                    camera.set_view(
+                        from.0 + (position - from.0) * t,
+                        from.1 + (target - from.1) * t,
+                        up,
+                    )?;
+                    self.transition = Some((from, progress));
+
+                    return Ok(());
+                }
+                _ => self.transition = None,
+            }
+        }
+
+        match self.mode {
+            CameraMode::Orbit => {
+                // Settle exactly on the view the transition was heading for
+                if let Some((position, target)) = desired {
+                    if *camera.position() != position || *camera.target() != target {
+                        camera.set_view(position, target, up)?;
+                    }
+                }
+
+                self.orbit.handle_events(camera, events)?;
+                self.orbit_view = (*camera.position(), *camera.target());
+            }
+            CameraMode::FreeFly => {
+                self.fly.handle_events(camera, events)?;
+            }
+            CameraMode::Chase | CameraMode::Ground => {
+                if let Some((position, target)) = desired {
+                    camera.set_view(position, target, up)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mode selection and the settings of the current mode
+    pub fn controls(&mut self, ui: &mut Ui, camera: &Camera) {
+        let mut mode = self.mode;
+
+        ui.horizontal(|ui| {
+            for &option in &CameraMode::ALL {
+                ui.radio_value(&mut mode, option, option.label());
+            }
+        });
+
+        self.set_mode(mode, camera);
+
+        match self.mode {
+            CameraMode::Chase => {
+                ui.horizontal(|ui| {
+                    ui.label("Distance (m)");
+                    ui.add(DragValue::new(&mut self.chase_distance).speed(0.1));
+                });
+                self.chase_distance = self.chase_distance.max(0.1);
+            }
+            CameraMode::Ground => {
+                ui.horizontal(|ui| {
+                    ui.label("East (m)");
+                    ui.add(DragValue::new(&mut self.observer.0).speed(1.0));
+                    ui.label("North (m)");
+                    ui.add(DragValue::new(&mut self.observer.1).speed(1.0));
+                });
+            }
+            CameraMode::FreeFly => {
+                ui.label("Drag to look around, scroll to fly forwards and backwards");
+            }
+            CameraMode::Orbit => {}
+        }
+    }
+}
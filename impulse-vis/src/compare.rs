@@ -0,0 +1,193 @@
+//! Other flights drawn alongside the one being shown, such as flights of
+//! other designs or the dispersed flights of a Monte Carlo analysis
+
+use std::error::Error;
+
+use three_d::{
+    egui::{Color32, Grid, ScrollArea, Ui},
+    *,
+};
+
+use crate::{
+    flight::{to_scene, Bounds, Flight},
+    mesh::{self, Rgb},
+    model::RocketModel,
+    trajectory::{self, material},
+};
+
+/// Size of the landing markers relative to the size of the scene
+const LANDING_SCALE: f32 = 0.006;
+
+/// Tallest the table of flights grows before scrolling (points)
+const TABLE_HEIGHT: f32 = 240.0;
+
+/// A flight being compared, drawn in its own color
+struct Run {
+    flight: Flight,
+    color: Rgb,
+    visible: bool,
+    rocket: RocketModel,
+    path: (Model, Material),
+    landing: Option<(Model, Material)>,
+}
+
+/// Flights compared with the one being shown, played back in step with it
+pub struct Comparison {
+    runs: Vec<Run>,
+}
+
+impl Comparison {
+    /// Flights drawn in a scene of some size (m)
+    pub fn new(context: &Context, flights: Vec<Flight>, size: f32) -> Result<Self, Box<dyn Error>> {
+        let runs = flights
+            .into_iter()
+            .enumerate()
+            .map(|(index, flight)| {
+                let color = run_color(index);
+                let path = trajectory::solid_path(&flight.name, &flight, size);
+                let landing = match flight.record.samples.last() {
+                    Some(sample) => {
+                        let marker = mesh::octahedron(
+                            "landing",
+                            to_scene(sample.position),
+                            size * LANDING_SCALE,
+                        );
+
+                        Some((Model::new(context, &marker)?, material(context, color)?))
+                    }
+                    None => None,
+                };
+
+                Ok(Run {
+                    rocket: RocketModel::new(context, &flight.rocket)?,
+                    path: (Model::new(context, &path)?, material(context, color)?),
+                    flight,
+                    color,
+                    visible: true,
+                    landing,
+                })
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        Ok(Self { runs })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn flights(&self) -> impl Iterator<Item = &Flight> {
+        self.runs.iter().map(|run| &run.flight)
+    }
+
+    /// Time from ignition to the end of the longest flight (s)
+    pub fn duration(&self) -> f64 {
+        self.flights().map(Flight::duration).fold(0.0, f64::max)
+    }
+
+    /// Smallest box containing a box and every flight
+    pub fn bounds(&self, bounds: Bounds) -> Bounds {
+        self.flights()
+            .map(Flight::bounds)
+            .fold(bounds, Bounds::union)
+    }
+
+    /// Move every rocket to where it was at a time since ignition (s)
+    pub fn set_time(&mut self, time: f64) {
+        for run in &mut self.runs {
+            if let Some(sample) = run.flight.record.sample_at(time) {
+                run.rocket.set_pose(sample.position, sample.attitude);
+            }
+        }
+    }
+
+    /// Flight paths, rockets and landing points of the visible flights
+    pub fn geometries(&self) -> impl Iterator<Item = (&Model, &Material)> {
+        self.runs.iter().filter(|run| run.visible).flat_map(|run| {
+            let (path, material) = &run.path;
+
+            std::iter::once((path, material))
+                .chain(
+                    run.landing
+                        .iter()
+                        .map(|(model, material)| (model, material)),
+                )
+                .chain(run.rocket.geometries())
+        })
+    }
+
+    /// Table of the apogee and landing distance of each flight, starting with
+    /// the flight being shown, with toggles for showing the others
+    pub fn controls(&mut self, ui: &mut Ui, shown: &Flight) {
+        ui.horizontal(|ui| {
+            if ui.button("Show all").clicked() {
+                self.runs.iter_mut().for_each(|run| run.visible = true);
+            }
+
+            if ui.button("Hide all").clicked() {
+                self.runs.iter_mut().for_each(|run| run.visible = false);
+            }
+        });
+
+        ScrollArea::from_max_height(TABLE_HEIGHT).show(ui, |ui| {
+            Grid::new("Comparison").striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.label("Flight");
+                ui.label("Apogee");
+                ui.label("Landing");
+                ui.end_row();
+
+                ui.label("");
+                ui.label(&shown.name);
+                summary_cells(ui, shown);
+                ui.end_row();
+
+                for run in &mut self.runs {
+                    let (r, g, b) = run.color;
+
+                    ui.checkbox(&mut run.visible, "");
+                    ui.colored_label(Color32::from_rgb(r, g, b), &run.flight.name);
+                    summary_cells(ui, &run.flight);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
+
+/// Apogee and landing distance of a flight, as cells of a table
+fn summary_cells(ui: &mut Ui, flight: &Flight) {
+    match &flight.summary {
+        Some(summary) => {
+            ui.label(format!("{:.1} m", summary.apogee));
+            ui.label(match summary.landing_distance {
+                Some(distance) => format!("{:.1} m", distance),
+                None => "Did not land".to_string(),
+            });
+        }
+        None => {
+            ui.label("");
+            ui.label("In flight");
+        }
+    }
+}
+
+/// Color of the flight with some index, with hues a golden angle apart so
+/// that neighbouring flights stand apart however many there are
+fn run_color(index: usize) -> Rgb {
+    let hue = (index as f32 * 0.618_034).fract() * 6.0;
+    let (high, low) = (240.0, 70.0);
+    let rising = low + (high - low) * hue.fract();
+    let falling = high - (high - low) * hue.fract();
+
+    let (r, g, b) = match hue as u32 {
+        0 => (high, rising, low),
+        1 => (falling, high, low),
+        2 => (low, high, rising),
+        3 => (low, falling, high),
+        4 => (rising, low, high),
+        _ => (high, low, falling),
+    };
+
+    (r as u8, g as u8, b as u8)
+}
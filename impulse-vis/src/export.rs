@@ -0,0 +1,338 @@
+//! Rendering the playback of a flight to images without a window, for sharing
+//! flights and for machines without a display or GPU
+//!
+//! Frames are rendered in software with OSMesa, which has to be installed,
+//! such as with the `libosmesa6` package on Debian and Ubuntu. WebM videos
+//! are assembled from the frames with `ffmpeg`, which has to be installed too.
+
+use std::{
+    error,
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use cgmath::{Quaternion, Vector3, Zero};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    imageops, Delay, Frame, RgbaImage,
+};
+use thiserror::Error;
+use three_d::*;
+use tracing::info;
+
+use crate::{
+    camera::{self, CameraMode, CameraRig, Subject},
+    flight::Flight,
+    model::RocketModel,
+    scene::Scene,
+    site::{self, LaunchSite},
+    terrain::Terrain,
+    trajectory::{ColorMode, Trajectory},
+};
+
+/// Most frames an export can have, as they are numbered with five digits
+const MAX_FRAMES: f64 = 100_000.0;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("the size, framerate and speed of exports must be positive and finite")]
+    InvalidSettings,
+    #[error(
+        "the export would have more than {} frames, so lower its framerate or raise its speed",
+        MAX_FRAMES
+    )]
+    TooManyFrames,
+    #[error("cannot tell the animation format of {}, expected .gif or .webm", .0.display())]
+    UnknownAnimationFormat(PathBuf),
+    #[error("WebM videos must have an even width and height, not {0}x{1}")]
+    OddVideoSize(u32, u32),
+    #[error("offscreen rendering is only supported on Linux and BSD")]
+    Unsupported,
+    #[error("failed to create an offscreen OpenGL context with OSMesa: {0}")]
+    Context(#[from] glutin::CreationError),
+    #[error("failed to use the offscreen OpenGL context: {0}")]
+    MakeCurrent(#[from] glutin::ContextError),
+    #[error("failed to render a frame: {0}")]
+    Render(Box<dyn error::Error>),
+    #[error("failed to write {}: {1}", .0.display())]
+    Io(PathBuf, #[source] io::Error),
+    #[error("failed to encode an image: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("ffmpeg failed to assemble the video: {0}")]
+    Ffmpeg(String),
+}
+
+impl From<Box<dyn error::Error>> for ExportError {
+    fn from(error: Box<dyn error::Error>) -> Self {
+        ExportError::Render(error)
+    }
+}
+
+/// How the playback of a flight is exported
+#[derive(Debug, Clone)]
+pub struct ExportSettings {
+    /// Directory the frames are written to as numbered PNG images
+    pub directory: PathBuf,
+    /// Size of the frames in pixels
+    pub width: u32,
+    pub height: u32,
+    /// Frames per second of the exported animation
+    pub framerate: f64,
+    /// Seconds of flight played per second of the exported animation
+    pub speed: f64,
+    pub camera: CameraMode,
+    /// GIF or WebM file to assemble the frames into, if any
+    pub animation: Option<PathBuf>,
+}
+
+/// Formats the frames can be assembled into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimationFormat {
+    Gif,
+    WebM,
+}
+
+impl AnimationFormat {
+    /// Format written to files with an extension, ignoring case
+    fn from_extension(extension: &str) -> Option<Self> {
+        if extension.eq_ignore_ascii_case("gif") {
+            Some(AnimationFormat::Gif)
+        } else if extension.eq_ignore_ascii_case("webm") {
+            Some(AnimationFormat::WebM)
+        } else {
+            None
+        }
+    }
+}
+
+/// Render the playback of a flight from ignition to landing, one image per
+/// frame
+pub fn export(
+    flight: &Flight,
+    terrain: Option<&Terrain>,
+    settings: &ExportSettings,
+) -> Result<(), ExportError> {
+    let ExportSettings {
+        width,
+        height,
+        framerate,
+        speed,
+        ..
+    } = *settings;
+
+    if width == 0
+        || height == 0
+        || !framerate.is_finite()
+        || framerate <= 0.0
+        || !speed.is_finite()
+        || speed <= 0.0
+    {
+        return Err(ExportError::InvalidSettings);
+    }
+
+    let frames = (flight.duration() / speed * framerate).ceil() + 1.0;
+
+    if frames.is_nan() || frames > MAX_FRAMES {
+        return Err(ExportError::TooManyFrames);
+    }
+
+    let frames = frames as usize;
+
+    let animation = match &settings.animation {
+        Some(path) => Some((
+            path,
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(AnimationFormat::from_extension)
+                .ok_or_else(|| ExportError::UnknownAnimationFormat(path.clone()))?,
+        )),
+        None => None,
+    };
+
+    // Video chroma is stored at half resolution, so VP9 needs even sizes
+    let webm = matches!(animation, Some((_, AnimationFormat::WebM)));
+
+    if webm && (width % 2 == 1 || height % 2 == 1) {
+        return Err(ExportError::OddVideoSize(width, height));
+    }
+
+    fs::create_dir_all(&settings.directory)
+        .map_err(|error| ExportError::Io(settings.directory.clone(), error))?;
+
+    // The context has to be kept alive for as long as it is drawn with
+    let (_offscreen, context) = offscreen_context(width, height)?;
+    let viewport = Viewport::new_at_origo(width, height);
+
+    let bounds = flight.bounds();
+    let (target, radius) = (bounds.center(), bounds.radius().max(1.0));
+    let (mut camera, distance) = camera::overview(&context, viewport, target, radius)?;
+
+    let site = LaunchSite::new(
+        &context,
+        &flight.record.launch,
+        site::reach(flight),
+        terrain,
+    )?;
+    let mut rocket = RocketModel::new(&context, &flight.rocket)?;
+    let rocket_length = flight.rocket.length();
+    let trajectory = Trajectory::new(&context, flight, ColorMode::Velocity)?;
+    let mut scene = Scene::new(&context)?;
+
+    let mut rig = CameraRig::new(
+        &camera,
+        (0.01 * distance, 10.0 * distance),
+        rocket_length as f32,
+    );
+    rig.mode = settings.camera;
+
+    let mut gif = match animation {
+        Some((path, AnimationFormat::Gif)) => {
+            let file = File::create(path).map_err(|error| ExportError::Io(path.clone(), error))?;
+            let mut encoder = GifEncoder::new(BufWriter::new(file));
+            encoder.set_repeat(Repeat::Infinite)?;
+
+            Some(encoder)
+        }
+        _ => None,
+    };
+
+    info!(frames, width, height, "Exporting flight");
+
+    for frame in 0..frames {
+        let time = (frame as f64 * speed / framerate).min(flight.duration());
+
+        let subject = match flight.record.sample_at(time) {
+            Some(sample) => {
+                rocket.set_pose(sample.position, sample.attitude);
+
+                Subject::new(&sample, rocket_length)
+            }
+            None => {
+                rocket.set_pose(Vector3::zero(), Quaternion::new(1.0, 0.0, 0.0, 0.0));
+
+                Subject {
+                    center: vec3(0.0, rocket_length as f32 / 2.0, 0.0),
+                    axis: vec3(0.0, 1.0, 0.0),
+                }
+            }
+        };
+
+        rig.update(&mut camera, &mut [], (1.0 / framerate) as f32, subject)?;
+        scene.render(
+            &context,
+            &camera,
+            site.ground(),
+            site.fixtures()
+                .chain(rocket.geometries())
+                .chain(trajectory.geometries()),
+            (target, radius),
+            || Ok(()),
+        )?;
+
+        // Rows are read from the bottom of the screen up
+        let pixels = Screen::read_color(&context, viewport)?;
+        let image = imageops::flip_vertical(
+            &RgbaImage::from_raw(width, height, pixels).expect("one pixel per screen pixel"),
+        );
+
+        let path = settings.directory.join(format!("frame-{:05}.png", frame));
+        image.save(&path)?;
+
+        if let Some(encoder) = &mut gif {
+            encoder.encode_frame(Frame::from_parts(
+                image,
+                0,
+                0,
+                Delay::from_saturating_duration(
+                    Duration::try_from_secs_f64(1.0 / framerate).unwrap_or(Duration::MAX),
+                ),
+            ))?;
+        }
+    }
+
+    info!(directory = %settings.directory.display(), frames, "Exported frames");
+
+    if let Some((path, format)) = animation {
+        if format == AnimationFormat::WebM {
+            assemble_webm(&settings.directory, framerate, frames, path)?;
+        }
+
+        info!(path = %path.display(), "Exported animation");
+    }
+
+    Ok(())
+}
+
+/// Encode the exported frames as a VP9 WebM video with ffmpeg. Only the
+/// frames of this export are read, as the directory may still hold later
+/// frames of a longer export.
+fn assemble_webm(
+    directory: &Path,
+    framerate: f64,
+    frames: usize,
+    path: &Path,
+) -> Result<(), ExportError> {
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .args(["-framerate", &framerate.to_string()])
+        .arg("-i")
+        .arg(directory.join("frame-%05d.png"))
+        .args(["-frames:v", &frames.to_string()])
+        .args(["-c:v", "libvpx-vp9", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .output()
+        .map_err(|error| ExportError::Ffmpeg(error.to_string()))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(ExportError::Ffmpeg(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Software OpenGL context drawing into a buffer of some size, rather than a
+/// window
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn offscreen_context(
+    width: u32,
+    height: u32,
+) -> Result<(glutin::Context<glutin::PossiblyCurrent>, Context), ExportError> {
+    use glutin::{
+        dpi::PhysicalSize, platform::unix::HeadlessContextExt, Api, ContextBuilder, GlProfile,
+        GlRequest,
+    };
+
+    let offscreen = ContextBuilder::new()
+        .with_gl(GlRequest::Specific(Api::OpenGl, (3, 3)))
+        .with_gl_profile(GlProfile::Core)
+        .build_osmesa(PhysicalSize::new(width, height))?;
+    let offscreen = unsafe { offscreen.make_current() }.map_err(|(_, error)| error)?;
+    let context = Context::load_with(|name| offscreen.get_proc_address(name) as *const _);
+
+    Ok((offscreen, context))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn offscreen_context(
+    _width: u32,
+    _height: u32,
+) -> Result<(glutin::Context<glutin::PossiblyCurrent>, Context), ExportError> {
+    Err(ExportError::Unsupported)
+}
@@ -0,0 +1,233 @@
+//! Flights simulated from project files with impulse-core
+
+use std::path::Path;
+
+use cgmath::{InnerSpace, Quaternion, Vector3};
+use impulse_core::{
+    project::{Project, ProjectError},
+    rocket::Rocket,
+    sim::{
+        FlightEventKind, FlightRecord, FlightSummary, MonteCarlo, SettingsError, Simulation,
+        SimulationConfig,
+    },
+};
+use thiserror::Error;
+use three_d::{vec3, Mat4, Vec3};
+
+#[derive(Debug, Error)]
+pub enum FlightError {
+    #[error("failed to open project: {0}")]
+    Project(#[from] ProjectError),
+    #[error("the design cannot be simulated:\n{}", .0.join("\n"))]
+    InvalidDesign(Vec<String>),
+    #[error("invalid simulation settings: {0}")]
+    Settings(#[from] SettingsError),
+}
+
+/// A completed flight of a project's rocket
+pub struct Flight {
+    /// Name of the rocket
+    pub name: String,
+    pub rocket: Rocket,
+    pub record: FlightRecord,
+    /// Summary of the flight, once it is complete
+    pub summary: Option<FlightSummary>,
+}
+
+impl Flight {
+    /// Load a project and simulate it to completion
+    pub fn load(path: &Path) -> Result<Self, FlightError> {
+        let project = load_project(path)?;
+
+        Self::simulate(project.rocket.clone(), project.config())
+    }
+
+    /// Load a project and simulate some of the flights of its Monte Carlo
+    /// analysis to completion
+    pub fn dispersed(path: &Path, runs: usize) -> Result<Vec<Self>, FlightError> {
+        let project = load_project(path)?;
+        let analysis = MonteCarlo::new(project.config(), project.dispersion)?;
+
+        (0..runs.min(analysis.total()))
+            .map(|index| {
+                Ok(Self {
+                    name: format!("Run {}", index + 1),
+                    ..Self::simulate(project.rocket.clone(), analysis.run_config(index))?
+                })
+            })
+            .collect()
+    }
+
+    /// Simulate a rocket to completion
+    fn simulate(rocket: Rocket, config: SimulationConfig) -> Result<Self, FlightError> {
+        let mut simulation = Simulation::new(config)?;
+        let record = simulation.run_to_completion();
+        let summary = FlightSummary::new(simulation.config(), &record.samples, &record.events);
+
+        Ok(Self {
+            name: rocket.name.clone(),
+            rocket,
+            record,
+            summary: Some(summary),
+        })
+    }
+
+    /// Flight about to be followed live, with nothing recorded yet
+    pub fn live(rocket: Rocket, config: &SimulationConfig) -> Self {
+        Self {
+            name: rocket.name.clone(),
+            rocket,
+            record: FlightRecord {
+                recovery: config
+                    .vehicle
+                    .recovery
+                    .iter()
+                    .map(|device| device.name.clone())
+                    .collect(),
+                launch: config.launch,
+                ..FlightRecord::default()
+            },
+            summary: None,
+        }
+    }
+
+    /// Summarize a live flight once everything has been received
+    pub fn finish(&mut self, config: &SimulationConfig) {
+        self.summary = Some(FlightSummary::new(
+            config,
+            &self.record.samples,
+            &self.record.events,
+        ));
+    }
+
+    /// Time from ignition to the end of the recording (s)
+    pub fn duration(&self) -> f64 {
+        self.record
+            .samples
+            .last()
+            .map(|sample| sample.time)
+            .unwrap_or(0.0)
+    }
+
+    /// Events shown along the trajectory, with where in the scene they
+    /// happened
+    pub fn markers(&self) -> Vec<(FlightEventKind, Vec3)> {
+        self.record
+            .events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event.kind,
+                    FlightEventKind::Burnout
+                        | FlightEventKind::Apogee
+                        | FlightEventKind::Deployment(_)
+                        | FlightEventKind::Landing
+                )
+            })
+            .filter_map(|event| {
+                let sample = self.record.sample_at(event.time)?;
+
+                Some((event.kind, to_scene(sample.position)))
+            })
+            .collect()
+    }
+
+    /// Axis aligned box around the trajectory and the launch rail
+    pub fn bounds(&self) -> Bounds {
+        self.record
+            .samples
+            .iter()
+            .map(|sample| to_scene(sample.position))
+            .fold(Bounds::point(vec3(0.0, 0.0, 0.0)), Bounds::including)
+    }
+}
+
+/// Load a project whose rocket can be simulated
+fn load_project(path: &Path) -> Result<Project, FlightError> {
+    let project = Project::load(path)?;
+
+    let errors: Vec<_> = project
+        .rocket
+        .validate()
+        .iter()
+        .chain(&project.rocket.check_motor(&project.motor))
+        .map(ToString::to_string)
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(FlightError::InvalidDesign(errors));
+    }
+
+    Ok(project)
+}
+
+/// Axis aligned box in the scene
+#[derive(Debug, Clone, Copy)]
+pub struct Bounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Bounds {
+    pub fn point(point: Vec3) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Smallest box containing both boxes
+    pub fn union(self, other: Bounds) -> Self {
+        self.including(other.min).including(other.max)
+    }
+
+    pub fn including(self, point: Vec3) -> Self {
+        Self {
+            min: vec3(
+                self.min.x.min(point.x),
+                self.min.y.min(point.y),
+                self.min.z.min(point.z),
+            ),
+            max: vec3(
+                self.max.x.max(point.x),
+                self.max.y.max(point.y),
+                self.max.z.max(point.z),
+            ),
+        }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Radius of the sphere around the center that contains the box
+    pub fn radius(&self) -> f32 {
+        ((self.max - self.min) * 0.5).magnitude()
+    }
+}
+
+/// Position in the scene, whose y axis points up and whose z axis points
+/// south, from the east-north-up frame of the launch site
+pub fn to_scene(position: Vector3<f64>) -> Vec3 {
+    vec3(position.x as f32, position.z as f32, -position.y as f32)
+}
+
+/// Transformation from the body frame of the vehicle to the scene, for a
+/// position and attitude in the east-north-up frame
+pub fn body_to_scene(position: Vector3<f64>, attitude: Quaternion<f64>) -> Mat4 {
+    // Columns are where the east, north and up axes end up in the scene
+    let axes = Mat4::new(
+        1.0, 0.0, 0.0, 0.0, //
+        0.0, 0.0, -1.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0,
+    );
+    let attitude = Quaternion::new(
+        attitude.s as f32,
+        attitude.v.x as f32,
+        attitude.v.y as f32,
+        attitude.v.z as f32,
+    );
+
+    Mat4::from_translation(to_scene(position)) * axes * Mat4::from(attitude)
+}
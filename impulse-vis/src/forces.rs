@@ -0,0 +1,395 @@
+//! Arrows showing the forces on the rocket where they act, with markers at
+//! its centers of gravity and pressure and an indicator of its angle of
+//! attack
+
+use std::error::Error;
+
+use cgmath::{InnerSpace, Rotation, Vector3};
+use impulse_core::{
+    rocket::Rocket,
+    sim::{standard_atmosphere, FlightPhase, FlightSample, LaunchConditions, STANDARD_GRAVITY},
+};
+use three_d::{
+    egui::{Color32, DragValue, Ui},
+    *,
+};
+
+use crate::{
+    flight::{body_to_scene, to_scene, Flight},
+    mesh::{self, Rgb},
+    trajectory::material,
+};
+
+/// Length of the arrow of the largest force in the flight, relative to the
+/// length of the rocket
+const FORCE_LENGTH: f32 = 1.5;
+
+/// Speed of the wind drawn as long as the rocket (m/s)
+const WIND_SPEED_SCALE: f32 = 10.0;
+
+/// Radius of arrow shafts relative to the length of the rocket
+const ARROW_SCALE: f32 = 0.008;
+
+/// Sides of the prisms arrows and lines are drawn with
+const ARROW_SIDES: u32 = 8;
+
+/// Size of the center markers relative to the diameter of the rocket, large
+/// enough to stand out of the body
+const CENTER_SCALE: f32 = 0.75;
+
+/// Length of the angle of attack indicator relative to the length of the
+/// rocket
+const ANGLE_LENGTH: f32 = 0.5;
+
+/// Segments of the arc of the angle of attack indicator
+const ARC_SEGMENTS: u32 = 16;
+
+/// Something the overlays can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    Thrust,
+    Drag,
+    NormalForce,
+    Gravity,
+    Wind,
+    Centers,
+    AngleOfAttack,
+}
+
+impl Overlay {
+    pub const ALL: [Overlay; 7] = [
+        Overlay::Thrust,
+        Overlay::Drag,
+        Overlay::NormalForce,
+        Overlay::Gravity,
+        Overlay::Wind,
+        Overlay::Centers,
+        Overlay::AngleOfAttack,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Overlay::Thrust => "Thrust",
+            Overlay::Drag => "Drag",
+            Overlay::NormalForce => "Normal force",
+            Overlay::Gravity => "Gravity",
+            Overlay::Wind => "Wind",
+            Overlay::Centers => "CG and CP",
+            Overlay::AngleOfAttack => "Angle of attack",
+        }
+    }
+
+    fn color(self) -> Rgb {
+        match self {
+            Overlay::Thrust => (255, 110, 30),
+            Overlay::Drag => (230, 50, 50),
+            Overlay::NormalForce => (200, 80, 230),
+            Overlay::Gravity => (40, 120, 255),
+            Overlay::Wind => (90, 180, 255),
+            Overlay::Centers => (255, 255, 255),
+            Overlay::AngleOfAttack => (240, 210, 40),
+        }
+    }
+}
+
+const CG_COLOR: Rgb = (40, 120, 255);
+const CP_COLOR: Rgb = (230, 50, 50);
+
+/// A vector acting on the rocket at a distance from its nose tip (m), in the
+/// east-north-up frame
+struct Applied {
+    overlay: Overlay,
+    vector: Vector3<f64>,
+    from_nose: f64,
+}
+
+/// Overlays drawn on the rocket at the time being shown
+pub struct ForceOverlays {
+    shown: [bool; Overlay::ALL.len()],
+    /// Length of force arrows, relative to the default
+    pub scale: f32,
+    launch: LaunchConditions,
+    length: f64,
+    diameter: f64,
+    reference_area: f64,
+    normal_force_slope: f64,
+    /// Largest force in the flight so far (N), and the number of samples it
+    /// was found in
+    max_force: (f64, usize),
+    /// Forces and wind at the time being shown, for the readout
+    applied: Vec<Applied>,
+    angle_of_attack: f64,
+    /// Time, toggles and scale the meshes were last built for
+    drawn: Option<(f64, [bool; Overlay::ALL.len()], f32)>,
+    geometries: Vec<(Model, Material)>,
+}
+
+impl ForceOverlays {
+    /// Overlays for a rocket launched in some conditions, all hidden
+    pub fn new(rocket: &Rocket, launch: &LaunchConditions) -> Self {
+        let vehicle = rocket.to_vehicle(None);
+
+        Self {
+            shown: [false; Overlay::ALL.len()],
+            scale: 1.0,
+            launch: *launch,
+            length: vehicle.length,
+            diameter: vehicle.reference_diameter,
+            reference_area: vehicle.reference_area(),
+            normal_force_slope: vehicle.normal_force_slope,
+            max_force: (0.0, 0),
+            applied: Vec::new(),
+            angle_of_attack: 0.0,
+            drawn: None,
+            geometries: Vec::new(),
+        }
+    }
+
+    /// Show the same overlays on the rocket of another flight
+    pub fn reset(&mut self, rocket: &Rocket, launch: &LaunchConditions) {
+        *self = Self {
+            shown: self.shown,
+            scale: self.scale,
+            ..Self::new(rocket, launch)
+        };
+    }
+
+    fn is_shown(&self, overlay: Overlay) -> bool {
+        self.shown[overlay as usize]
+    }
+
+    /// Thrust at the aft end, drag and normal force at the center of pressure,
+    /// gravity at the center of gravity and the wind at the center of
+    /// pressure, as simulated for a sample
+    fn forces(&self, sample: &FlightSample) -> Vec<Applied> {
+        let axis = sample.attitude.rotate_vector(Vector3::unit_z());
+        let wind = self.launch.wind();
+        let relative_velocity = sample.velocity - wind;
+        let airspeed = relative_velocity.magnitude();
+        let flow = if airspeed > f64::EPSILON {
+            relative_velocity / airspeed
+        } else {
+            axis
+        };
+
+        let density = standard_atmosphere(self.launch.site_altitude + sample.position.z).density;
+        let dynamic_pressure = 0.5 * density * airspeed * airspeed;
+        let crossflow = flow - axis * flow.dot(axis);
+        let normal =
+            if sample.phase != FlightPhase::Recovery && crossflow.magnitude() > f64::EPSILON {
+                -crossflow.normalize()
+                    * dynamic_pressure
+                    * self.reference_area
+                    * self.normal_force_slope
+                    * sample.angle_of_attack.sin()
+            } else {
+                Vector3::new(0.0, 0.0, 0.0)
+            };
+
+        vec![
+            Applied {
+                overlay: Overlay::Thrust,
+                vector: axis * sample.thrust,
+                from_nose: self.length,
+            },
+            Applied {
+                overlay: Overlay::Drag,
+                vector: -flow * sample.drag,
+                from_nose: sample.cp,
+            },
+            Applied {
+                overlay: Overlay::NormalForce,
+                vector: normal,
+                from_nose: sample.cp,
+            },
+            Applied {
+                overlay: Overlay::Gravity,
+                vector: Vector3::new(0.0, 0.0, -STANDARD_GRAVITY * sample.mass),
+                from_nose: sample.cg,
+            },
+            Applied {
+                overlay: Overlay::Wind,
+                vector: wind,
+                from_nose: sample.cp,
+            },
+        ]
+    }
+
+    /// Rebuild the overlays for the sample being shown, if anything changed
+    pub fn update(
+        &mut self,
+        context: &Context,
+        flight: &Flight,
+        sample: &FlightSample,
+    ) -> Result<(), Box<dyn Error>> {
+        let samples = &flight.record.samples;
+
+        // Keep the arrows to scale as a live flight comes in
+        if self.max_force.1 != samples.len() {
+            let largest = samples
+                .iter()
+                .map(|sample| {
+                    sample
+                        .thrust
+                        .max(sample.drag)
+                        .max(STANDARD_GRAVITY * sample.mass)
+                })
+                .fold(0.0, f64::max);
+
+            self.max_force = (largest, samples.len());
+            self.drawn = None;
+        }
+
+        let state = (sample.time, self.shown, self.scale);
+
+        if self.drawn == Some(state) {
+            return Ok(());
+        }
+
+        self.applied = self.forces(sample);
+        self.angle_of_attack = sample.angle_of_attack;
+        self.geometries.clear();
+
+        let (body_length, length) = (self.length, self.length as f32);
+        let body = body_to_scene(sample.position, sample.attitude);
+        let on_axis = |from_nose: f64| {
+            (body * vec4(0.0, 0.0, (body_length - from_nose) as f32, 1.0)).truncate()
+        };
+        let radius = ARROW_SCALE * length;
+        let force_scale = self.scale * FORCE_LENGTH * length / self.max_force.0.max(1e-3) as f32;
+
+        for applied in &self.applied {
+            if !self.is_shown(applied.overlay) {
+                continue;
+            }
+
+            let scale = match applied.overlay {
+                Overlay::Wind => self.scale * length / WIND_SPEED_SCALE,
+                _ => force_scale,
+            };
+            let vector = to_scene(applied.vector) * scale;
+
+            // Forces point at where they act, and the wind blows onto it
+            let point = on_axis(applied.from_nose);
+            let (from, to) = match applied.overlay {
+                Overlay::Wind | Overlay::Drag => (point - vector, point),
+                _ => (point, point + vector),
+            };
+
+            if vector.magnitude() < radius {
+                continue;
+            }
+
+            let arrow = mesh::arrow(applied.overlay.label(), from, to, radius, ARROW_SIDES);
+
+            self.geometries.push((
+                Model::new(context, &arrow)?,
+                material(context, applied.overlay.color())?,
+            ));
+        }
+
+        if self.is_shown(Overlay::Centers) {
+            let size = CENTER_SCALE * self.diameter as f32;
+
+            for &(label, from_nose, color) in
+                &[("CG", sample.cg, CG_COLOR), ("CP", sample.cp, CP_COLOR)]
+            {
+                let marker = mesh::octahedron(label, on_axis(from_nose), size);
+
+                self.geometries
+                    .push((Model::new(context, &marker)?, material(context, color)?));
+            }
+        }
+
+        if self.is_shown(Overlay::AngleOfAttack) {
+            let indicator = angle_indicator(
+                on_axis(0.0),
+                to_scene(sample.attitude.rotate_vector(Vector3::unit_z())),
+                to_scene(sample.velocity - self.launch.wind()),
+                ANGLE_LENGTH * length,
+            );
+            let mesh = mesh::tube("angle of attack", &indicator, 0.5 * radius, ARROW_SIDES);
+
+            self.geometries.push((
+                Model::new(context, &mesh)?,
+                material(context, Overlay::AngleOfAttack.color())?,
+            ));
+        }
+
+        self.drawn = Some(state);
+
+        Ok(())
+    }
+
+    /// Everything to draw in the geometry pass
+    pub fn geometries(&self) -> impl Iterator<Item = (&Model, &Material)> {
+        self.geometries
+            .iter()
+            .map(|(model, material)| (model, material))
+    }
+
+    /// Toggles for each overlay, with the values at the time being shown
+    pub fn controls(&mut self, ui: &mut Ui) {
+        for &overlay in &Overlay::ALL {
+            let value = match overlay {
+                Overlay::Centers => "CG blue, CP red".to_string(),
+                Overlay::AngleOfAttack => format!("{:.1}°", self.angle_of_attack.to_degrees()),
+                Overlay::Wind => format!("{:.1} m/s", self.launch.wind_speed),
+                _ => self
+                    .applied
+                    .iter()
+                    .find(|applied| applied.overlay == overlay)
+                    .map(|applied| format!("{:.1} N", applied.vector.magnitude()))
+                    .unwrap_or_default(),
+            };
+            let (r, g, b) = overlay.color();
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.shown[overlay as usize], overlay.label());
+                ui.colored_label(Color32::from_rgb(r, g, b), value);
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Arrow scale");
+            ui.add(
+                DragValue::new(&mut self.scale)
+                    .speed(0.05)
+                    .clamp_range(0.05..=20.0),
+            );
+        });
+    }
+}
+
+/// Lines along the body axis and the direction of travel through the air from
+/// the nose tip, joined by an arc across the angle of attack between them
+fn angle_indicator(nose: Vec3, axis: Vec3, travel: Vec3, length: f32) -> Vec<(Vec3, Vec3)> {
+    let axis = axis.normalize();
+    let mut segments = vec![(nose, nose + axis * length)];
+
+    if travel.magnitude() < f32::EPSILON {
+        return segments;
+    }
+
+    let travel = travel.normalize();
+    let angle = axis.dot(travel).clamp(-1.0, 1.0).acos();
+    segments.push((nose, nose + travel * length));
+
+    if angle.sin() < 1e-3 {
+        return segments;
+    }
+
+    // Sweep from one direction to the other at a constant distance
+    let arc: Vec<_> = (0..=ARC_SEGMENTS)
+        .map(|step| {
+            let t = step as f32 / ARC_SEGMENTS as f32;
+            let direction =
+                (axis * ((1.0 - t) * angle).sin() + travel * (t * angle).sin()) / angle.sin();
+
+            nose + direction * (0.8 * length)
+        })
+        .collect();
+    segments.extend(arc.windows(2).map(|pair| (pair[0], pair[1])));
+
+    segments
+}
@@ -0,0 +1,174 @@
+//! Following a running simulation from the telemetry it publishes
+
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use impulse_core::sim::{SimulationConfig, TelemetryFrame, TelemetryReader};
+use tracing::{info, warn};
+
+use crate::flight::Flight;
+
+/// Wait before trying to connect again, doubled after each failure
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Longest wait between attempts to connect
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(4);
+
+/// News from the thread receiving telemetry
+enum Message {
+    Connected,
+    Frame(Box<TelemetryFrame>),
+    /// The connection was lost, for a reason
+    Lost(String),
+}
+
+/// How the flight changed with the latest telemetry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveUpdate {
+    Unchanged,
+    /// A new flight started, replacing the last one
+    Started,
+    /// More of the current flight arrived
+    Extended,
+}
+
+/// Telemetry from a running simulation, received on a background thread that
+/// reconnects whenever the connection is lost
+pub struct LiveFeed {
+    pub address: String,
+    receiver: Receiver<Message>,
+    connected: bool,
+    /// Configuration of the flight being received, used to summarize it
+    config: Option<SimulationConfig>,
+}
+
+impl LiveFeed {
+    /// Start connecting to a simulation publishing on an address
+    pub fn connect(address: String) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let receiving = address.clone();
+
+        thread::spawn(move || receive(&receiving, sender));
+
+        Self {
+            address,
+            receiver,
+            connected: false,
+            config: None,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        if self.connected {
+            format!("Connected to {}", self.address)
+        } else {
+            format!("Waiting for a simulation on {}", self.address)
+        }
+    }
+
+    /// Apply the telemetry received since the last update to the flight being
+    /// followed
+    pub fn update(&mut self, flight: &mut Option<Flight>) -> LiveUpdate {
+        let mut update = LiveUpdate::Unchanged;
+
+        while let Ok(message) = self.receiver.try_recv() {
+            let frame = match message {
+                Message::Connected => {
+                    self.connected = true;
+                    continue;
+                }
+                Message::Lost(reason) => {
+                    warn!(%reason, "Lost connection to the simulation");
+
+                    self.connected = false;
+                    continue;
+                }
+                Message::Frame(frame) => *frame,
+            };
+
+            if let TelemetryFrame::Start { .. } = frame {
+                self.config = frame.config();
+
+                if let (TelemetryFrame::Start { rocket, .. }, Some(config)) = (frame, &self.config)
+                {
+                    info!(name = %rocket.name, "Following a new flight");
+
+                    *flight = Some(Flight::live(rocket, config));
+                    update = LiveUpdate::Started;
+                }
+
+                continue;
+            }
+
+            // Telemetry from partway through a flight is only taken after its
+            // start, which is always sent first
+            let current = match flight.as_mut() {
+                Some(current) => current,
+                None => continue,
+            };
+
+            match frame {
+                TelemetryFrame::Sample(sample) => {
+                    current.record.samples.push(sample.into());
+
+                    if update == LiveUpdate::Unchanged {
+                        update = LiveUpdate::Extended;
+                    }
+                }
+                TelemetryFrame::Event(event) => current.record.events.push(event),
+                TelemetryFrame::End => {
+                    if let Some(config) = &self.config {
+                        current.finish(config);
+                    }
+                }
+                TelemetryFrame::Start { .. } | TelemetryFrame::Unknown => {}
+            }
+        }
+
+        update
+    }
+}
+
+/// Receive telemetry until the feed is dropped, connecting again whenever the
+/// connection is lost
+fn receive(address: &str, sender: Sender<Message>) {
+    let mut delay = RETRY_DELAY;
+
+    loop {
+        let mut reader = match TelemetryReader::connect(address) {
+            Ok(reader) => reader,
+            Err(_) => {
+                thread::sleep(delay);
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+                continue;
+            }
+        };
+
+        info!(address, "Connected to the simulation");
+
+        delay = RETRY_DELAY;
+
+        if sender.send(Message::Connected).is_err() {
+            return;
+        }
+
+        let reason = loop {
+            match reader.read_frame() {
+                Ok(Some(frame)) => {
+                    if sender.send(Message::Frame(Box::new(frame))).is_err() {
+                        return;
+                    }
+                }
+                Ok(None) => break "the simulation closed the connection".to_string(),
+                Err(error) => break error.to_string(),
+            }
+        };
+
+        if sender.send(Message::Lost(reason)).is_err() {
+            return;
+        }
+    }
+}
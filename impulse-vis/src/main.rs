@@ -1,13 +1,183 @@
-use std::{collections::VecDeque, error::Error, iter, time::Instant};
+//! 3D visualization of simulated flights
 
+use std::{
+    collections::VecDeque,
+    error::Error,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use cgmath::{Quaternion, Vector3, Zero};
+use impulse_core::{
+    rocket::Rocket,
+    sim::{LaunchConditions, DEFAULT_TELEMETRY_ADDRESS},
+};
+use structopt::StructOpt;
 use three_d::{core::Indices, *};
+use tracing::{info, warn};
+
+use camera::{CameraMode, CameraRig, Subject};
+use compare::Comparison;
+use export::ExportSettings;
+use flight::{Bounds, Flight};
+use forces::ForceOverlays;
+use live::{LiveFeed, LiveUpdate};
+use model::RocketModel;
+use playback::Playback;
+use scene::Scene;
+use site::{reach, LaunchSite, MIN_REACH};
+use terrain::Terrain;
+use trajectory::{ColorMode, Trajectory};
+
+mod camera;
+mod compare;
+mod export;
+mod flight;
+mod forces;
+mod live;
+mod mesh;
+mod model;
+mod playback;
+mod scene;
+mod site;
+mod terrain;
+mod trajectory;
+
+/// Shortest time between rebuilds of the flight path of a live flight
+const LIVE_REFRESH: Duration = Duration::from_millis(250);
+
+/// Impulse flight visualization
+#[derive(Debug, StructOpt)]
+struct Options {
+    /// Project file to simulate and show the flight of
+    #[structopt(parse(from_os_str))]
+    project: Option<PathBuf>,
+    /// Grayscale PNG or GeoTIFF elevation model of the terrain around the
+    /// launch rail
+    #[structopt(long, parse(from_os_str))]
+    terrain: Option<PathBuf>,
+    /// Distance covered by the elevation model from west to east (m)
+    #[structopt(long, default_value = "1000")]
+    terrain_width: f32,
+    /// Difference in height between the darkest and brightest points of the
+    /// elevation model (m)
+    #[structopt(long, default_value = "100")]
+    terrain_relief: f32,
+    /// Follow a simulation publishing live telemetry, such as one started with
+    /// `impulse run --publish`, on an address (127.0.0.1:7437 by default)
+    #[structopt(long)]
+    connect: Option<Option<String>>,
+    /// Render the playback of the flight to numbered PNG images in a
+    /// directory, without opening a window
+    #[structopt(
+        long,
+        parse(from_os_str),
+        requires = "project",
+        conflicts_with = "connect"
+    )]
+    export: Option<PathBuf>,
+    /// Width of exported images in pixels
+    #[structopt(long, default_value = "1280")]
+    width: u32,
+    /// Height of exported images in pixels
+    #[structopt(long, default_value = "720")]
+    height: u32,
+    /// Frames per second of exported playback
+    #[structopt(long, default_value = "30")]
+    framerate: f64,
+    /// Seconds of flight played per second of exported playback
+    #[structopt(long, default_value = "1")]
+    speed: f64,
+    /// Camera mode of exported playback: orbit, chase or ground
+    #[structopt(long, default_value = "orbit")]
+    camera: CameraMode,
+    /// Also assemble the exported images into an animated GIF or WebM video
+    #[structopt(long, parse(from_os_str), requires = "export")]
+    animation: Option<PathBuf>,
+    /// Other project files to fly alongside the project, for comparing
+    /// designs
+    #[structopt(
+        long,
+        parse(from_os_str),
+        requires = "project",
+        conflicts_with = "connect"
+    )]
+    compare: Vec<PathBuf>,
+    /// Also fly some of the dispersed flights of the project's Monte Carlo
+    /// analysis
+    #[structopt(long, requires = "project", conflicts_with = "connect")]
+    monte_carlo: Option<usize>,
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let options = Options::from_args();
+
     tracing_subscriber::fmt().pretty().init();
 
+    let mut flight = match &options.project {
+        Some(path) => {
+            info!(path = %path.display(), "Simulating project");
+
+            Some(Flight::load(path)?)
+        }
+        None => None,
+    };
+
+    let terrain = match &options.terrain {
+        Some(path) => {
+            info!(path = %path.display(), "Loading terrain");
+
+            Some(Terrain::load(
+                path,
+                options.terrain_width,
+                options.terrain_relief,
+            )?)
+        }
+        None => None,
+    };
+
+    if let (Some(directory), Some(flight)) = (&options.export, &flight) {
+        export::export(
+            flight,
+            terrain.as_ref(),
+            &ExportSettings {
+                directory: directory.clone(),
+                width: options.width,
+                height: options.height,
+                framerate: options.framerate,
+                speed: options.speed,
+                camera: options.camera,
+                animation: options.animation.clone(),
+            },
+        )?;
+
+        return Ok(());
+    }
+
+    let mut compared = Vec::new();
+
+    for path in &options.compare {
+        info!(path = %path.display(), "Simulating project to compare");
+
+        compared.push(Flight::load(path)?);
+    }
+
+    if let (Some(runs), Some(path)) = (options.monte_carlo, &options.project) {
+        info!(runs, "Simulating dispersed flights");
+
+        compared.extend(Flight::dispersed(path, runs)?);
+    }
+
+    let mut live = options.connect.clone().map(|address| {
+        LiveFeed::connect(address.unwrap_or_else(|| DEFAULT_TELEMETRY_ADDRESS.to_string()))
+    });
+
     // Create a window
     let window = Window::new(WindowSettings {
-        title: "Triangle!".to_string(),
+        title: match &flight {
+            Some(flight) => format!("Impulse - {}", flight.name),
+            None => "Impulse".to_string(),
+        },
         max_size: Some((1280, 720)),
         ..Default::default()
     })?;
@@ -16,65 +186,90 @@ fn main() -> Result<(), Box<dyn Error>> {
     let context = window.gl()?;
 
     // Create the lighting pipeline
-    let mut pipeline = DeferredPipeline::new(&context)?;
+    let mut scene = Scene::new(&context)?;
+
+    // Frame the whole flight and every flight compared with it, looking from
+    // the south east
+    let (mut target, mut radius) = match &flight {
+        Some(flight) => {
+            let bounds = compared
+                .iter()
+                .map(Flight::bounds)
+                .fold(flight.bounds(), Bounds::union);
+
+            (bounds.center(), bounds.radius().max(1.0))
+        }
+        None => (vec3(0.0, 0.0, 0.0), 10.0),
+    };
 
     // Create a camera
-    let mut camera = Camera::new_perspective(
-        &context,
-        window.viewport().unwrap(),
-        vec3(0.0, 20.0, 20.0),
-        vec3(0.0, 0.0, 0.0),
-        vec3(0.0, 1.0, 0.0),
-        degrees(45.0),
-        0.1,
-        10000.0,
-    )?;
-    let mut control = OrbitControl::new(
-        *camera.target(),
-        0.5 * camera.target().distance(*camera.position()),
-        10.0 * camera.target().distance(*camera.position()),
-    );
+    let (mut camera, distance) =
+        camera::overview(&context, window.viewport().unwrap(), target, radius)?;
 
     let axes = Axes::new(&context, 2.0, 10.0)?;
     let mut gui = GUI::new(&context)?;
 
-    let material = Material::new(
-        &context,
-        &CPUMaterial {
-            albedo: Color::GREEN,
-            ..Default::default()
-        },
-    )?;
-
-    let ground = Model::new(
-        &context,
-        &CPUMesh {
-            name: "ground".into(),
-            material_name: None,
-            positions: [
-                vec3(-10000.0, 0.0, 10000.0),
-                vec3(10000.0, 0.0, 10000.0),
-                vec3(0.0, 0.0, -10000.0),
-            ]
-            .iter()
-            .flat_map(|v| [v.x, v.y, v.z])
-            .collect(),
-            indices: None,
-            normals: Some(
-                iter::repeat(vec3(0.0, 1.0, 0.0))
-                    .take(3)
-                    .flat_map(|v| [v.x, v.y, v.z])
-                    .collect(),
-            ),
-            uvs: None,
-            colors: None,
-        },
-    )?;
+    // Lay out the launch site far enough to take in the whole flight
+    let mut site = match &flight {
+        Some(flight) => LaunchSite::new(
+            &context,
+            &flight.record.launch,
+            compared.iter().map(reach).fold(reach(flight), f32::max),
+            terrain.as_ref(),
+        )?,
+        None => LaunchSite::new(
+            &context,
+            &LaunchConditions::default(),
+            MIN_REACH,
+            terrain.as_ref(),
+        )?,
+    };
 
-    let mut cube = Model::new(&context, &CPUMesh::cube())?;
-    cube.set_transformation(Mat4::from_translation(Vec3::new(0.0, 10.0, 2.0)));
+    // Stand the rocket on the launch rail, or show the default design upright
+    // on the pad when there is no flight
+    let design = match &flight {
+        Some(flight) => flight.rocket.clone(),
+        None => Rocket::default(),
+    };
+    let mut rocket = RocketModel::new(&context, &design)?;
+    let mut rocket_length = design.length();
+    let mut overlays = ForceOverlays::new(
+        &design,
+        &flight
+            .as_ref()
+            .map_or_else(LaunchConditions::default, |flight| flight.record.launch),
+    );
+    match flight
+        .as_ref()
+        .and_then(|flight| flight.record.samples.first())
+    {
+        Some(sample) => rocket.set_pose(sample.position, sample.attitude),
+        None => rocket.set_pose(Vector3::zero(), Quaternion::new(1.0, 0.0, 0.0, 0.0)),
+    }
 
-    let mut sunlight = DirectionalLight::new(&context, 0.5, Color::WHITE, &vec3(0.0, -1.0, 0.2))?;
+    let mut trajectory = match &flight {
+        Some(flight) => Some(Trajectory::new(&context, flight, ColorMode::Velocity)?),
+        None => None,
+    };
+    let mut color_mode = ColorMode::Velocity;
+    let mut drawn_samples = flight
+        .as_ref()
+        .map_or(0, |flight| flight.record.samples.len());
+    let mut last_drawn = Instant::now();
+
+    let mut comparison = Comparison::new(&context, compared, radius)?;
+    let mut playback = Playback::new(
+        flight
+            .as_ref()
+            .map_or(0.0, Flight::duration)
+            .max(comparison.duration()),
+    );
+
+    let mut rig = CameraRig::new(
+        &camera,
+        (0.01 * distance, 10.0 * distance),
+        rocket_length as f32,
+    );
 
     let mut fps_rolling_average = VecDeque::with_capacity(1000);
     let mut frame_time_rolling_average = VecDeque::with_capacity(1000);
@@ -88,6 +283,46 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
         fps_rolling_average.push_front(1000.0 / frame_input.elapsed_time);
 
+        // Take in the telemetry of a live flight, starting over with each new
+        // flight
+        if let Some(feed) = &mut live {
+            let update = feed.update(&mut flight);
+
+            if let (LiveUpdate::Started, Some(current)) = (update, &flight) {
+                match RocketModel::new(&context, &current.rocket) {
+                    Ok(model) => {
+                        rocket = model;
+                        rocket_length = current.rocket.length();
+                    }
+                    Err(error) => warn!(%error, "Failed to build the rocket model"),
+                }
+                overlays.reset(&current.rocket, &current.record.launch);
+
+                match LaunchSite::new(
+                    &context,
+                    &current.record.launch,
+                    reach(current),
+                    terrain.as_ref(),
+                ) {
+                    Ok(rebuilt) => site = rebuilt,
+                    Err(error) => warn!(%error, "Failed to build the launch site"),
+                }
+
+                trajectory = None;
+                drawn_samples = 0;
+                playback = Playback::new(0.0);
+            }
+
+            if let (LiveUpdate::Started | LiveUpdate::Extended, Some(current)) = (update, &flight) {
+                playback.extend(current.duration());
+            }
+        }
+
+        playback.advance(frame_input.elapsed_time / 1000.0);
+        let sample = flight
+            .as_ref()
+            .and_then(|flight| flight.record.sample_at(playback.time));
+
         // Ensure the viewport matches the current window viewport which changes if the window is resized
         gui.update(&mut frame_input, |gui_context| {
             use three_d::egui::{plot::*, *};
@@ -95,6 +330,73 @@ fn main() -> Result<(), Box<dyn Error>> {
             Window::new("Visualization Properties")
                 // .default_size((0.0, 0.0))
                 .show(&gui_context, |ui| {
+                    if let Some(feed) = &live {
+                        ui.label(feed.status());
+                    }
+
+                    match &flight {
+                        Some(flight) => {
+                            ui.heading(&flight.name);
+
+                            match &flight.summary {
+                                Some(summary) => {
+                                    for (label, value) in summary.rows().into_iter().take(3) {
+                                        ui.label(format!("{}: {}", label, value));
+                                    }
+                                }
+                                None => {
+                                    ui.label("In flight");
+                                }
+                            }
+
+                            ui.separator();
+                            ui.heading("Flight Path");
+                            ui.horizontal(|ui| {
+                                ui.label("Color by");
+                                ui.radio_value(&mut color_mode, ColorMode::Velocity, "Velocity");
+                                ui.radio_value(&mut color_mode, ColorMode::Phase, "Phase");
+                            });
+
+                            if let Some(trajectory) = &trajectory {
+                                for (label, (r, g, b)) in trajectory.legend() {
+                                    ui.colored_label(Color32::from_rgb(*r, *g, *b), label);
+                                }
+                            }
+
+                            if !comparison.is_empty() {
+                                ui.separator();
+                                ui.heading("Comparison");
+                                comparison.controls(ui, flight);
+                            }
+
+                            ui.separator();
+                            ui.heading("Playback");
+                            playback.controls(ui);
+
+                            if let Some(sample) = &sample {
+                                playback::telemetry(ui, sample);
+                            }
+
+                            ui.separator();
+                            ui.heading("Forces");
+                            overlays.controls(ui);
+                        }
+                        None if live.is_some() => {}
+                        None => {
+                            ui.label("Pass a project file to show its flight");
+                        }
+                    }
+
+                    ui.separator();
+                    ui.heading("Launch Site");
+                    for (label, value) in site.rows() {
+                        ui.label(format!("{}: {}", label, value));
+                    }
+
+                    ui.separator();
+                    ui.heading("Camera");
+                    rig.controls(ui, &camera);
+
                     ui.heading("Camera Position");
                     ui.label(format!("{:?}", camera.position()));
                     ui.heading("Camera Target");
@@ -152,40 +454,92 @@ fn main() -> Result<(), Box<dyn Error>> {
         })
         .unwrap();
 
-        // Setup camera
-        camera.set_viewport(frame_input.viewport).unwrap();
-        control
-            .handle_events(&mut camera, &mut frame_input.events)
-            .unwrap();
+        // Follow the timeline, which the scrubber may have moved
+        let current = flight
+            .as_ref()
+            .and_then(|flight| Some((flight, flight.record.sample_at(playback.time)?)));
+        comparison.set_time(playback.time);
+        let subject = match current {
+            Some((flight, sample)) => {
+                rocket.set_pose(sample.position, sample.attitude);
 
-        // Move cube
-        cube.set_transformation(Mat4::from_angle_x(radians(0.01)).concat(cube.transformation()));
+                if let Err(error) = overlays.update(&context, flight, &sample) {
+                    warn!(%error, "Failed to build the force overlays");
+                }
 
-        // Calculate shadows
-        sunlight
-            .generate_shadow_map(&Vec3::new(0.0, 0.0, 0.0), 40.0, 200.0, 1024, 1024, &[&cube])
-            .unwrap();
+                Subject::new(&sample, rocket_length)
+            }
+            None => Subject {
+                center: vec3(0.0, rocket_length as f32 / 2.0, 0.0),
+                axis: vec3(0.0, 1.0, 0.0),
+            },
+        };
 
-        // Calculate geometry
-        pipeline
-            .geometry_pass(&camera, &[(&ground, &material), (&cube, &material)])
-            .unwrap();
+        // Rebuild the flight path when its coloring changes, and every so
+        // often as a live flight comes in
+        let recolored = matches!(&trajectory, Some(current) if current.mode != color_mode);
+        let grown = matches!(&flight, Some(flight) if flight.record.samples.len() != drawn_samples)
+            && last_drawn.elapsed() >= LIVE_REFRESH;
 
-        // Start writing to the screen and clears the color and depth
-        Screen::write(
-            &context,
-            ClearState::color_and_depth(0.2, 0.2, 0.2, 1.0, 1.0),
-            || {
-                // axes.render(&camera)?;
-                pipeline.light_pass(&camera, None, &[&sunlight], &[], &[])?;
+        if let (Some(flight), true) = (&flight, recolored || grown) {
+            match Trajectory::new(&context, flight, color_mode) {
+                Ok(rebuilt) => {
+                    trajectory = Some(rebuilt);
 
-                gui.render()?;
+                    // Keep the shadows and the ground around every flight
+                    let bounds = comparison.bounds(flight.bounds());
+                    target = bounds.center();
+                    radius = bounds.radius().max(1.0);
 
-                Ok(())
-            },
+                    if terrain.is_none() && reach(flight) > site.layout.half_size() {
+                        match LaunchSite::new(&context, &flight.record.launch, reach(flight), None)
+                        {
+                            Ok(rebuilt) => site = rebuilt,
+                            Err(error) => warn!(%error, "Failed to build the launch site"),
+                        }
+                    }
+                }
+                Err(error) => {
+                    warn!(%error, "Failed to build the flight path");
+
+                    if let Some(current) = &trajectory {
+                        color_mode = current.mode;
+                    }
+                }
+            }
+
+            drawn_samples = flight.record.samples.len();
+            last_drawn = Instant::now();
+        }
+
+        // Setup camera
+        camera.set_viewport(frame_input.viewport).unwrap();
+        rig.update(
+            &mut camera,
+            &mut frame_input.events,
+            (frame_input.elapsed_time / 1000.0) as f32,
+            subject,
         )
         .unwrap();
 
+        scene
+            .render(
+                &context,
+                &camera,
+                site.ground(),
+                site.fixtures()
+                    .chain(rocket.geometries())
+                    .chain(overlays.geometries())
+                    .chain(comparison.geometries())
+                    .chain(trajectory.iter().flat_map(Trajectory::geometries)),
+                (target, radius),
+                || {
+                    // axes.render(&camera)?;
+                    gui.render()
+                },
+            )
+            .unwrap();
+
         if frame_time_rolling_average.len() == frame_time_rolling_average.capacity() {
             frame_time_rolling_average.pop_back();
         }
@@ -0,0 +1,223 @@
+//! Procedural meshes, built as plain [`CPUMesh`] data without a graphics
+//! context
+
+use std::f32::consts::TAU;
+
+use cgmath::InnerSpace;
+use three_d::{core::Indices, *};
+
+pub use self::{
+    ground::{distance_rings, grid_lines, ground_tiles, GroundLayout},
+    rocket::rocket_meshes,
+};
+
+mod ground;
+mod rocket;
+
+/// A color as red, green and blue, shared by the scene and the legend
+pub type Rgb = (u8, u8, u8);
+
+/// Accumulates triangles into a mesh
+#[derive(Default)]
+struct MeshBuilder {
+    positions: Vec<f32>,
+    normals: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    /// Add a triangle whose corners are counterclockwise seen from outside
+    fn triangle(&mut self, a: Vec3, b: Vec3, c: Vec3) {
+        let normal = (b - a).cross(c - a);
+
+        // Skip degenerate triangles, which have no direction to shade
+        if normal.magnitude2() == 0.0 {
+            return;
+        }
+
+        self.triangle_with_normal([a, b, c], normal.normalize());
+    }
+
+    fn triangle_with_normal(&mut self, corners: [Vec3; 3], normal: Vec3) {
+        let indices = [
+            self.vertex(corners[0], normal),
+            self.vertex(corners[1], normal),
+            self.vertex(corners[2], normal),
+        ];
+
+        self.indices.extend_from_slice(&indices);
+    }
+
+    /// Add a vertex, returning its index
+    fn vertex(&mut self, position: Vec3, normal: Vec3) -> u32 {
+        let index = (self.positions.len() / 3) as u32;
+
+        self.positions
+            .extend_from_slice(&[position.x, position.y, position.z]);
+        self.normals
+            .extend_from_slice(&[normal.x, normal.y, normal.z]);
+
+        index
+    }
+
+    /// Add a quad whose corners are counterclockwise seen from outside
+    fn quad(&mut self, a: Vec3, b: Vec3, c: Vec3, d: Vec3) {
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+
+    /// Add a quad facing away from a point inside a convex solid, whatever the
+    /// order of its corners
+    fn quad_facing_away(&mut self, corners: [Vec3; 4], inside: Vec3) {
+        let [a, b, c, d] = corners;
+
+        if (b - a).cross(c - a).dot(a - inside) >= 0.0 {
+            self.quad(a, b, c, d);
+        } else {
+            self.quad(d, c, b, a);
+        }
+    }
+
+    /// Add a quad with its own normal at each corner, counterclockwise seen
+    /// from outside
+    fn smooth_quad(&mut self, corners: [(Vec3, Vec3); 4]) {
+        let [a, b, c, d] = corners;
+        let indices = [
+            self.vertex(a.0, a.1),
+            self.vertex(b.0, b.1),
+            self.vertex(c.0, c.1),
+            self.vertex(d.0, d.1),
+        ];
+
+        self.indices.extend_from_slice(&[
+            indices[0], indices[1], indices[2], indices[0], indices[2], indices[3],
+        ]);
+    }
+
+    fn build(self, name: &str) -> CPUMesh {
+        CPUMesh {
+            name: name.into(),
+            material_name: None,
+            positions: self.positions,
+            indices: Some(Indices::U32(self.indices)),
+            normals: Some(self.normals),
+            uvs: None,
+            colors: None,
+        }
+    }
+}
+
+/// Two unit vectors perpendicular to a direction and to each other, forming a
+/// right handed frame with it
+fn perpendicular_axes(direction: Vec3) -> (Vec3, Vec3) {
+    let reference = if direction.y.abs() < 0.9 {
+        vec3(0.0, 1.0, 0.0)
+    } else {
+        vec3(1.0, 0.0, 0.0)
+    };
+    let u = direction.cross(reference).normalize();
+    let v = direction.cross(u);
+
+    (u, v)
+}
+
+/// Thin prisms along each segment, used to draw lines with lighting
+pub fn tube(name: &str, segments: &[(Vec3, Vec3)], radius: f32, sides: u32) -> CPUMesh {
+    let mut builder = MeshBuilder::default();
+
+    for &(start, end) in segments {
+        let length = (end - start).magnitude();
+
+        if length == 0.0 {
+            continue;
+        }
+
+        let (u, v) = perpendicular_axes((end - start) / length);
+        let ring = |side: u32| {
+            let angle = side as f32 / sides as f32 * TAU;
+
+            u * angle.cos() + v * angle.sin()
+        };
+
+        for side in 0..sides {
+            let (a, b) = (ring(side), ring(side + 1));
+
+            builder.quad(
+                start + a * radius,
+                start + b * radius,
+                end + b * radius,
+                end + a * radius,
+            );
+        }
+    }
+
+    builder.build(name)
+}
+
+/// Octahedron centered on a point, used to mark places along a trajectory
+pub fn octahedron(name: &str, center: Vec3, size: f32) -> CPUMesh {
+    let mut builder = MeshBuilder::default();
+
+    for &x in &[-1.0f32, 1.0] {
+        for &y in &[-1.0f32, 1.0] {
+            for &z in &[-1.0f32, 1.0] {
+                let a = center + vec3(x, 0.0, 0.0) * size;
+                let b = center + vec3(0.0, y, 0.0) * size;
+                let c = center + vec3(0.0, 0.0, z) * size;
+
+                // Keep every face counterclockwise from outside
+                if x * y * z > 0.0 {
+                    builder.triangle(a, b, c);
+                } else {
+                    builder.triangle(a, c, b);
+                }
+            }
+        }
+    }
+
+    builder.build(name)
+}
+
+/// Arrow from one point to another with a shaft of some radius, used to show
+/// directions such as the wind
+pub fn arrow(name: &str, from: Vec3, to: Vec3, radius: f32, sides: u32) -> CPUMesh {
+    let mut builder = MeshBuilder::default();
+    let length = (to - from).magnitude();
+
+    if length == 0.0 {
+        return builder.build(name);
+    }
+
+    let direction = (to - from) / length;
+    let (u, v) = perpendicular_axes(direction);
+    let ring = |side: u32| {
+        let angle = side as f32 / sides as f32 * TAU;
+
+        u * angle.cos() + v * angle.sin()
+    };
+
+    // The head takes up to a third of the arrow
+    let head_radius = 2.5 * radius;
+    let base = to - direction * (3.0 * head_radius).min(length / 3.0);
+
+    for side in 0..sides {
+        let (a, b) = (ring(side), ring(side + 1));
+
+        builder.quad(
+            from + a * radius,
+            from + b * radius,
+            base + b * radius,
+            base + a * radius,
+        );
+        builder.triangle(from, from + b * radius, from + a * radius);
+        builder.quad(
+            base + a * radius,
+            base + b * radius,
+            base + b * head_radius,
+            base + a * head_radius,
+        );
+        builder.triangle(base + a * head_radius, base + b * head_radius, to);
+    }
+
+    builder.build(name)
+}
@@ -0,0 +1,158 @@
+//! Meshes of the ground around the launch site, following the height of the
+//! terrain
+//!
+//! Heights are given as a function of the position east (x) and south (z) of
+//! the launch rail in the scene.
+
+use std::f32::consts::TAU;
+
+use cgmath::InnerSpace;
+use three_d::*;
+
+use super::MeshBuilder;
+
+/// Sides of the polygons that distance rings are drawn as
+const RING_SIDES: u32 = 128;
+
+/// Square of ground centered on the launch rail, divided into square tiles
+#[derive(Debug, Clone, Copy)]
+pub struct GroundLayout {
+    /// Tiles along each side, which is even so the rail sits on a corner
+    pub tiles: u32,
+    /// Width of a tile (m)
+    pub tile_size: f32,
+    /// Cells each side of a tile is divided into to follow the terrain
+    pub cells_per_tile: u32,
+}
+
+impl GroundLayout {
+    /// Distance from the launch rail to the edges of the ground (m)
+    pub fn half_size(&self) -> f32 {
+        self.tiles as f32 / 2.0 * self.tile_size
+    }
+
+    fn cell_size(&self) -> f32 {
+        self.tile_size / self.cells_per_tile as f32
+    }
+}
+
+/// Point on the ground and the normal of the ground there
+fn surface(height: &dyn Fn(f32, f32) -> f32, x: f32, z: f32, step: f32) -> (Vec3, Vec3) {
+    let slope_x = (height(x + step, z) - height(x - step, z)) / (2.0 * step);
+    let slope_z = (height(x, z + step) - height(x, z - step)) / (2.0 * step);
+
+    (
+        vec3(x, height(x, z), z),
+        vec3(-slope_x, 1.0, -slope_z).normalize(),
+    )
+}
+
+/// Checkered tiles of the ground, as separate meshes of the light and dark
+/// tiles
+pub fn ground_tiles(layout: &GroundLayout, height: &dyn Fn(f32, f32) -> f32) -> [CPUMesh; 2] {
+    let mut builders = [MeshBuilder::default(), MeshBuilder::default()];
+    let cells = layout.tiles * layout.cells_per_tile;
+    let (half, cell) = (layout.half_size(), layout.cell_size());
+
+    for column in 0..cells {
+        for row in 0..cells {
+            let (x0, z0) = (column as f32 * cell - half, row as f32 * cell - half);
+            let (x1, z1) = (x0 + cell, z0 + cell);
+            let corner = |x, z| surface(height, x, z, cell);
+            let checker = (column / layout.cells_per_tile + row / layout.cells_per_tile) % 2;
+
+            // Counterclockwise seen from above
+            builders[checker as usize].smooth_quad([
+                corner(x0, z0),
+                corner(x0, z1),
+                corner(x1, z1),
+                corner(x1, z0),
+            ]);
+        }
+    }
+
+    let [light, dark] = builders;
+
+    [light.build("ground"), dark.build("ground")]
+}
+
+/// Lines along the edges of the tiles, of some width (m), lifted a little
+/// above the ground so they are not hidden by it
+pub fn grid_lines(
+    layout: &GroundLayout,
+    width: f32,
+    lift: f32,
+    height: &dyn Fn(f32, f32) -> f32,
+) -> CPUMesh {
+    let mut builder = MeshBuilder::default();
+    let cells = layout.tiles * layout.cells_per_tile;
+    let (half, cell) = (layout.half_size(), layout.cell_size());
+    let point = |x, z| {
+        let (position, normal) = surface(height, x, z, cell);
+
+        (position + normal * lift, normal)
+    };
+
+    for line in 0..=layout.tiles {
+        let across = line as f32 * layout.tile_size - half;
+
+        for step in 0..cells {
+            let (start, end) = (step as f32 * cell - half, (step + 1) as f32 * cell - half);
+            let (near, far) = (across - width / 2.0, across + width / 2.0);
+
+            // Running north to south, then west to east
+            builder.smooth_quad([
+                point(near, start),
+                point(near, end),
+                point(far, end),
+                point(far, start),
+            ]);
+            builder.smooth_quad([
+                point(start, near),
+                point(start, far),
+                point(end, far),
+                point(end, near),
+            ]);
+        }
+    }
+
+    builder.build("grid")
+}
+
+/// Circles of some width (m) around the launch rail at each radius (m),
+/// lifted a little above the ground
+pub fn distance_rings(
+    radii: &[f32],
+    width: f32,
+    lift: f32,
+    step: f32,
+    height: &dyn Fn(f32, f32) -> f32,
+) -> CPUMesh {
+    let mut builder = MeshBuilder::default();
+    let point = |angle: f32, radius: f32| {
+        let (position, normal) = surface(height, radius * angle.cos(), radius * angle.sin(), step);
+
+        (position + normal * lift, normal)
+    };
+
+    for &radius in radii {
+        let (inner, outer) = (radius - width / 2.0, radius + width / 2.0);
+
+        for side in 0..RING_SIDES {
+            let (a, b) = (
+                side as f32 / RING_SIDES as f32 * TAU,
+                (side + 1) as f32 / RING_SIDES as f32 * TAU,
+            );
+
+            // Angles run from east towards south, clockwise seen from above
+            builder.smooth_quad([
+                point(a, outer),
+                point(a, inner),
+                point(b, inner),
+                point(b, outer),
+            ]);
+        }
+    }
+
+    builder.build("distance rings")
+}
@@ -0,0 +1,433 @@
+//! Meshes of a rocket design, in its body frame
+//!
+//! The body frame has its origin on the axis at the aft end of the rocket,
+//! which is where the simulation places the vehicle, and its z axis pointing
+//! out the nose.
+
+use std::f32::consts::TAU;
+
+use impulse_core::rocket::{Component, ComponentKind, Rocket};
+use three_d::*;
+
+use super::{MeshBuilder, Rgb};
+
+/// Faces around the body of revolved components
+const SIDES: u32 = 32;
+
+/// Steps along the length of a nose cone profile
+const NOSE_STEPS: u32 = 24;
+
+/// Mesh of one component of a rocket, drawn in its own material
+pub struct ComponentMesh {
+    pub mesh: CPUMesh,
+    pub color: Rgb,
+}
+
+/// Meshes of every visible component of a rocket. Internal components are
+/// hidden inside the body and are skipped.
+pub fn rocket_meshes(rocket: &Rocket) -> Vec<ComponentMesh> {
+    let length = rocket.length();
+    let mut meshes = Vec::new();
+
+    for (front, component) in rocket.stack() {
+        if let Some(profile) = profile(&component.kind) {
+            let profile: Vec<_> = profile
+                .into_iter()
+                .map(|(x, radius)| (length - front - x, radius))
+                .collect();
+
+            meshes.push(ComponentMesh {
+                mesh: revolve(&component.name, &profile),
+                color: color(&component.kind),
+            });
+        }
+
+        for child in &component.children {
+            add_fins(&mut meshes, length, front, component, child);
+        }
+    }
+
+    meshes
+}
+
+/// Outline of a revolved component as distances from its front and radii
+/// (m), from the front to the aft end
+fn profile(kind: &ComponentKind) -> Option<Vec<(f64, f64)>> {
+    match *kind {
+        ComponentKind::NoseCone {
+            shape,
+            length,
+            diameter,
+            ..
+        } => Some(
+            (0..=NOSE_STEPS)
+                .map(|step| {
+                    let fraction = step as f64 / NOSE_STEPS as f64;
+
+                    (fraction * length, shape.radius(fraction, length, diameter))
+                })
+                .collect(),
+        ),
+        ComponentKind::BodyTube {
+            length, diameter, ..
+        } => Some(vec![(0.0, diameter / 2.0), (length, diameter / 2.0)]),
+        ComponentKind::Transition {
+            length,
+            fore_diameter,
+            aft_diameter,
+            ..
+        } => Some(vec![
+            (0.0, fore_diameter / 2.0),
+            (length, aft_diameter / 2.0),
+        ]),
+        _ => None,
+    }
+}
+
+fn color(kind: &ComponentKind) -> Rgb {
+    match kind {
+        ComponentKind::NoseCone { .. } => (235, 235, 235),
+        ComponentKind::BodyTube { .. } => (200, 45, 40),
+        ComponentKind::Transition { .. } => (170, 170, 170),
+        ComponentKind::FinSet { .. } => (35, 35, 35),
+        _ => (128, 128, 128),
+    }
+}
+
+/// Surface of revolution around the z axis through points given as heights
+/// along the axis and radii, closed with a flat cap at each end
+fn revolve(name: &str, profile: &[(f64, f64)]) -> CPUMesh {
+    let mut builder = MeshBuilder::default();
+    let around = |side: u32| {
+        let angle = side as f32 / SIDES as f32 * TAU;
+
+        (angle.cos(), angle.sin())
+    };
+
+    for pair in profile.windows(2) {
+        let ((z0, r0), (z1, r1)) = (pair[0], pair[1]);
+        let (z0, r0, z1, r1) = (z0 as f32, r0 as f32, z1 as f32, r1 as f32);
+
+        // Outward normal of the slanted side, whose profile runs from the
+        // front at z0 back to z1
+        let axial = z0 - z1;
+        let slope = (r1 - r0).hypot(axial);
+
+        if slope == 0.0 {
+            continue;
+        }
+
+        let (radial, along) = (axial / slope, (r1 - r0) / slope);
+
+        for side in 0..SIDES {
+            let corner = |(cos, sin): (f32, f32), z: f32, r: f32| {
+                (
+                    vec3(r * cos, r * sin, z),
+                    vec3(radial * cos, radial * sin, along),
+                )
+            };
+            let (a, b) = (around(side), around(side + 1));
+
+            builder.smooth_quad([
+                corner(a, z0, r0),
+                corner(a, z1, r1),
+                corner(b, z1, r1),
+                corner(b, z0, r0),
+            ]);
+        }
+    }
+
+    if let (Some(&(front, front_radius)), Some(&(aft, aft_radius))) =
+        (profile.first(), profile.last())
+    {
+        cap(&mut builder, front as f32, front_radius as f32, 1.0);
+        cap(&mut builder, aft as f32, aft_radius as f32, -1.0);
+    }
+
+    builder.build(name)
+}
+
+/// Flat disc across the body at a height, facing along the axis in the
+/// direction of a sign
+fn cap(builder: &mut MeshBuilder, z: f32, radius: f32, facing: f32) {
+    if radius <= 0.0 {
+        return;
+    }
+
+    let center = vec3(0.0, 0.0, z);
+    let normal = vec3(0.0, 0.0, facing);
+
+    for side in 0..SIDES {
+        let point = |side: u32| {
+            let angle = side as f32 / SIDES as f32 * TAU * facing;
+
+            vec3(radius * angle.cos(), radius * angle.sin(), z)
+        };
+
+        builder.triangle_with_normal([center, point(side), point(side + 1)], normal);
+    }
+}
+
+/// Add the fins of a fin set attached to an external component, with their
+/// roots on its surface
+fn add_fins(
+    meshes: &mut Vec<ComponentMesh>,
+    length: f64,
+    parent_front: f64,
+    parent: &Component,
+    fins: &Component,
+) {
+    let (count, root_chord, tip_chord, span, sweep, thickness, cant) = match fins.kind {
+        ComponentKind::FinSet {
+            count,
+            root_chord,
+            tip_chord,
+            span,
+            sweep,
+            thickness,
+            cant,
+            ..
+        } => (count, root_chord, tip_chord, span, sweep, thickness, cant),
+        _ => return,
+    };
+
+    let root_radius = surface_radius(&parent.kind, fins.position) as f32;
+    let front = (length - parent_front - fins.position) as f32;
+    let (root_chord, tip_chord, span, sweep, thickness, cant) = (
+        root_chord as f32,
+        tip_chord as f32,
+        span as f32,
+        sweep as f32,
+        thickness as f32,
+        cant as f32,
+    );
+
+    // Planform as distances aft of the root leading edge and out from the
+    // root, which is convex as both chords run along the body
+    let planform = [
+        (0.0, 0.0),
+        (root_chord, 0.0),
+        (sweep + tip_chord, span),
+        (sweep, span),
+    ];
+
+    let mut builder = MeshBuilder::default();
+
+    for fin in 0..count {
+        let angle = fin as f32 / count as f32 * TAU;
+        let outward = vec3(angle.cos(), angle.sin(), 0.0);
+        let sideways = vec3(-angle.sin(), angle.cos(), 0.0);
+        let aft = vec3(0.0, 0.0, -1.0);
+
+        // Cant turns each fin about the line out from the middle of its root
+        let pivot = root_chord / 2.0;
+        let point = |(chord, out): (f32, f32), side: f32| {
+            let along = chord - pivot;
+            let across = side * thickness / 2.0;
+
+            vec3(0.0, 0.0, front)
+                + outward * (root_radius + out)
+                + aft * (pivot + along * cant.cos() - across * cant.sin())
+                + sideways * (along * cant.sin() + across * cant.cos())
+        };
+
+        let left: Vec<_> = planform.iter().map(|&corner| point(corner, -1.0)).collect();
+        let right: Vec<_> = planform.iter().map(|&corner| point(corner, 1.0)).collect();
+        let inside = point((root_chord / 2.0, span / 2.0), 0.0);
+
+        builder.quad_facing_away([left[0], left[1], left[2], left[3]], inside);
+        builder.quad_facing_away([right[0], right[1], right[2], right[3]], inside);
+
+        for edge in 0..planform.len() {
+            let next = (edge + 1) % planform.len();
+
+            builder.quad_facing_away([left[edge], left[next], right[next], right[edge]], inside);
+        }
+    }
+
+    meshes.push(ComponentMesh {
+        mesh: builder.build(&fins.name),
+        color: color(&fins.kind),
+    });
+}
+
+/// Outer radius of an external component at a distance from its front (m)
+fn surface_radius(kind: &ComponentKind, offset: f64) -> f64 {
+    match profile(kind) {
+        Some(profile) => {
+            let index = profile
+                .iter()
+                .position(|&(x, _)| x >= offset)
+                .unwrap_or(profile.len() - 1);
+
+            match index {
+                0 => profile[0].1,
+                index => {
+                    let ((x0, r0), (x1, r1)) = (profile[index - 1], profile[index]);
+
+                    r0 + (r1 - r0) * (offset - x0) / (x1 - x0)
+                }
+            }
+        }
+        None => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use impulse_core::rocket::NoseShape;
+    use three_d::core::Indices;
+
+    use super::*;
+
+    /// Corners of each triangle of a mesh, with their normals
+    fn triangles(mesh: &CPUMesh) -> Vec<[(Vec3, Vec3); 3]> {
+        let indices = match &mesh.indices {
+            Some(Indices::U32(indices)) => indices,
+            _ => panic!("{} is not indexed with u32", mesh.name),
+        };
+        let normals = mesh.normals.as_ref().unwrap();
+        let vertex = |index: u32| {
+            let i = index as usize * 3;
+
+            (
+                vec3(
+                    mesh.positions[i],
+                    mesh.positions[i + 1],
+                    mesh.positions[i + 2],
+                ),
+                vec3(normals[i], normals[i + 1], normals[i + 2]),
+            )
+        };
+
+        indices
+            .chunks(3)
+            .map(|corners| [vertex(corners[0]), vertex(corners[1]), vertex(corners[2])])
+            .collect()
+    }
+
+    /// Check that every normal is a unit vector on the side the triangle faces
+    fn assert_consistent_normals(mesh: &CPUMesh) {
+        for [a, b, c] in triangles(mesh) {
+            let facing = (b.0 - a.0).cross(c.0 - a.0);
+
+            // Quads meeting at the tip of a nose cone collapse to slivers with
+            // no direction to check
+            if facing.magnitude() < 1e-12 {
+                continue;
+            }
+
+            for (_, normal) in &[a, b, c] {
+                assert!((normal.magnitude() - 1.0).abs() < 1e-4, "{}", mesh.name);
+                assert!(facing.dot(*normal) > 0.0, "{}", mesh.name);
+            }
+        }
+    }
+
+    fn mesh<'a>(meshes: &'a [ComponentMesh], name: &str) -> &'a CPUMesh {
+        &meshes
+            .iter()
+            .find(|mesh| mesh.mesh.name == name)
+            .unwrap_or_else(|| panic!("no mesh named {}", name))
+            .mesh
+    }
+
+    #[test]
+    fn meshes_visible_components() {
+        let meshes = rocket_meshes(&Rocket::default());
+        let names: Vec<_> = meshes.iter().map(|mesh| mesh.mesh.name.as_str()).collect();
+
+        assert_eq!(names, ["Nose cone", "Body tube", "Fins"]);
+        for mesh in &meshes {
+            assert_eq!(
+                mesh.mesh.positions.len(),
+                mesh.mesh.normals.as_ref().unwrap().len()
+            );
+            assert_consistent_normals(&mesh.mesh);
+        }
+    }
+
+    #[test]
+    fn body_tube_is_closed_cylinder() {
+        let meshes = rocket_meshes(&Rocket::default());
+        let tube = mesh(&meshes, "Body tube");
+
+        // One band of quads around the side and a fan across each end
+        assert_eq!(triangles(tube).len() as u32, 2 * SIDES + 2 * SIDES);
+        assert_eq!(tube.positions.len() as u32 / 3, 4 * SIDES + 2 * 3 * SIDES);
+
+        // The tube runs from the aft end of the nose back to the tail
+        let heights = tube.positions.chunks(3).map(|position| position[2]);
+        let (low, high) = heights.fold((f32::MAX, f32::MIN), |(low, high), z| {
+            (low.min(z), high.max(z))
+        });
+        assert!(low.abs() < 1e-6);
+        assert!((high - 0.84).abs() < 1e-6);
+
+        // The sides face straight out from the axis
+        for [a, b, c] in triangles(tube) {
+            for (position, normal) in &[a, b, c] {
+                if normal.z == 0.0 {
+                    let radial = vec3(position.x, position.y, 0.0).normalize();
+
+                    assert!((normal - radial).magnitude() < 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nose_cone_comes_to_a_point() {
+        let meshes = rocket_meshes(&Rocket::default());
+        let nose = mesh(&meshes, "Nose cone");
+
+        // A band for each step of the profile and a cap across the base only,
+        // as the tip has no radius
+        assert_eq!(triangles(nose).len() as u32, 2 * SIDES * NOSE_STEPS + SIDES);
+
+        let tip = nose
+            .positions
+            .chunks(3)
+            .map(|position| position[2])
+            .fold(f32::MIN, f32::max);
+        assert!((tip - 1.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fins_have_two_faces_and_four_edges() {
+        let meshes = rocket_meshes(&Rocket::default());
+        let fins = mesh(&meshes, "Fins");
+
+        assert_eq!(triangles(fins).len(), 3 * 6 * 2);
+        assert_eq!(fins.positions.len() / 3, 3 * 6 * 2 * 3);
+
+        // Roots sit on the body tube and tips reach out a span beyond it
+        let reach = fins
+            .positions
+            .chunks(3)
+            .map(|position| position[0].hypot(position[1]))
+            .fold(0.0, f32::max);
+        assert!(reach > 0.033 + 0.075 - 1e-3 && reach < 0.033 + 0.075 + 1e-3);
+    }
+
+    #[test]
+    fn skips_degenerate_components() {
+        let rocket = Rocket {
+            name: "Stub".into(),
+            components: vec![Component::new(
+                "Nose cone",
+                ComponentKind::NoseCone {
+                    shape: NoseShape::Conical,
+                    length: 0.0,
+                    diameter: 0.0,
+                    thickness: 0.0,
+                    density: 0.0,
+                },
+            )],
+        };
+        let meshes = rocket_meshes(&rocket);
+
+        assert_eq!(meshes.len(), 1);
+        assert!(meshes[0].mesh.positions.is_empty());
+    }
+}
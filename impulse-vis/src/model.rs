@@ -0,0 +1,43 @@
+//! The rocket drawn in the scene
+
+use std::error::Error;
+
+use cgmath::{Quaternion, Vector3};
+use impulse_core::rocket::Rocket;
+use three_d::*;
+
+use crate::{flight::body_to_scene, mesh, trajectory::material};
+
+/// Every visible component of a rocket, each in its own material
+pub struct RocketModel {
+    parts: Vec<(Model, Material)>,
+}
+
+impl RocketModel {
+    pub fn new(context: &Context, rocket: &Rocket) -> Result<Self, Box<dyn Error>> {
+        let parts = mesh::rocket_meshes(rocket)
+            .into_iter()
+            .map(|part| {
+                Ok((
+                    Model::new(context, &part.mesh)?,
+                    material(context, part.color)?,
+                ))
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        Ok(Self { parts })
+    }
+
+    /// Place the rocket at a position and attitude in the east-north-up frame
+    pub fn set_pose(&mut self, position: Vector3<f64>, attitude: Quaternion<f64>) {
+        let transformation = body_to_scene(position, attitude);
+
+        for (model, _) in &mut self.parts {
+            model.set_transformation(transformation);
+        }
+    }
+
+    pub fn geometries(&self) -> impl Iterator<Item = (&Model, &Material)> {
+        self.parts.iter().map(|(model, material)| (model, material))
+    }
+}
@@ -0,0 +1,123 @@
+//! Playing back a recorded flight in real time or faster
+
+use cgmath::InnerSpace;
+use impulse_core::sim::{FlightPhase, FlightSample};
+use three_d::egui::{Grid, Slider, Ui};
+
+/// Speed multipliers offered for playback
+const SPEEDS: [f64; 6] = [0.25, 0.5, 1.0, 2.0, 5.0, 10.0];
+
+/// Position in time along a flight and how fast it moves
+pub struct Playback {
+    /// Time since ignition being shown (s)
+    pub time: f64,
+    pub playing: bool,
+    /// Seconds of flight played per second of real time
+    pub speed: f64,
+    /// Length of the recording (s)
+    duration: f64,
+}
+
+impl Playback {
+    pub fn new(duration: f64) -> Self {
+        Self {
+            time: 0.0,
+            playing: false,
+            speed: 1.0,
+            duration,
+        }
+    }
+
+    /// Move forward by a frame of real time (s), pausing at the end
+    pub fn advance(&mut self, elapsed: f64) {
+        if !self.playing {
+            return;
+        }
+
+        self.time += elapsed * self.speed;
+
+        if self.time >= self.duration {
+            self.time = self.duration;
+            self.playing = false;
+        }
+    }
+
+    /// Lengthen the recording as more of a live flight arrives, staying at
+    /// the end if that is where the playback was
+    pub fn extend(&mut self, duration: f64) {
+        if self.time >= self.duration {
+            self.time = duration;
+        }
+
+        self.duration = duration;
+    }
+
+    /// Timeline scrubber, play and pause button and speed selection
+    pub fn controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .button(if self.playing { "Pause" } else { "Play" })
+                .clicked()
+            {
+                // Start over when playing from the end
+                if !self.playing && self.time >= self.duration {
+                    self.time = 0.0;
+                }
+
+                self.playing = !self.playing;
+            }
+
+            ui.add(Slider::new(&mut self.time, 0.0..=self.duration).text("s"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Speed");
+
+            for &speed in &SPEEDS {
+                ui.selectable_value(&mut self.speed, speed, format!("{}×", speed));
+            }
+        });
+    }
+}
+
+/// Readout of the state of the vehicle at the time being shown
+pub fn telemetry(ui: &mut Ui, sample: &FlightSample) {
+    let phase = match sample.phase {
+        FlightPhase::Rail => "On the rail",
+        FlightPhase::Boost => "Boost",
+        FlightPhase::Coast => "Coast",
+        FlightPhase::Recovery => "Recovery",
+        FlightPhase::Landed => "Landed",
+    };
+
+    let rows = [
+        ("Time", format!("{:.2} s", sample.time)),
+        ("Phase", phase.to_string()),
+        ("Altitude", format!("{:.1} m", sample.altitude())),
+        (
+            "Downrange",
+            format!("{:.1} m", sample.position.truncate().magnitude()),
+        ),
+        ("Speed", format!("{:.1} m/s", sample.speed())),
+        ("Vertical speed", format!("{:.1} m/s", sample.velocity.z)),
+        (
+            "Acceleration",
+            format!("{:.1} m/s²", sample.acceleration.magnitude()),
+        ),
+        ("Mach", format!("{:.2}", sample.mach)),
+        ("Thrust", format!("{:.1} N", sample.thrust)),
+        (
+            "Angle of attack",
+            format!("{:.1}°", sample.angle_of_attack.to_degrees()),
+        ),
+        ("Stability", format!("{:.2} cal", sample.stability)),
+    ];
+
+    Grid::new("Telemetry").show(ui, |ui| {
+        for (label, value) in rows.iter() {
+            ui.label(*label);
+            ui.label(value.as_str());
+            ui.end_row();
+        }
+    });
+}
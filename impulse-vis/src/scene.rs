@@ -0,0 +1,65 @@
+//! Lighting and drawing the scene, shared by the window and offscreen exports
+
+use std::error::Error;
+
+use three_d::*;
+
+/// Deferred lighting of the scene under the sun
+pub struct Scene {
+    pipeline: DeferredPipeline,
+    sunlight: DirectionalLight,
+}
+
+impl Scene {
+    pub fn new(context: &Context) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            pipeline: DeferredPipeline::new(context)?,
+            sunlight: DirectionalLight::new(context, 0.5, Color::WHITE, &vec3(0.0, -1.0, 0.2))?,
+        })
+    }
+
+    /// Draw the ground and the objects standing on it to the screen, with
+    /// shadows cast by the objects within some radius of a center, then
+    /// anything drawn over the scene such as the GUI
+    pub fn render<'a>(
+        &mut self,
+        context: &Context,
+        camera: &Camera,
+        ground: impl Iterator<Item = (&'a Model, &'a Material)>,
+        objects: impl Iterator<Item = (&'a Model, &'a Material)>,
+        (center, radius): (Vec3, f32),
+        overlay: impl FnOnce() -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let objects: Vec<_> = objects.collect();
+
+        // Calculate shadows of everything but the ground
+        let shadow_casters: Vec<_> = objects.iter().map(|&(model, _)| model as _).collect();
+        self.sunlight.generate_shadow_map(
+            &center,
+            2.0 * radius,
+            4.0 * radius,
+            1024,
+            1024,
+            &shadow_casters,
+        )?;
+
+        // Calculate geometry
+        let geometries: Vec<_> = ground
+            .chain(objects)
+            .map(|(model, material)| (model as _, material))
+            .collect();
+        self.pipeline.geometry_pass(camera, &geometries)?;
+
+        // Start writing to the screen and clears the color and depth
+        let (pipeline, sunlight) = (&mut self.pipeline, &self.sunlight);
+        Screen::write(
+            context,
+            ClearState::color_and_depth(0.2, 0.2, 0.2, 1.0, 1.0),
+            || {
+                pipeline.light_pass(camera, None, &[sunlight], &[], &[])?;
+
+                overlay()
+            },
+        )
+    }
+}
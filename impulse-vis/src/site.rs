@@ -0,0 +1,223 @@
+//! The ground, launch rail and wind at the launch site, giving a sense of
+//! scale and drift
+
+use std::error::Error;
+
+use cgmath::InnerSpace;
+use impulse_core::sim::LaunchConditions;
+use three_d::*;
+
+use crate::{
+    flight::{to_scene, Flight},
+    mesh::{self, GroundLayout, Rgb},
+    terrain::Terrain,
+    trajectory::material,
+};
+
+/// Closest the edges of the ground come to the launch rail (m)
+pub const MIN_REACH: f32 = 20.0;
+
+/// Tiles along each side of the ground, roughly
+const TILES: f32 = 20.0;
+
+/// Most cells along each side of the ground when following terrain
+const MAX_TERRAIN_CELLS: u32 = 400;
+
+/// Radius of the launch rail (m)
+const RAIL_RADIUS: f32 = 0.01;
+
+const LIGHT_GROUND: Rgb = (98, 142, 72);
+const DARK_GROUND: Rgb = (86, 128, 64);
+const GRID_COLOR: Rgb = (210, 220, 200);
+const RING_COLOR: Rgb = (240, 200, 80);
+const RAIL_COLOR: Rgb = (150, 150, 160);
+const WIND_COLOR: Rgb = (90, 180, 255);
+
+/// Everything drawn at the launch site, ready to be rendered
+pub struct LaunchSite {
+    pub layout: GroundLayout,
+    /// Distance between the rings around the launch rail (m)
+    pub ring_spacing: f32,
+    launch: LaunchConditions,
+    /// Tiles, grid lines and rings on the ground, which cast no shadows
+    ground: Vec<(Model, Material)>,
+    /// Launch rail and wind indicator
+    fixtures: Vec<(Model, Material)>,
+}
+
+impl LaunchSite {
+    /// Lay out the ground to reach some distance from the launch rail (m), or
+    /// over the terrain if there is any
+    pub fn new(
+        context: &Context,
+        launch: &LaunchConditions,
+        reach: f32,
+        terrain: Option<&Terrain>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let layout = match terrain {
+            Some(terrain) => {
+                let (width, depth) = terrain.size();
+                let half = width.min(depth) / 2.0;
+                let tile_size = round_step(2.0 * half / TILES);
+                let tiles = 2 * ((half / tile_size) as u32).max(1);
+                let cells_per_tile = ((tile_size / terrain.spacing()).ceil() as u32)
+                    .clamp(1, (MAX_TERRAIN_CELLS / tiles).max(1));
+
+                GroundLayout {
+                    tiles,
+                    tile_size,
+                    cells_per_tile,
+                }
+            }
+            None => {
+                let tile_size = round_step(2.0 * reach / TILES);
+
+                GroundLayout {
+                    tiles: 2 * (reach / tile_size).ceil().max(1.0) as u32,
+                    tile_size,
+                    cells_per_tile: 1,
+                }
+            }
+        };
+
+        let height = |x: f32, z: f32| terrain.map_or(0.0, |terrain| terrain.height(x, z));
+        let half = layout.half_size();
+        let (tile, step) = (
+            layout.tile_size,
+            layout.tile_size / layout.cells_per_tile as f32,
+        );
+        let lift = 0.001 * tile;
+
+        let ring_spacing = round_step(half / 4.0);
+        let radii: Vec<_> = (1..)
+            .map(|ring| ring as f32 * ring_spacing)
+            .take_while(|&radius| radius <= half)
+            .collect();
+
+        let [light, dark] = mesh::ground_tiles(&layout, &height);
+        let mut ground = vec![
+            (
+                Model::new(context, &light)?,
+                material(context, LIGHT_GROUND)?,
+            ),
+            (Model::new(context, &dark)?, material(context, DARK_GROUND)?),
+        ];
+        ground.push((
+            Model::new(
+                context,
+                &mesh::grid_lines(&layout, 0.02 * tile, lift, &height),
+            )?,
+            material(context, GRID_COLOR)?,
+        ));
+        ground.push((
+            Model::new(
+                context,
+                &mesh::distance_rings(&radii, 0.04 * tile, 2.0 * lift, step, &height),
+            )?,
+            material(context, RING_COLOR)?,
+        ));
+
+        let rail = mesh::tube(
+            "launch rail",
+            &[(
+                vec3(0.0, 0.0, 0.0),
+                to_scene(launch.rail_direction() * launch.rail_length),
+            )],
+            RAIL_RADIUS,
+            4,
+        );
+        let mut fixtures = vec![(Model::new(context, &rail)?, material(context, RAIL_COLOR)?)];
+
+        // Point at the launch rail from upwind, a little above the ground
+        if launch.wind_speed > 0.0 {
+            let downwind = to_scene(launch.wind()).normalize();
+            let above = vec3(0.0, height(0.0, 0.0) + 0.1 * tile, 0.0);
+            let arrow = mesh::arrow(
+                "wind",
+                above - downwind * tile,
+                above - downwind * (0.2 * tile),
+                0.02 * tile,
+                8,
+            );
+
+            fixtures.push((Model::new(context, &arrow)?, material(context, WIND_COLOR)?));
+        }
+
+        Ok(Self {
+            layout,
+            ring_spacing,
+            launch: *launch,
+            ground,
+            fixtures,
+        })
+    }
+
+    /// Tiles, grid lines and rings, which lie on the ground
+    pub fn ground(&self) -> impl Iterator<Item = (&Model, &Material)> {
+        self.ground
+            .iter()
+            .map(|(model, material)| (model, material))
+    }
+
+    /// Launch rail and wind indicator, which stand above the ground
+    pub fn fixtures(&self) -> impl Iterator<Item = (&Model, &Material)> {
+        self.fixtures
+            .iter()
+            .map(|(model, material)| (model, material))
+    }
+
+    /// Scale of the ground and the launch conditions, as labels and values
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        let launch = &self.launch;
+
+        vec![
+            (
+                "Grid",
+                format!(
+                    "{} m squares, rings every {} m",
+                    self.layout.tile_size, self.ring_spacing
+                ),
+            ),
+            (
+                "Launch rail",
+                format!(
+                    "{:.2} m, {:.1}° towards {:.0}°",
+                    launch.rail_length,
+                    launch.rail_angle.to_degrees(),
+                    launch.rail_heading.to_degrees().rem_euclid(360.0)
+                ),
+            ),
+            (
+                "Wind",
+                format!(
+                    "{:.1} m/s from {:.0}°",
+                    launch.wind_speed,
+                    launch.wind_direction.to_degrees().rem_euclid(360.0)
+                ),
+            ),
+        ]
+    }
+}
+
+/// Distance from the launch rail the ground has to reach to take in a flight
+/// (m)
+pub fn reach(flight: &Flight) -> f32 {
+    let bounds = flight.bounds();
+    let furthest = [bounds.min.x, bounds.max.x, bounds.min.z, bounds.max.z]
+        .iter()
+        .fold(0.0f32, |furthest, &distance| furthest.max(distance.abs()));
+
+    (1.25 * furthest).max(MIN_REACH)
+}
+
+/// Smallest round distance of 1, 2 or 5 times a power of ten that is at least
+/// some distance (m)
+fn round_step(distance: f32) -> f32 {
+    let power = 10f32.powf(distance.max(1e-3).log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|&factor| factor * power)
+        .find(|&step| step >= distance)
+        .unwrap_or(10.0 * power)
+}
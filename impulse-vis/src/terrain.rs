@@ -0,0 +1,99 @@
+//! Terrain around the launch site from a digital elevation model
+//!
+//! Elevation models are read from grayscale images such as PNG or GeoTIFF
+//! files, with the darkest value at the lowest ground and the brightest at the
+//! highest. Geographic metadata is not read: the image is centered on the
+//! launch rail with north up, and its size and relief are given separately.
+//! The terrain is only drawn, as the simulation takes the ground to be flat.
+
+use std::path::Path;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TerrainError {
+    #[error("failed to read elevation model: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("elevation model must be at least 2 by 2 pixels")]
+    TooSmall,
+}
+
+/// Heights of the ground on a regular grid centered on the launch rail
+pub struct Terrain {
+    /// Heights above the launch rail (m), row by row from the north west
+    heights: Vec<f32>,
+    columns: u32,
+    rows: u32,
+    /// Distance covered from west to east (m)
+    width: f32,
+}
+
+impl Terrain {
+    /// Load an elevation model covering some width from west to east (m), with
+    /// some difference in height between its lowest and highest points (m)
+    pub fn load(path: &Path, width: f32, relief: f32) -> Result<Self, TerrainError> {
+        let image = image::open(path)?.into_luma16();
+        let (columns, rows) = image.dimensions();
+
+        if columns < 2 || rows < 2 {
+            return Err(TerrainError::TooSmall);
+        }
+
+        let heights = image
+            .pixels()
+            .map(|pixel| pixel.0[0] as f32 / u16::MAX as f32 * relief)
+            .collect();
+
+        let mut terrain = Self {
+            heights,
+            columns,
+            rows,
+            width,
+        };
+
+        // Put the launch rail at zero height, where the simulation starts
+        let pad = terrain.height(0.0, 0.0);
+
+        for height in &mut terrain.heights {
+            *height -= pad;
+        }
+
+        Ok(terrain)
+    }
+
+    /// Distances covered from west to east and from north to south (m),
+    /// assuming square pixels
+    pub fn size(&self) -> (f32, f32) {
+        (
+            self.width,
+            self.width * (self.rows - 1) as f32 / (self.columns - 1) as f32,
+        )
+    }
+
+    /// Distance between neighbouring heights (m)
+    pub fn spacing(&self) -> f32 {
+        self.width / (self.columns - 1) as f32
+    }
+
+    /// Height of the ground (m) at a point in the scene, east (x) and south
+    /// (z) of the launch rail, holding the edge heights beyond the model
+    pub fn height(&self, x: f32, z: f32) -> f32 {
+        let (width, depth) = self.size();
+        let column =
+            ((x / width + 0.5) * (self.columns - 1) as f32).clamp(0.0, (self.columns - 1) as f32);
+        let row = ((z / depth + 0.5) * (self.rows - 1) as f32).clamp(0.0, (self.rows - 1) as f32);
+
+        // Interpolate between the four surrounding heights
+        let (left, top) = (
+            (column as u32).min(self.columns - 2),
+            (row as u32).min(self.rows - 2),
+        );
+        let (across, down) = (column - left as f32, row - top as f32);
+        let at = |column: u32, row: u32| self.heights[(row * self.columns + column) as usize];
+
+        let upper = at(left, top) * (1.0 - across) + at(left + 1, top) * across;
+        let lower = at(left, top + 1) * (1.0 - across) + at(left + 1, top + 1) * across;
+
+        upper * (1.0 - down) + lower * down
+    }
+}
@@ -0,0 +1,229 @@
+//! The flight path drawn as a colored line, with markers at key events
+
+use std::error::Error;
+
+use cgmath::InnerSpace;
+use impulse_core::sim::{FlightEventKind, FlightPhase, FlightSample};
+use three_d::*;
+
+use crate::{
+    flight::{to_scene, Flight},
+    mesh::{self, Rgb},
+};
+
+/// Sides of the prisms the flight path is drawn with
+const TUBE_SIDES: u32 = 6;
+
+/// Colors the speed is divided into
+const SPEED_BANDS: usize = 12;
+
+/// Thickness of the flight path relative to the size of the flight
+const TUBE_SCALE: f32 = 0.002;
+
+/// Size of event markers relative to the size of the flight
+const MARKER_SCALE: f32 = 0.01;
+
+/// Shortest segment drawn relative to the size of the flight, so that long
+/// flights do not turn into millions of triangles
+const SEGMENT_SCALE: f32 = 0.0005;
+
+/// What the color of the flight path shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Velocity,
+    Phase,
+}
+
+/// Flight path and event markers, ready to be rendered
+pub struct Trajectory {
+    pub mode: ColorMode,
+    /// Meshes of the flight path, one for each color
+    path: Vec<(Model, Material)>,
+    markers: Vec<(Model, Material)>,
+    /// Labels and colors explaining the flight path and markers
+    legend: Vec<(String, Rgb)>,
+}
+
+impl Trajectory {
+    pub fn new(
+        context: &Context,
+        flight: &Flight,
+        mode: ColorMode,
+    ) -> Result<Self, Box<dyn Error>> {
+        let size = flight.bounds().radius().max(1.0);
+        let samples = thin_out(&flight.record.samples, size * SEGMENT_SCALE);
+        let max_speed = samples.iter().map(FlightSample::speed).fold(0.0, f64::max);
+
+        // Group the segments by the color they are drawn in
+        let mut groups: Vec<(String, Rgb, Vec<_>)> = match mode {
+            ColorMode::Velocity => (0..SPEED_BANDS)
+                .map(|band| {
+                    let low = max_speed * band as f64 / SPEED_BANDS as f64;
+                    let high = max_speed * (band + 1) as f64 / SPEED_BANDS as f64;
+
+                    (
+                        format!("{:.0} to {:.0} m/s", low, high),
+                        heat_color(band as f32 / (SPEED_BANDS - 1) as f32),
+                        Vec::new(),
+                    )
+                })
+                .collect(),
+            ColorMode::Phase => PHASES
+                .iter()
+                .map(|&(_, label, color)| (label.to_string(), color, Vec::new()))
+                .collect(),
+        };
+
+        for pair in samples.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+
+            let group = match mode {
+                ColorMode::Velocity if max_speed > 0.0 => {
+                    let speed = (start.speed() + end.speed()) / 2.0;
+
+                    ((speed / max_speed * SPEED_BANDS as f64) as usize).min(SPEED_BANDS - 1)
+                }
+                ColorMode::Velocity => 0,
+                ColorMode::Phase => match PHASES
+                    .iter()
+                    .position(|&(phase, _, _)| phase == start.phase)
+                {
+                    Some(index) => index,
+                    // Sliding along the ground after landing is not drawn
+                    None => continue,
+                },
+            };
+
+            groups[group]
+                .2
+                .push((to_scene(start.position), to_scene(end.position)));
+        }
+
+        let mut legend = Vec::new();
+        let mut path = Vec::new();
+
+        for (label, color, segments) in groups {
+            if segments.is_empty() {
+                continue;
+            }
+
+            let mesh = mesh::tube(&label, &segments, size * TUBE_SCALE, TUBE_SIDES);
+
+            path.push((Model::new(context, &mesh)?, material(context, color)?));
+            legend.push((label, color));
+        }
+
+        let mut markers = Vec::new();
+
+        for (kind, position) in flight.markers() {
+            let (label, color) = marker_style(kind);
+            let mesh = mesh::octahedron(label, position, size * MARKER_SCALE);
+
+            markers.push((Model::new(context, &mesh)?, material(context, color)?));
+
+            if !legend.iter().any(|(existing, _)| existing == label) {
+                legend.push((label.to_string(), color));
+            }
+        }
+
+        Ok(Self {
+            mode,
+            path,
+            markers,
+            legend,
+        })
+    }
+
+    /// Everything to draw in the geometry pass
+    pub fn geometries(&self) -> impl Iterator<Item = (&Model, &Material)> {
+        self.path
+            .iter()
+            .chain(&self.markers)
+            .map(|(model, material)| (model, material))
+    }
+
+    pub fn legend(&self) -> &[(String, Rgb)] {
+        &self.legend
+    }
+}
+
+/// Flight path in a single color, as thick as suits a scene of some size (m)
+pub fn solid_path(name: &str, flight: &Flight, size: f32) -> CPUMesh {
+    let samples = thin_out(&flight.record.samples, size * SEGMENT_SCALE);
+    let segments: Vec<_> = samples
+        .windows(2)
+        // Sliding along the ground after landing is not drawn
+        .filter(|pair| pair[0].phase != FlightPhase::Landed)
+        .map(|pair| (to_scene(pair[0].position), to_scene(pair[1].position)))
+        .collect();
+
+    mesh::tube(name, &segments, size * TUBE_SCALE, TUBE_SIDES)
+}
+
+/// Phases drawn in the phase color mode, with their labels and colors
+const PHASES: [(FlightPhase, &str, Rgb); 4] = [
+    (FlightPhase::Rail, "Launch rail", (160, 160, 160)),
+    (FlightPhase::Boost, "Boost", (255, 110, 30)),
+    (FlightPhase::Coast, "Coast", (240, 210, 40)),
+    (FlightPhase::Recovery, "Recovery", (60, 140, 255)),
+];
+
+fn marker_style(kind: FlightEventKind) -> (&'static str, Rgb) {
+    match kind {
+        FlightEventKind::Burnout => ("Burnout", (255, 140, 0)),
+        FlightEventKind::Apogee => ("Apogee", (255, 255, 255)),
+        FlightEventKind::Deployment(_) => ("Deployment", (40, 200, 90)),
+        FlightEventKind::Landing => ("Landing", (220, 40, 40)),
+        FlightEventKind::Ignition | FlightEventKind::RailExit => ("Event", (200, 200, 200)),
+    }
+}
+
+/// Material of a single flat color
+pub fn material(context: &Context, (r, g, b): Rgb) -> Result<Material, Box<dyn Error>> {
+    Ok(Material::new(
+        context,
+        &CPUMaterial {
+            albedo: Color::new_opaque(r, g, b),
+            ..Default::default()
+        },
+    )?)
+}
+
+/// Color from blue through green to red as a fraction goes from zero to one
+pub fn heat_color(fraction: f32) -> Rgb {
+    // Hue from 240° (blue) down to 0° (red) at full saturation and value
+    let hue = (1.0 - fraction.clamp(0.0, 1.0)) * 4.0;
+    let rising = (hue.fract() * 255.0) as u8;
+    let falling = 255 - rising;
+
+    match hue as u32 {
+        0 => (255, rising, 0),
+        1 => (falling, 255, 0),
+        2 => (0, 255, rising),
+        3 => (0, falling, 255),
+        _ => (0, 0, 255),
+    }
+}
+
+/// Samples at least a given distance apart, always keeping the first and last
+/// samples and every change of phase
+fn thin_out(samples: &[FlightSample], spacing: f32) -> Vec<FlightSample> {
+    let mut kept: Vec<FlightSample> = Vec::new();
+
+    for (index, sample) in samples.iter().enumerate() {
+        let keep = match kept.last() {
+            Some(last) => {
+                index == samples.len() - 1
+                    || last.phase != sample.phase
+                    || (sample.position - last.position).magnitude() >= spacing as f64
+            }
+            None => true,
+        };
+
+        if keep {
+            kept.push(*sample);
+        }
+    }
+
+    kept
+}
@@ -0,0 +1,251 @@
+//! Running simulations from the command line, without opening a window
+
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use cgmath::Vector3;
+use structopt::StructOpt;
+use thiserror::Error;
+use tracing::info;
+
+use crate::{
+    project::{Project, ProjectError},
+    sim::{
+        self, DispersionResults, ExportError, ExportFormat, ExportOptions, FieldLimits,
+        FlightEventKind, FlightRecord, FlightSummary, FlightUpdate, MonteCarlo, RangeCheck,
+        Sampling, SettingsError, Simulation, TelemetryError, TelemetryFrame, TelemetryPublisher,
+    },
+};
+
+/// Simulate a project and print a summary of the flight
+#[derive(Debug, StructOpt)]
+pub struct RunOptions {
+    /// Project file to simulate
+    #[structopt(parse(from_os_str))]
+    project: PathBuf,
+    /// Files to write the trajectory to, in the format given by their
+    /// extension (.csv, .json, .kml or .geojson)
+    #[structopt(short, long = "export", parse(from_os_str))]
+    exports: Vec<PathBuf>,
+    /// Rate to resample exported trajectories at instead of writing every
+    /// integrator step (Hz)
+    #[structopt(long)]
+    rate: Option<f64>,
+    /// Also run the project's Monte Carlo dispersion analysis, printing its
+    /// statistics and marking its landing points on exported maps
+    #[structopt(long)]
+    monte_carlo: bool,
+    /// Publish live telemetry for impulse-vis on an address such as
+    /// 127.0.0.1:7437, flying the simulation in real time
+    #[structopt(long)]
+    publish: Option<String>,
+    /// Seconds of flight simulated per second of real time while publishing
+    #[structopt(long, default_value = "1")]
+    speed: f64,
+}
+
+#[derive(Debug, Error)]
+pub enum RunError {
+    #[error("failed to open {}: {source}", path.display())]
+    Project { path: PathBuf, source: ProjectError },
+    #[error("the design cannot be simulated:\n{}", .0.join("\n"))]
+    InvalidDesign(Vec<String>),
+    #[error("invalid simulation settings: {0}")]
+    Settings(#[from] SettingsError),
+    #[error("cannot tell the export format of {}, expected .csv, .json, .kml or .geojson", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("failed to export to {}: {source}", path.display())]
+    Export { path: PathBuf, source: ExportError },
+    #[error("failed to publish telemetry: {0}")]
+    Telemetry(#[from] TelemetryError),
+    #[error("the publishing speed must be a finite number greater than zero")]
+    InvalidSpeed,
+    #[error("the publishing speed {0:e} is too slow to fly the simulation at")]
+    SpeedTooSlow(f64),
+}
+
+/// Simulate a project to completion, print its summary and write the
+/// requested exports
+pub fn run(options: RunOptions) -> Result<(), RunError> {
+    // Check every export path up front so a typo does not cost a simulation
+    let exports = options
+        .exports
+        .iter()
+        .map(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(ExportFormat::from_extension)
+                .map(|format| (path.as_path(), format))
+                .ok_or_else(|| RunError::UnknownFormat(path.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if options.publish.is_some() && (!options.speed.is_finite() || options.speed <= 0.0) {
+        return Err(RunError::InvalidSpeed);
+    }
+
+    let project = Project::load(&options.project).map_err(|source| RunError::Project {
+        path: options.project.clone(),
+        source,
+    })?;
+
+    let errors: Vec<_> = project
+        .rocket
+        .validate()
+        .iter()
+        .chain(&project.rocket.check_motor(&project.motor))
+        .map(ToString::to_string)
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(RunError::InvalidDesign(errors));
+    }
+
+    info!(path = %options.project.display(), "Simulating project");
+
+    let config = project.config();
+    let mut simulation = Simulation::new(config.clone())?;
+    let record = match &options.publish {
+        Some(address) => publish(&mut simulation, &project, address, options.speed)?,
+        None => simulation.run_to_completion(),
+    };
+    let summary = FlightSummary::new(simulation.config(), &record.samples, &record.events);
+
+    print_summary(&summary);
+
+    if !record
+        .events
+        .iter()
+        .any(|event| event.kind == FlightEventKind::Landing)
+    {
+        eprintln!("warning: the flight did not land before the simulation time limit");
+    }
+
+    let landing_points = if options.monte_carlo {
+        info!(
+            runs = project.dispersion.runs,
+            "Running dispersion analysis"
+        );
+
+        let results = MonteCarlo::new(config, project.dispersion)?.run_to_completion();
+
+        println!();
+        print_dispersion(&results, &project.field);
+
+        results.landing_points()
+    } else {
+        Vec::new()
+    };
+
+    let sampling = match options.rate {
+        Some(rate) => Sampling::FixedRate(rate),
+        None => Sampling::EveryStep,
+    };
+
+    for (path, format) in exports {
+        export(path, format, sampling, &record, &landing_points)?;
+    }
+
+    Ok(())
+}
+
+/// Fly a simulation in real time, or a multiple of it, publishing its
+/// telemetry as it goes
+fn publish(
+    simulation: &mut Simulation,
+    project: &Project,
+    address: &str,
+    speed: f64,
+) -> Result<FlightRecord, RunError> {
+    let mut publisher = TelemetryPublisher::bind(address)?;
+
+    info!(address = %publisher.local_addr()?, "Publishing telemetry");
+
+    let config = simulation.config();
+    publisher.publish(&TelemetryFrame::start(
+        project.rocket.clone(),
+        project.motor.clone(),
+        config.launch,
+        config.settings,
+    ))?;
+
+    let start = Instant::now();
+    let mut samples = Vec::new();
+
+    for update in simulation.updates() {
+        if let FlightUpdate::Sample(sample) = update {
+            // Hold each sample back until its time comes
+            let due = Duration::try_from_secs_f64(sample.time.max(0.0) / speed)
+                .map_err(|_| RunError::SpeedTooSlow(speed))?;
+
+            if let Some(wait) = due.checked_sub(start.elapsed()) {
+                thread::sleep(wait);
+            }
+
+            samples.push(sample);
+        }
+
+        publisher.publish(&update.into())?;
+    }
+
+    publisher.publish(&TelemetryFrame::End)?;
+
+    Ok(FlightRecord::new(simulation, samples))
+}
+
+fn print_summary(summary: &FlightSummary) {
+    let rows = summary.rows();
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+
+    for (label, value) in rows {
+        println!("{:width$}  {}", label, value, width = width);
+    }
+}
+
+fn print_dispersion(results: &DispersionResults, field: &FieldLimits) {
+    let mut rows = results.rows();
+
+    if field.is_limited() {
+        rows.push((
+            "Out of bounds".into(),
+            RangeCheck::new(results, field).to_string(),
+        ));
+    }
+
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (label, value) in rows {
+        println!("{:width$}  {}", label, value, width = width);
+    }
+}
+
+fn export(
+    path: &Path,
+    format: ExportFormat,
+    sampling: Sampling,
+    record: &FlightRecord,
+    landing_points: &[Vector3<f64>],
+) -> Result<(), RunError> {
+    let options = ExportOptions {
+        format,
+        sampling,
+        landing_points: landing_points.to_vec(),
+        ..ExportOptions::default()
+    };
+
+    sim::export_trajectory(path, record, &options).map_err(|source| RunError::Export {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    info!(path = %path.display(), %format, "Exported trajectory");
+
+    Ok(())
+}
@@ -2,22 +2,45 @@
 
 #![deny(unsafe_code)]
 
-use std::{panic::catch_unwind, thread};
+use std::{io, panic::catch_unwind, path::PathBuf, process, thread};
 
 use crossfire::mpmc;
 use iced::{window, Application, Settings};
+use impulse_core::rocket;
 use sim::simulation_thread;
+use structopt::StructOpt;
 use tracing::{info, warn};
 use tracing_subscriber::{filter::Directive, EnvFilter};
-use ui::{Counter, SimulationCommunication};
+use ui::{Counter, Flags, SimulationCommunication};
 
+mod cli;
 mod model;
+mod project;
 mod sim;
 mod ui;
 
+/// Impulse Rocket Simulation Tool
+#[derive(Debug, StructOpt)]
+struct Options {
+    /// RASP (.eng) or RockSim (.rse) motor files to make available for selection
+    #[structopt(short, long = "motor", parse(from_os_str))]
+    motors: Vec<PathBuf>,
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Simulate a project without opening a window, for scripts and CI
+    Run(cli::RunOptions),
+}
+
 fn main() -> iced::Result {
+    let options = Options::from_args();
+
     tracing_subscriber::fmt()
         .compact()
+        .with_writer(io::stderr)
         .with_env_filter(
             EnvFilter::from_default_env().add_directive(
                 if cfg!(debug_assertions) {
@@ -31,6 +54,17 @@ fn main() -> iced::Result {
         )
         .init();
 
+    if let Some(Command::Run(run)) = options.command {
+        process::exit(match cli::run(run) {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("error: {}", error);
+
+                1
+            }
+        });
+    }
+
     let (to_sim, from_ui) = mpmc::bounded_tx_future_rx_blocking(10);
     let (to_ui, from_sim) = mpmc::bounded_tx_blocking_rx_future(10);
 
@@ -40,14 +74,17 @@ fn main() -> iced::Result {
 
     Counter::run(Settings {
         window: window::Settings {
-            size: (750, 500),
+            size: (1000, 750),
             ..Default::default()
         },
         antialiasing: true,
-        flags: SimulationCommunication {
-            to_sim,
-            from_sim,
-            sim_thread: sim_thread.thread().clone(),
+        flags: Flags {
+            simulation: SimulationCommunication {
+                to_sim,
+                from_sim,
+                sim_thread: sim_thread.thread().clone(),
+            },
+            motor_files: options.motors,
         },
         default_font: Settings::<()>::default().default_font,
         default_text_size: Settings::<()>::default().default_text_size,
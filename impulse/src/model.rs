@@ -1,14 +1,69 @@
 use std::fmt::{self, Debug, Display};
 
-#[derive(Debug, Clone, Copy)]
+use crate::{
+    rocket::Rocket,
+    sim::{
+        Axis, DispersionResults, DispersionSettings, FlightRecord, FlightSample, FlightSummary,
+        Goal, LaunchConditions, Metric, Motor, Optimum, SimulationSettings, SweepParameter,
+        SweepResults,
+    },
+};
+
+#[derive(Debug, Clone)]
 pub enum SimulationEvent {
     StatusUpdate(SimulationStatus),
+    /// Samples recorded since the previous batch, in chronological order
+    Samples(Vec<FlightSample>),
+    /// Summary of the flight, sent once the simulation completes
+    Summary(FlightSummary),
+    /// Everything recorded during the flight, sent once the simulation completes
+    Record(FlightRecord),
+    /// Number of flights of a dispersion analysis simulated so far
+    DispersionProgress {
+        completed: usize,
+        total: usize,
+    },
+    /// Results of a dispersion analysis, sent once every flight is simulated
+    Dispersion(DispersionResults),
+    /// Number of flights of a sweep or optimization simulated so far
+    SweepProgress {
+        completed: usize,
+        total: usize,
+    },
+    /// Results of a parameter sweep, sent once every flight is simulated
+    Sweep(SweepResults),
+    /// Best parameter value found by an optimization
+    Optimum(Optimum),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum SimulationControl {
     Start,
+    /// Start a Monte Carlo dispersion analysis of the current setup
+    StartDispersion(DispersionSettings),
+    /// Fly the current setup across one or two parameter ranges
+    StartSweep {
+        x: Axis,
+        y: Option<Axis>,
+    },
+    /// Search a parameter range of the current setup for the value that best
+    /// meets a goal
+    StartOptimization {
+        parameter: SweepParameter,
+        range: (f64, f64),
+        metric: Metric,
+        goal: Goal,
+    },
     Stop,
+    /// Replace the rocket used by subsequent simulations, or leave none until a
+    /// valid design is applied
+    SetRocket(Option<Rocket>),
+    /// Replace the motor loaded into the rocket's motor mount
+    SetMotor(Motor),
+    /// Replace the launch conditions of subsequent simulations
+    SetLaunchConditions(LaunchConditions),
+    /// Replace the integration settings of subsequent simulations
+    SetSettings(SimulationSettings),
 }
 
 #[derive(Debug, Clone, Copy)]
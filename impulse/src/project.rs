@@ -0,0 +1,53 @@
+//! Project files, which are loaded and saved by [`impulse_core::project`], and
+//! the list of recently used ones
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use directories::ProjectDirs;
+use tracing::warn;
+
+pub use impulse_core::project::*;
+
+/// Most recently used project files that are remembered
+const MAX_RECENT_FILES: usize = 8;
+
+/// Location of the list of recently used project files
+fn recent_files_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "impulse").map(|dirs| dirs.config_dir().join("recent.json"))
+}
+
+/// Project files that have been opened or saved recently, most recent first
+pub fn recent_files() -> Vec<PathBuf> {
+    recent_files_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Move a project file to the top of the recently used list, returning the
+/// updated list
+pub fn add_recent_file(path: &Path) -> Vec<PathBuf> {
+    let mut files = recent_files();
+    files.retain(|file| file != path);
+    files.insert(0, path.to_path_buf());
+    files.truncate(MAX_RECENT_FILES);
+
+    let saved = recent_files_path().map(|recent_path| -> Result<(), ProjectError> {
+        if let Some(parent) = recent_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(recent_path, serde_json::to_string_pretty(&files)?)?;
+
+        Ok(())
+    });
+
+    if let Some(Err(error)) = saved {
+        warn!(%error, "Failed to save recent files");
+    }
+
+    files
+}
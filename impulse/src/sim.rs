@@ -1,3 +1,6 @@
+//! Runs simulations from [`impulse_core::sim`] on a background thread,
+//! controlled by and reporting to the UI
+
 use std::{
     any::TypeId,
     hash::{Hash, Hasher},
@@ -12,7 +15,22 @@ use iced::Subscription;
 use iced_futures::{subscription::Recipe, BoxStream};
 use tracing::{debug, info, trace, warn};
 
-use crate::model::{SimulationControl, SimulationEvent, SimulationStatus};
+use crate::{
+    model::{SimulationControl, SimulationEvent, SimulationStatus},
+    rocket::Rocket,
+};
+
+pub use impulse_core::sim::*;
+
+/// Interval between the samples forwarded to the UI thread (s)
+const SAMPLE_INTERVAL: f64 = 0.05;
+
+/// Simulated time covered by each batch of samples sent to the UI thread (s)
+const BATCH_DURATION: f64 = 0.5;
+
+/// Number of flights of a dispersion analysis or sweep simulated between
+/// progress updates
+const ANALYSIS_BATCH: usize = 16;
 
 /// Get a subscription to the events emitted from the simulation thread
 pub fn subscribe(
@@ -52,6 +70,13 @@ fn simulation_thread_internal(
     from_ui: RxBlocking<SimulationControl, SharedSenderFRecvB>,
 ) -> Option<()> {
     let mut status = SimulationStatus::Idle;
+    let mut rocket = Some(Rocket::default());
+    let mut motor = Motor::demo();
+    let mut launch = LaunchConditions::default();
+    let mut settings = SimulationSettings::default();
+    let mut simulation = None;
+    let mut analysis: Option<Analysis> = None;
+    let mut history = Vec::new();
 
     let update_status = |old_status: &mut _, new_status| {
         *old_status = new_status;
@@ -59,34 +84,321 @@ fn simulation_thread_internal(
         to_ui.send(SimulationEvent::StatusUpdate(new_status)).ok()
     };
 
+    update_status(&mut status, SimulationStatus::Idle)?;
+
     loop {
         debug!("Parking simulation thread");
-        update_status(&mut status, SimulationStatus::Idle);
         thread::park();
 
         loop {
             let control = match from_ui.try_recv() {
                 Ok(e) => Some(e),
                 Err(TryRecvError::Empty) => None,
-                Err(TryRecvError::Disconnected) => todo!(),
+                Err(TryRecvError::Disconnected) => return None,
             };
 
             trace!(?status, ?control);
 
             match (control, status) {
-                (None, SimulationStatus::Idle) => {
+                (None, SimulationStatus::Running) if analysis.is_some() => {
+                    let (progress, results) = analysis.as_mut()?.run_batch();
+
+                    to_ui.send(progress).ok()?;
+
+                    if let Some(results) = results {
+                        analysis = None;
+                        to_ui.send(results).ok()?;
+                        update_status(&mut status, SimulationStatus::Complete)?;
+                        break;
+                    }
+                }
+                (None, SimulationStatus::Running) => {
+                    let simulation = simulation.as_mut()?;
+
+                    to_ui
+                        .send(SimulationEvent::Samples(run_batch(
+                            simulation,
+                            &mut history,
+                        )))
+                        .ok()?;
+
+                    if simulation.is_complete() {
+                        let summary =
+                            FlightSummary::new(simulation.config(), &history, simulation.events());
+                        let record = FlightRecord::new(simulation, history.clone());
+
+                        to_ui.send(SimulationEvent::Summary(summary)).ok()?;
+                        to_ui.send(SimulationEvent::Record(record)).ok()?;
+                        update_status(&mut status, SimulationStatus::Complete)?;
+                        break;
+                    }
+                }
+                (None, _) => {
                     break;
                 }
-                (None, _) => unimplemented!("Simulation not setup yet, but this should tick it"),
-                (Some(SimulationControl::Start), SimulationStatus::Idle) => {
+                (Some(SimulationControl::Start), SimulationStatus::Running) => {
+                    warn!("Simulation is already running");
+                }
+                (
+                    Some(
+                        SimulationControl::Start
+                        | SimulationControl::StartDispersion(_)
+                        | SimulationControl::StartSweep { .. }
+                        | SimulationControl::StartOptimization { .. },
+                    ),
+                    _,
+                ) if rocket.is_none() => {
+                    warn!("No valid rocket design to simulate");
+                    to_ui.send(SimulationEvent::StatusUpdate(status)).ok()?;
+                }
+                (Some(SimulationControl::Start), _) => {
+                    let rocket = rocket.as_ref()?;
+                    let new_simulation = match Simulation::new(SimulationConfig {
+                        vehicle: rocket.to_vehicle(Some(motor.clone())),
+                        launch,
+                        settings,
+                    }) {
+                        Ok(simulation) => simulation,
+                        Err(error) => {
+                            warn!(%error, "Cannot start the simulation");
+                            to_ui.send(SimulationEvent::StatusUpdate(status)).ok()?;
+                            continue;
+                        }
+                    };
+
+                    analysis = None;
+                    history.clear();
+                    history.push(new_simulation.sample());
+
                     update_status(&mut status, SimulationStatus::Running)?;
+                    to_ui.send(SimulationEvent::Samples(history.clone())).ok()?;
+
+                    simulation.replace(new_simulation);
+                }
+                (
+                    Some(
+                        SimulationControl::StartDispersion(_)
+                        | SimulationControl::StartSweep { .. }
+                        | SimulationControl::StartOptimization { .. },
+                    ),
+                    SimulationStatus::Running,
+                ) => {
+                    warn!("Simulation is already running");
+                }
+                (Some(SimulationControl::StartDispersion(dispersion_settings)), _) => {
+                    info!(
+                        runs = dispersion_settings.runs,
+                        "Starting dispersion analysis"
+                    );
+
+                    let rocket = rocket.as_ref()?;
+
+                    let monte_carlo = match MonteCarlo::new(
+                        SimulationConfig {
+                            vehicle: rocket.to_vehicle(Some(motor.clone())),
+                            launch,
+                            settings,
+                        },
+                        dispersion_settings,
+                    ) {
+                        Ok(monte_carlo) => monte_carlo,
+                        Err(error) => {
+                            warn!(%error, "Cannot start the dispersion analysis");
+                            to_ui.send(SimulationEvent::StatusUpdate(status)).ok()?;
+                            continue;
+                        }
+                    };
+                    let new_analysis = Analysis::Dispersion(monte_carlo);
+
+                    update_status(&mut status, SimulationStatus::Running)?;
+                    to_ui.send(new_analysis.progress()).ok()?;
+
+                    analysis.replace(new_analysis);
+                }
+                (Some(SimulationControl::StartSweep { x, y }), _) => {
+                    info!(?x, ?y, "Starting parameter sweep");
+
+                    let rocket = rocket.as_ref()?;
+
+                    let baseline = match Baseline::new(
+                        SimulationConfig {
+                            vehicle: rocket.to_vehicle(Some(motor.clone())),
+                            launch,
+                            settings,
+                        },
+                        rocket.nose_weight_position(),
+                    ) {
+                        Ok(baseline) => baseline,
+                        Err(error) => {
+                            warn!(%error, "Cannot start the parameter sweep");
+                            to_ui.send(SimulationEvent::StatusUpdate(status)).ok()?;
+                            continue;
+                        }
+                    };
+                    let new_analysis = Analysis::Sweep(Sweep::new(baseline, x, y));
+
+                    update_status(&mut status, SimulationStatus::Running)?;
+                    to_ui.send(new_analysis.progress()).ok()?;
+
+                    analysis.replace(new_analysis);
+                }
+                (
+                    Some(SimulationControl::StartOptimization {
+                        parameter,
+                        range,
+                        metric,
+                        goal,
+                    }),
+                    _,
+                ) => {
+                    info!(?parameter, ?range, ?metric, ?goal, "Starting optimization");
+
+                    let rocket = rocket.as_ref()?;
+
+                    let baseline = match Baseline::new(
+                        SimulationConfig {
+                            vehicle: rocket.to_vehicle(Some(motor.clone())),
+                            launch,
+                            settings,
+                        },
+                        rocket.nose_weight_position(),
+                    ) {
+                        Ok(baseline) => baseline,
+                        Err(error) => {
+                            warn!(%error, "Cannot start the optimization");
+                            to_ui.send(SimulationEvent::StatusUpdate(status)).ok()?;
+                            continue;
+                        }
+                    };
+                    let new_analysis = Analysis::Optimization(Optimizer::new(
+                        baseline, parameter, range, metric, goal,
+                    ));
+
+                    update_status(&mut status, SimulationStatus::Running)?;
+                    to_ui.send(new_analysis.progress()).ok()?;
+
+                    analysis.replace(new_analysis);
                 }
                 (Some(SimulationControl::Stop), SimulationStatus::Running) => {
+                    analysis = None;
                     update_status(&mut status, SimulationStatus::Cancelled)?;
                     break;
                 }
-                _ => unimplemented!(),
+                (Some(SimulationControl::Stop), _) => {
+                    warn!("No simulation is running");
+                }
+                (Some(SimulationControl::SetRocket(new_rocket)), _) => {
+                    debug!(
+                        name = ?new_rocket.as_ref().map(|rocket| &rocket.name),
+                        "Received new rocket design"
+                    );
+
+                    rocket = new_rocket;
+                }
+                (Some(SimulationControl::SetMotor(new_motor)), _) => {
+                    debug!(designation = %new_motor.designation, "Received new motor");
+
+                    motor = new_motor;
+                }
+                (Some(SimulationControl::SetLaunchConditions(new_launch)), _) => {
+                    debug!(?new_launch, "Received new launch conditions");
+
+                    launch = new_launch;
+                }
+                (Some(SimulationControl::SetSettings(new_settings)), _) => {
+                    debug!(?new_settings, "Received new simulation settings");
+
+                    settings = new_settings;
+                }
+            }
+        }
+    }
+}
+
+/// A batch analysis flying many variations of the current setup
+enum Analysis {
+    Dispersion(MonteCarlo),
+    Sweep(Sweep),
+    Optimization(Optimizer),
+}
+
+impl Analysis {
+    /// Event reporting the number of flights simulated so far
+    fn progress(&self) -> SimulationEvent {
+        match self {
+            Analysis::Dispersion(monte_carlo) => SimulationEvent::DispersionProgress {
+                completed: monte_carlo.completed(),
+                total: monte_carlo.total(),
+            },
+            Analysis::Sweep(sweep) => SimulationEvent::SweepProgress {
+                completed: sweep.completed(),
+                total: sweep.total(),
+            },
+            Analysis::Optimization(optimizer) => SimulationEvent::SweepProgress {
+                completed: optimizer.completed(),
+                total: optimizer.total(),
+            },
+        }
+    }
+
+    /// Simulate the next batch of flights, returning the progress and, once
+    /// every flight is simulated, the results
+    fn run_batch(&mut self) -> (SimulationEvent, Option<SimulationEvent>) {
+        let results = match self {
+            Analysis::Dispersion(monte_carlo) => {
+                monte_carlo.run_batch(ANALYSIS_BATCH);
+
+                if monte_carlo.is_complete() {
+                    Some(SimulationEvent::Dispersion(monte_carlo.results()))
+                } else {
+                    None
+                }
+            }
+            Analysis::Sweep(sweep) => {
+                sweep.run_batch(ANALYSIS_BATCH);
+
+                if sweep.is_complete() {
+                    Some(SimulationEvent::Sweep(sweep.results()))
+                } else {
+                    None
+                }
+            }
+            // Each step of the search depends on the last, so they run one
+            // at a time
+            Analysis::Optimization(optimizer) => {
+                optimizer.step();
+
+                if optimizer.is_complete() {
+                    Some(SimulationEvent::Optimum(optimizer.result()))
+                } else {
+                    None
+                }
             }
+        };
+
+        (self.progress(), results)
+    }
+}
+
+/// Advance the simulation by [`BATCH_DURATION`], recording every step into
+/// the history and collecting a sample for the UI every [`SAMPLE_INTERVAL`]
+/// and at the end of the flight
+fn run_batch(simulation: &mut Simulation, history: &mut Vec<FlightSample>) -> Vec<FlightSample> {
+    let end = simulation.time() + BATCH_DURATION;
+    let mut samples = Vec::new();
+
+    while simulation.time() < end && !simulation.is_complete() {
+        let previous_time = simulation.time();
+        let sample = simulation.step();
+
+        history.push(sample);
+
+        if (sample.time / SAMPLE_INTERVAL).floor() > (previous_time / SAMPLE_INTERVAL).floor()
+            || simulation.is_complete()
+        {
+            samples.push(sample);
         }
     }
+
+    samples
 }
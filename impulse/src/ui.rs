@@ -1,31 +1,167 @@
-use std::{fmt::Debug, thread::Thread};
+use std::{
+    fmt::{self, Debug, Display},
+    future::Future,
+    path::{Path, PathBuf},
+    thread::Thread,
+};
 
 use crossfire::mpmc::{RxFuture, SharedSenderBRecvF, SharedSenderFRecvB, TxFuture};
 use iced::{
-    button, executor, Align, Application, Button, Clipboard, Column, Command, Container, Element,
-    Length, Subscription, Text,
+    button, executor, pick_list, Align, Application, Button, Checkbox, Clipboard, Color, Column,
+    Command, Container, Element, Length, PickList, Row, Space, Subscription, Text,
 };
-use tracing::trace;
+use plotters_iced::ChartWidget;
+use rfd::{AsyncFileDialog, AsyncMessageDialog, MessageButtons, MessageLevel};
+use tracing::{info, trace, warn};
 
 use crate::{
     model::{SimulationControl, SimulationEvent, SimulationStatus},
-    sim,
+    project::{self, Project},
+    rocket::{ImportLevel, ImportMessage, Rocket},
+    sim::{self, FlightRecord, FlightSummary, LaunchConditions, Motor, SimulationSettings},
+};
+
+use self::{
+    dispersion::{DispersionMessage, DispersionScreen},
+    editor::{Editor, EditorMessage},
+    export::{ExportMessage, ExportPanel},
+    launch::{LaunchEditor, LaunchMessage},
+    motors::{MotorBrowser, MotorMessage},
+    plot::{FlightPlot, Series},
+    sweep::{SweepMessage, SweepScreen},
 };
 
+mod dispersion;
+mod editor;
+mod export;
+mod landing;
+mod launch;
+mod motors;
+mod plot;
+mod range;
+mod summary;
+mod sweep;
+mod units;
+
+const ERROR_COLOR: Color = Color::from_rgb(0.8, 0.1, 0.1);
+const WARNING_COLOR: Color = Color::from_rgb(0.8, 0.5, 0.0);
+
 pub struct Counter {
     simulation_status: Option<SimulationStatus>,
 
     simulation: SimulationCommunication,
 
+    screen: Screen,
+    editor: Editor,
+    motor: Motor,
+    motors: MotorBrowser,
+    launch: LaunchConditions,
+    launch_editor: LaunchEditor,
+    settings: SimulationSettings,
+    simulation_screen: SimulationScreen,
+    dispersion: DispersionScreen,
+    sweep: SweepScreen,
+    files: ProjectFiles,
+
+    button_screens: [button::State; Screen::ALL.len()],
+}
+
+/// The file the project is saved in and the controls for opening and saving
+struct ProjectFiles {
+    path: Option<PathBuf>,
+    /// The project as it was last opened or saved, to detect unsaved changes
+    saved: Project,
+    recent: Vec<RecentFile>,
+    error: Option<String>,
+
+    button_open: button::State,
+    button_save: button::State,
+    button_save_as: button::State,
+    button_import: button::State,
+    recent_picker: pick_list::State<RecentFile>,
+}
+
+/// A recently used project file, as listed in the recent files picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFile(PathBuf);
+
+impl Display for RecentFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+/// Controls and results of the simulation
+struct SimulationScreen {
+    plot: FlightPlot,
+    summary: Option<FlightSummary>,
+    record: Option<FlightRecord>,
+    export: ExportPanel,
+
     button_control_sim: button::State,
+    button_reset_plot: button::State,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
     StartSimulation,
     StopSimulation,
     PendAction,
+    /// A control that leaves the simulation status as it is has been sent
+    ControlSent,
     SimulationEvent(SimulationEvent),
+    ShowScreen(Screen),
+    Editor(EditorMessage),
+    Motors(MotorMessage),
+    Launch(LaunchMessage),
+    Dispersion(DispersionMessage),
+    Sweep(SweepMessage),
+    OpenProject,
+    OpenRecent(RecentFile),
+    ProjectPicked(Option<PathBuf>),
+    SaveProject,
+    SaveProjectAs,
+    SavePathPicked(Option<PathBuf>),
+    ImportDesign,
+    DesignPicked(Option<PathBuf>),
+    Export(ExportMessage),
+    ToggleSeries(Series, bool),
+    ResetPlotView,
+    PlotChanged,
+}
+
+/// The top level screens of the application, selected with tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Design,
+    Motors,
+    Launch,
+    Simulation,
+    Dispersion,
+    Sweep,
+}
+
+impl Screen {
+    const ALL: [Screen; 6] = [
+        Screen::Design,
+        Screen::Motors,
+        Screen::Launch,
+        Screen::Simulation,
+        Screen::Dispersion,
+        Screen::Sweep,
+    ];
+}
+
+impl Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self, f)
+    }
+}
+
+pub struct Flags {
+    pub simulation: SimulationCommunication,
+    /// Motor files to load on startup
+    pub motor_files: Vec<PathBuf>,
 }
 
 pub struct SimulationCommunication {
@@ -34,29 +170,112 @@ pub struct SimulationCommunication {
     pub sim_thread: Thread,
 }
 
+impl SimulationCommunication {
+    /// Send a control signal to the simulation thread, waking it if necessary
+    fn send(&self, control: SimulationControl) -> Command<Message> {
+        let to_sim = self.to_sim.clone();
+        let sim_thread = self.sim_thread.clone();
+
+        // Only starting or stopping changes the status, which is unknown until
+        // the simulation thread reports it again
+        let pends = !matches!(
+            control,
+            SimulationControl::SetRocket(_)
+                | SimulationControl::SetMotor(_)
+                | SimulationControl::SetLaunchConditions(_)
+                | SimulationControl::SetSettings(_)
+        );
+
+        trace!(?control, "Sending control signal to sim");
+
+        Command::perform(
+            async move {
+                sim_thread.unpark();
+                to_sim.send(control).await
+            },
+            move |_| {
+                if pends {
+                    Message::PendAction
+                } else {
+                    Message::ControlSent
+                }
+            },
+        )
+    }
+}
+
 impl Application for Counter {
     type Message = Message;
 
     type Executor = executor::Default;
 
-    type Flags = SimulationCommunication;
+    type Flags = Flags;
 
     fn new(flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        let (mut motors, load_motors) = MotorBrowser::new();
+        let project = Project::default();
+
+        for path in &flags.motor_files {
+            motors.load_file(path);
+        }
+
         (
             Self {
-                simulation: flags,
+                simulation: flags.simulation,
 
                 simulation_status: None,
 
-                button_control_sim: button::State::new(),
+                screen: Screen::Design,
+                editor: Editor::new(project.rocket.clone()),
+                motor: project.motor.clone(),
+                motors,
+                launch: project.launch,
+                launch_editor: LaunchEditor::new(project.launch),
+                settings: project.settings,
+                simulation_screen: SimulationScreen {
+                    plot: FlightPlot::new(),
+                    summary: None,
+                    record: None,
+                    export: ExportPanel::new(),
+
+                    button_control_sim: button::State::new(),
+                    button_reset_plot: button::State::new(),
+                },
+                dispersion: DispersionScreen::new(project.dispersion, project.field.clone()),
+                sweep: SweepScreen::new(),
+
+                files: ProjectFiles {
+                    path: None,
+                    saved: project,
+                    recent: project::recent_files()
+                        .into_iter()
+                        .map(RecentFile)
+                        .collect(),
+                    error: None,
+
+                    button_open: button::State::new(),
+                    button_save: button::State::new(),
+                    button_save_as: button::State::new(),
+                    button_import: button::State::new(),
+                    recent_picker: pick_list::State::default(),
+                },
+
+                button_screens: Default::default(),
             },
-            Command::none(),
+            load_motors.map(Message::Motors),
         )
     }
 
     fn title(&self) -> String {
         format!(
-            "Impulse Rocket Simulator ({})",
+            "Impulse Rocket Simulator - {}{} ({})",
+            self.files
+                .path
+                .as_deref()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "Untitled".into()),
+            if self.is_dirty() { "*" } else { "" },
             self.simulation_status
                 .as_ref()
                 .map(ToString::to_string)
@@ -73,51 +292,494 @@ impl Application for Counter {
         message: Self::Message,
         _clipboard: &mut Clipboard,
     ) -> Command<Self::Message> {
-        let send_to_sim = |control| {
-            let to_sim = self.simulation.to_sim.clone();
-            let sim_thread = self.simulation.sim_thread.clone();
-
-            trace!(?control, "Sending control signal to sim");
-
-            Command::perform(
-                async move {
-                    sim_thread.unpark();
-                    to_sim.send(control).await
-                },
-                |_| Message::PendAction,
-            )
-        };
-
         match message {
-            Message::StartSimulation => send_to_sim(SimulationControl::Start),
-            Message::StopSimulation => send_to_sim(SimulationControl::Stop),
+            Message::StartSimulation => {
+                self.simulation_screen.plot.clear();
+                self.simulation_screen.summary = None;
+                self.simulation_screen.record = None;
+
+                self.simulation.send(SimulationControl::Start)
+            }
+            Message::StopSimulation => self.simulation.send(SimulationControl::Stop),
             Message::PendAction => {
                 self.simulation_status.take();
 
                 Command::none()
             }
+            Message::ControlSent => Command::none(),
             Message::SimulationEvent(e) => match e {
                 SimulationEvent::StatusUpdate(status) => {
                     self.simulation_status.replace(status);
 
                     Command::none()
                 }
+                SimulationEvent::Samples(samples) => {
+                    self.simulation_screen.plot.extend(samples);
+
+                    Command::none()
+                }
+                SimulationEvent::Summary(summary) => {
+                    self.simulation_screen.summary.replace(summary);
+
+                    Command::none()
+                }
+                SimulationEvent::Record(record) => {
+                    self.simulation_screen.record.replace(record);
+
+                    Command::none()
+                }
+                SimulationEvent::DispersionProgress { completed, total } => {
+                    self.dispersion.set_progress(completed, total);
+
+                    Command::none()
+                }
+                SimulationEvent::Dispersion(results) => {
+                    self.dispersion.set_results(results);
+
+                    Command::none()
+                }
+                SimulationEvent::SweepProgress { completed, total } => {
+                    self.sweep.set_progress(completed, total);
+
+                    Command::none()
+                }
+                SimulationEvent::Sweep(results) => {
+                    self.sweep.set_results(results);
+
+                    Command::none()
+                }
+                SimulationEvent::Optimum(optimum) => {
+                    self.sweep.set_optimum(optimum);
+
+                    Command::none()
+                }
+            },
+            Message::ShowScreen(screen) => {
+                self.screen = screen;
+
+                Command::none()
+            }
+            Message::Editor(message) => match self.editor.update(message) {
+                Some(rocket) => self
+                    .simulation
+                    .send(SimulationControl::SetRocket(Some(rocket))),
+                None => Command::none(),
+            },
+            Message::Motors(message) => {
+                let (command, motor) = self.motors.update(message, self.editor.rocket());
+                let command = command.map(Message::Motors);
+
+                match motor {
+                    Some(motor) => {
+                        self.motor = motor.clone();
+
+                        Command::batch(vec![
+                            command,
+                            self.simulation.send(SimulationControl::SetMotor(motor)),
+                        ])
+                    }
+                    None => command,
+                }
+            }
+            Message::Launch(message) => match self.launch_editor.update(message) {
+                Some(launch) => {
+                    self.launch = launch;
+
+                    self.simulation
+                        .send(SimulationControl::SetLaunchConditions(launch))
+                }
+                None => Command::none(),
+            },
+            Message::Dispersion(message) => match self.dispersion.update(message) {
+                Some(control) => self.simulation.send(control),
+                None => Command::none(),
             },
+            Message::Sweep(message) => match self.sweep.update(message) {
+                Some(control) => self.simulation.send(control),
+                None => Command::none(),
+            },
+            Message::OpenProject => {
+                let confirmed = self.confirm_discard();
+
+                Command::perform(
+                    async move {
+                        if !confirmed.await {
+                            return None;
+                        }
+
+                        AsyncFileDialog::new()
+                            .set_title("Open Project")
+                            .add_filter("Impulse project", &[project::EXTENSION])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::ProjectPicked,
+                )
+            }
+            Message::OpenRecent(RecentFile(path)) => {
+                let confirmed = self.confirm_discard();
+
+                Command::perform(
+                    async move {
+                        if confirmed.await {
+                            Some(path)
+                        } else {
+                            None
+                        }
+                    },
+                    Message::ProjectPicked,
+                )
+            }
+            Message::ProjectPicked(Some(path)) => self.open_project(path),
+            Message::ProjectPicked(None)
+            | Message::SavePathPicked(None)
+            | Message::DesignPicked(None) => Command::none(),
+            Message::SaveProject => match self.files.path.clone() {
+                Some(path) => {
+                    self.save_project(path);
+
+                    Command::none()
+                }
+                None => self.pick_save_path(),
+            },
+            Message::SaveProjectAs => self.pick_save_path(),
+            Message::SavePathPicked(Some(mut path)) => {
+                if path.extension().is_none() {
+                    path.set_extension(project::EXTENSION);
+                }
+
+                self.save_project(path);
+
+                Command::none()
+            }
+            Message::ImportDesign => {
+                let confirmed = self.confirm_discard();
+
+                Command::perform(
+                    async move {
+                        if !confirmed.await {
+                            return None;
+                        }
+
+                        AsyncFileDialog::new()
+                            .set_title("Import Design")
+                            .add_filter("Rocket design", &["ork", "rkt"])
+                            .add_filter("OpenRocket design", &["ork"])
+                            .add_filter("RockSim design", &["rkt"])
+                            .pick_file()
+                            .await
+                            .map(|file| file.path().to_path_buf())
+                    },
+                    Message::DesignPicked,
+                )
+            }
+            Message::DesignPicked(Some(path)) => self.import_design(path),
+            Message::Export(message) => {
+                let screen = &mut self.simulation_screen;
+                let landing_points = self
+                    .dispersion
+                    .results()
+                    .map(|results| results.landing_points())
+                    .unwrap_or_default();
+
+                screen
+                    .export
+                    .update(message, screen.record.as_ref(), &landing_points)
+                    .map(Message::Export)
+            }
+            Message::ToggleSeries(series, visible) => {
+                self.simulation_screen.plot.set_visible(series, visible);
+
+                Command::none()
+            }
+            Message::ResetPlotView => {
+                self.simulation_screen.plot.reset_view();
+
+                Command::none()
+            }
+            Message::PlotChanged => Command::none(),
         }
     }
 
     fn view(&mut self) -> Element<'_, Self::Message> {
+        let dirty = self.is_dirty();
+        let current_screen = self.screen;
+        let tabs = Screen::ALL.iter().zip(self.button_screens.iter_mut()).fold(
+            Row::new().spacing(5),
+            |tabs, (&screen, state)| {
+                let button = Button::new(state, Text::new(screen.to_string()));
+
+                tabs.push(if screen == current_screen {
+                    button
+                } else {
+                    button.on_press(Message::ShowScreen(screen))
+                })
+            },
+        );
+
+        let files = self.files.view(dirty);
+        let content = match self.screen {
+            Screen::Design => self.editor.view(&self.motor).map(Message::Editor),
+            Screen::Motors => self
+                .motors
+                .view(self.editor.rocket(), &self.motor)
+                .map(Message::Motors),
+            Screen::Launch => self.launch_editor.view().map(Message::Launch),
+            Screen::Simulation => self.simulation_screen.view(self.simulation_status),
+            Screen::Dispersion => self
+                .dispersion
+                .view(self.simulation_status)
+                .map(Message::Dispersion),
+            Screen::Sweep => self.sweep.view(self.simulation_status).map(Message::Sweep),
+        };
+
+        Container::new(
+            Column::new()
+                .spacing(10)
+                .push(
+                    tabs.push(Space::with_width(Length::Fill))
+                        .push(files)
+                        .align_items(Align::Center),
+                )
+                .push(content),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(5)
+        .into()
+    }
+}
+
+impl Counter {
+    /// The project as it currently stands in the UI
+    fn project(&self) -> Project {
+        Project {
+            rocket: self.editor.rocket().clone(),
+            motor: self.motor.clone(),
+            launch: self.launch,
+            settings: self.settings,
+            dispersion: self.dispersion.settings(),
+            field: self.dispersion.field_limits().clone(),
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.project() != self.files.saved
+    }
+
+    /// Ask the user whether to discard unsaved changes, resolving immediately
+    /// if there are none
+    fn confirm_discard(&self) -> impl Future<Output = bool> {
+        let dialog = if self.is_dirty() {
+            Some(
+                AsyncMessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title("Unsaved Changes")
+                    .set_description("The current project has unsaved changes. Discard them?")
+                    .set_buttons(MessageButtons::YesNo),
+            )
+        } else {
+            None
+        };
+
+        async move {
+            match dialog {
+                Some(dialog) => dialog.show().await,
+                None => true,
+            }
+        }
+    }
+
+    fn open_project(&mut self, path: PathBuf) -> Command<Message> {
+        let project = match Project::load(&path) {
+            Ok(project) => project,
+            Err(error) => {
+                warn!(path = %path.display(), %error, "Failed to open project");
+
+                self.files.error = Some(format!("Failed to open {}: {}", path.display(), error));
+
+                return Command::none();
+            }
+        };
+
+        info!(path = %path.display(), "Opened project");
+
+        self.editor = Editor::new(project.rocket.clone());
+        self.motor = project.motor.clone();
+        self.launch = project.launch;
+        self.launch_editor = LaunchEditor::new(project.launch);
+        self.settings = project.settings;
+        self.dispersion = DispersionScreen::new(project.dispersion, project.field.clone());
+        self.files.opened(path, project.clone());
+
+        // An invalid design is only flown once it has been fixed in the editor,
+        // which lists what is wrong with it, so the previous project's rocket
+        // cannot be flown in the meantime
+        let rocket = if project.rocket.validate().is_empty() {
+            Some(project.rocket)
+        } else {
+            self.screen = Screen::Design;
+
+            None
+        };
+
+        Command::batch(vec![
+            self.simulation.send(SimulationControl::SetRocket(rocket)),
+            self.simulation
+                .send(SimulationControl::SetMotor(project.motor)),
+            self.simulation
+                .send(SimulationControl::SetLaunchConditions(project.launch)),
+            self.simulation
+                .send(SimulationControl::SetSettings(project.settings)),
+        ])
+    }
+
+    /// Replace the rocket with one designed in another simulator, leaving the
+    /// rest of the project as it is
+    fn import_design(&mut self, path: PathBuf) -> Command<Message> {
+        let import = match Rocket::import(&path) {
+            Ok(import) => import,
+            Err(error) => {
+                warn!(path = %path.display(), %error, "Failed to import design");
+
+                self.files.error = Some(format!("Failed to import {}: {}", path.display(), error));
+
+                return Command::none();
+            }
+        };
+
+        info!(path = %path.display(), "Imported design");
+
+        let mut log = import.log;
+
+        // Designs that fail validation are already in the import log, and are
+        // only flown once they have been fixed in the editor rather than
+        // leaving the previous rocket to be flown in the meantime
+        let rocket = Some(import.rocket.clone()).filter(|rocket| rocket.validate().is_empty());
+        let mut commands = vec![self.simulation.send(SimulationControl::SetRocket(rocket))];
+
+        if let Some(motor) = import.motor.filter(|motor| !motor.designation.is_empty()) {
+            log.push(ImportMessage {
+                component: motor.designation.clone(),
+                level: ImportLevel::Info,
+                message: "searching ThrustCurve for the motor, assign it from the Motors screen"
+                    .into(),
+            });
+            commands.push(self.motors.find(&motor).map(Message::Motors));
+        }
+
+        self.editor = Editor::new(import.rocket);
+        self.editor.set_import_log(log);
+        self.screen = Screen::Design;
+        self.files.path = None;
+        self.files.error = None;
+
+        Command::batch(commands)
+    }
+
+    /// Ask where to save the project, named after the rocket by default
+    fn pick_save_path(&self) -> Command<Message> {
+        let file_name = format!("{}.{}", self.editor.rocket().name, project::EXTENSION);
+
+        Command::perform(
+            AsyncFileDialog::new()
+                .set_title("Save Project")
+                .add_filter("Impulse project", &[project::EXTENSION])
+                .set_file_name(&file_name)
+                .save_file(),
+            |file| Message::SavePathPicked(file.map(|file| file.path().to_path_buf())),
+        )
+    }
+
+    fn save_project(&mut self, path: PathBuf) {
+        let project = self.project();
+
+        match project.save(&path) {
+            Ok(()) => {
+                info!(path = %path.display(), "Saved project");
+
+                self.files.opened(path, project);
+            }
+            Err(error) => {
+                warn!(path = %path.display(), %error, "Failed to save project");
+
+                self.files.error = Some(format!("Failed to save {}: {}", path.display(), error));
+            }
+        }
+    }
+}
+
+impl ProjectFiles {
+    /// Record that the project is now stored in a file
+    fn opened(&mut self, path: PathBuf, project: Project) {
+        self.recent = project::add_recent_file(&path)
+            .into_iter()
+            .map(RecentFile)
+            .collect();
+        self.path = Some(path);
+        self.saved = project;
+        self.error = None;
+    }
+
+    fn view(&mut self, dirty: bool) -> Row<'_, Message> {
+        let mut save = Button::new(&mut self.button_save, Text::new("Save"));
+        if dirty || self.path.is_none() {
+            save = save.on_press(Message::SaveProject);
+        }
+
+        let row = Row::new().spacing(5).align_items(Align::Center);
+        let row = match &self.error {
+            Some(error) => row.push(Text::new(error.as_str()).size(16).color(ERROR_COLOR)),
+            None => row,
+        };
+
+        row.push(
+            Button::new(&mut self.button_open, Text::new("Open...")).on_press(Message::OpenProject),
+        )
+        .push(save)
+        .push(
+            Button::new(&mut self.button_save_as, Text::new("Save As..."))
+                .on_press(Message::SaveProjectAs),
+        )
+        .push(
+            Button::new(&mut self.button_import, Text::new("Import..."))
+                .on_press(Message::ImportDesign),
+        )
+        .push(Text::new("Recent:"))
+        .push(PickList::new(
+            &mut self.recent_picker,
+            &self.recent[..],
+            None,
+            Message::OpenRecent,
+        ))
+    }
+}
+
+impl SimulationScreen {
+    fn view(&mut self, simulation_status: Option<SimulationStatus>) -> Element<'_, Message> {
+        let series_toggles = Series::ALL.iter().fold(
+            Row::new().spacing(10).align_items(Align::Center),
+            |row, &series| {
+                row.push(Checkbox::new(
+                    self.plot.is_visible(series),
+                    series.name(),
+                    move |visible| Message::ToggleSeries(series, visible),
+                ))
+            },
+        );
+
         let content = Column::new()
             .align_items(Align::Center)
             .push(Text::new(format!(
                 "Simulation Status: {}",
-                self.simulation_status
+                simulation_status
                     .as_ref()
                     .map(ToString::to_string)
                     .unwrap_or_else(|| "Pending".into())
             )));
 
-        let content = if let Some(status) = self.simulation_status {
+        let content = if let Some(status) = simulation_status {
             let (button_label, button_message) = match status {
                 SimulationStatus::Idle
                 | SimulationStatus::Complete
@@ -133,12 +795,34 @@ impl Application for Counter {
             content
         };
 
+        let content = content
+            .spacing(5)
+            .push(
+                series_toggles.push(
+                    Button::new(&mut self.button_reset_plot, Text::new("Reset Zoom"))
+                        .on_press(Message::ResetPlotView),
+                ),
+            )
+            .push({
+                let chart = ChartWidget::new(&mut self.plot)
+                    .width(Length::Fill)
+                    .height(Length::Fill);
+
+                match &self.summary {
+                    Some(flight_summary) => Row::new().spacing(10).push(chart).push(
+                        Column::new()
+                            .spacing(20)
+                            .push(summary::view(flight_summary))
+                            .push(self.export.view(self.record.is_some()).map(Message::Export)),
+                    ),
+                    None => Row::new().push(chart),
+                }
+            });
+
         Container::new(content)
             .width(Length::Fill)
             .height(Length::Fill)
-            .padding(5)
             .center_x()
-            .center_y()
             .into()
     }
 }
@@ -0,0 +1,506 @@
+use std::fmt::{self, Display};
+
+use iced::{
+    button, pick_list, scrollable, text_input, Align, Button, Column, Element, Length, PickList,
+    ProgressBar, Row, Scrollable, Text, TextInput,
+};
+use plotters_iced::ChartWidget;
+
+use crate::{
+    model::{SimulationControl, SimulationStatus},
+    sim::{DispersionResults, DispersionSettings, Distribution, FieldLimits, RangeCheck},
+};
+
+use super::{
+    landing::LandingPlot,
+    range::{RangeEditor, RangeMessage},
+    units::{format_value, Quantity, Unit},
+    ERROR_COLOR,
+};
+
+/// Screen for setting up and running Monte Carlo dispersion analyses
+pub struct DispersionScreen {
+    settings: DispersionSettings,
+    runs: String,
+    seed: String,
+    fields: Vec<Field>,
+    /// Flights simulated so far and the number in the analysis
+    progress: Option<(usize, usize)>,
+    results: Option<DispersionResults>,
+    range: RangeEditor,
+    /// Landings outside the field limits, if any limits are set
+    range_check: Option<RangeCheck>,
+    landing_plot: LandingPlot,
+
+    scroll: scrollable::State,
+    runs_input: text_input::State,
+    seed_input: text_input::State,
+    control_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum DispersionMessage {
+    RunsChanged(String),
+    SeedChanged(String),
+    KindChanged(usize, DistributionKind),
+    ValueChanged(usize, String),
+    UnitChanged(usize, Unit),
+    Range(RangeMessage),
+    Start,
+    Stop,
+}
+
+/// The distribution of one of the perturbed parameters, being edited
+struct Field {
+    parameter: Parameter,
+    kind: DistributionKind,
+    text: String,
+    unit: Unit,
+    error: Option<&'static str>,
+    kind_picker: pick_list::State<DistributionKind>,
+    input: text_input::State,
+    unit_picker: pick_list::State<Unit>,
+}
+
+/// The shape of a [`Distribution`], without its width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionKind {
+    Fixed,
+    Normal,
+    Uniform,
+}
+
+impl DistributionKind {
+    const ALL: [DistributionKind; 3] = [
+        DistributionKind::Fixed,
+        DistributionKind::Normal,
+        DistributionKind::Uniform,
+    ];
+
+    fn of(distribution: Distribution) -> Self {
+        match distribution {
+            Distribution::Fixed => DistributionKind::Fixed,
+            Distribution::Normal(_) => DistributionKind::Normal,
+            Distribution::Uniform(_) => DistributionKind::Uniform,
+        }
+    }
+
+    fn with_width(self, width: f64) -> Distribution {
+        match self {
+            DistributionKind::Fixed => Distribution::Fixed,
+            DistributionKind::Normal => Distribution::Normal(width),
+            DistributionKind::Uniform => Distribution::Uniform(width),
+        }
+    }
+}
+
+impl Display for DistributionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DistributionKind::Fixed => "Fixed",
+            DistributionKind::Normal => "Normal (σ)",
+            DistributionKind::Uniform => "Uniform (±)",
+        })
+    }
+}
+
+/// Width of a distribution, which is zero for fixed values
+fn width(distribution: Distribution) -> f64 {
+    match distribution {
+        Distribution::Fixed => 0.0,
+        Distribution::Normal(width) | Distribution::Uniform(width) => width,
+    }
+}
+
+impl DispersionScreen {
+    pub fn new(settings: DispersionSettings, limits: FieldLimits) -> Self {
+        let fields = Parameter::ALL
+            .iter()
+            .map(|&parameter| {
+                let distribution = parameter.get(&settings);
+                let unit = parameter.quantity().default_unit();
+
+                Field {
+                    parameter,
+                    kind: DistributionKind::of(distribution),
+                    text: format_value(unit.in_unit(width(distribution))),
+                    unit,
+                    error: None,
+                    kind_picker: pick_list::State::default(),
+                    input: text_input::State::new(),
+                    unit_picker: pick_list::State::default(),
+                }
+            })
+            .collect();
+
+        Self {
+            settings,
+            runs: settings.runs.to_string(),
+            seed: settings.seed.to_string(),
+            fields,
+            progress: None,
+            results: None,
+            range: RangeEditor::new(limits),
+            range_check: None,
+            landing_plot: LandingPlot::new(),
+
+            scroll: scrollable::State::new(),
+            runs_input: text_input::State::new(),
+            seed_input: text_input::State::new(),
+            control_button: button::State::new(),
+        }
+    }
+
+    pub fn settings(&self) -> DispersionSettings {
+        self.settings
+    }
+
+    pub fn field_limits(&self) -> &FieldLimits {
+        self.range.limits()
+    }
+
+    pub fn results(&self) -> Option<&DispersionResults> {
+        self.results.as_ref()
+    }
+
+    pub fn set_progress(&mut self, completed: usize, total: usize) {
+        self.progress = Some((completed, total));
+    }
+
+    pub fn set_results(&mut self, results: DispersionResults) {
+        self.results = Some(results);
+        self.check_range();
+    }
+
+    /// Check the landings against the field limits and redraw the map
+    fn check_range(&mut self) {
+        let limits = self.range.limits();
+
+        self.range_check = match &self.results {
+            Some(results) if limits.is_limited() => Some(RangeCheck::new(results, limits)),
+            _ => None,
+        };
+        self.landing_plot
+            .set(self.results.as_ref(), self.range_check.as_ref(), limits);
+    }
+
+    /// Whether every value entered can be used
+    fn is_valid(&self) -> bool {
+        self.runs.trim().parse::<usize>().is_ok_and(|runs| runs > 0)
+            && self.seed.trim().parse::<u64>().is_ok()
+            && self.fields.iter().all(|field| field.error.is_none())
+    }
+
+    /// Handle a message, returning the control to send to the simulation
+    /// thread when an analysis is started or stopped
+    pub fn update(&mut self, message: DispersionMessage) -> Option<SimulationControl> {
+        match message {
+            DispersionMessage::RunsChanged(runs) => {
+                if let Ok(value) = runs.trim().parse() {
+                    self.settings.runs = value;
+                }
+
+                self.runs = runs;
+            }
+            DispersionMessage::SeedChanged(seed) => {
+                if let Ok(value) = seed.trim().parse() {
+                    self.settings.seed = value;
+                }
+
+                self.seed = seed;
+            }
+            DispersionMessage::KindChanged(index, kind) => {
+                let field = self.fields.get_mut(index)?;
+                let width = width(field.parameter.get(&self.settings));
+
+                field.kind = kind;
+                field
+                    .parameter
+                    .set(&mut self.settings, kind.with_width(width));
+            }
+            DispersionMessage::ValueChanged(index, text) => {
+                let field = self.fields.get_mut(index)?;
+
+                field.text = text;
+
+                match field.text.trim().parse::<f64>() {
+                    Ok(value) if !value.is_finite() => field.error = Some("not a number"),
+                    Ok(value) if value < 0.0 => field.error = Some("must not be negative"),
+                    Ok(value) => {
+                        field.error = None;
+                        field.parameter.set(
+                            &mut self.settings,
+                            field.kind.with_width(field.unit.to_si(value)),
+                        );
+                    }
+                    Err(_) => field.error = Some("not a number"),
+                }
+            }
+            DispersionMessage::UnitChanged(index, unit) => {
+                let field = self.fields.get_mut(index)?;
+                let width = width(field.parameter.get(&self.settings));
+
+                field.unit = unit;
+                field.text = format_value(unit.in_unit(width));
+                field.error = None;
+            }
+            DispersionMessage::Range(message) => {
+                if self.range.update(message).is_some() {
+                    self.check_range();
+                }
+            }
+            DispersionMessage::Start => {
+                if self.is_valid() {
+                    self.progress = None;
+                    self.results = None;
+                    self.check_range();
+
+                    return Some(SimulationControl::StartDispersion(self.settings));
+                }
+            }
+            DispersionMessage::Stop => return Some(SimulationControl::Stop),
+        }
+
+        None
+    }
+
+    pub fn view(&mut self, status: Option<SimulationStatus>) -> Element<'_, DispersionMessage> {
+        let valid = self.is_valid();
+
+        let mut settings = Column::new()
+            .spacing(5)
+            .push(Text::new("Dispersion Analysis").size(24))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(Text::new("Flights").width(Length::Units(160)))
+                    .push(
+                        TextInput::new(
+                            &mut self.runs_input,
+                            "",
+                            &self.runs,
+                            DispersionMessage::RunsChanged,
+                        )
+                        .padding(4)
+                        .width(Length::Units(100)),
+                    ),
+            )
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(Text::new("Random seed").width(Length::Units(160)))
+                    .push(
+                        TextInput::new(
+                            &mut self.seed_input,
+                            "",
+                            &self.seed,
+                            DispersionMessage::SeedChanged,
+                        )
+                        .padding(4)
+                        .width(Length::Units(100)),
+                    ),
+            );
+
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            let mut row = Row::new()
+                .spacing(5)
+                .align_items(Align::Center)
+                .push(Text::new(field.parameter.label()).width(Length::Units(160)))
+                .push(PickList::new(
+                    &mut field.kind_picker,
+                    &DistributionKind::ALL[..],
+                    Some(field.kind),
+                    move |kind| DispersionMessage::KindChanged(index, kind),
+                ));
+
+            if field.kind != DistributionKind::Fixed {
+                row = row
+                    .push(
+                        TextInput::new(&mut field.input, "", &field.text, move |text| {
+                            DispersionMessage::ValueChanged(index, text)
+                        })
+                        .padding(4)
+                        .width(Length::Units(80)),
+                    )
+                    .push(PickList::new(
+                        &mut field.unit_picker,
+                        field.parameter.quantity().units(),
+                        Some(field.unit),
+                        move |unit| DispersionMessage::UnitChanged(index, unit),
+                    ));
+            }
+
+            if let Some(error) = field.error {
+                row = row.push(Text::new(error).color(ERROR_COLOR));
+            }
+
+            settings = settings.push(row);
+        }
+
+        let running = matches!(status, Some(SimulationStatus::Running));
+        let control = if running {
+            Button::new(&mut self.control_button, Text::new("Stop Analysis"))
+                .on_press(DispersionMessage::Stop)
+        } else {
+            let button = Button::new(&mut self.control_button, Text::new("Start Analysis"));
+
+            match status {
+                Some(_) if valid => button.on_press(DispersionMessage::Start),
+                _ => button,
+            }
+        };
+
+        settings = settings.push(control);
+
+        if let Some((completed, total)) = self.progress {
+            settings = settings.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        ProgressBar::new(0.0..=total.max(1) as f32, completed as f32)
+                            .width(Length::Units(300)),
+                    )
+                    .push(Text::new(format!("{} / {} flights", completed, total)).size(16)),
+            );
+        }
+
+        settings = settings.push(self.range.view().map(DispersionMessage::Range));
+
+        let results: Element<'_, DispersionMessage> = match &self.results {
+            Some(results) => {
+                let mut column = results_view(results);
+
+                if let Some(range_check) = &self.range_check {
+                    let text = Text::new(format!("Landed out of bounds: {}", range_check)).size(16);
+
+                    column = column.push(if range_check.count() > 0 {
+                        text.color(ERROR_COLOR)
+                    } else {
+                        text
+                    });
+                }
+
+                column
+                    .push(
+                        ChartWidget::new(&mut self.landing_plot)
+                            .width(Length::Units(500))
+                            .height(Length::Units(500)),
+                    )
+                    .into()
+            }
+            None => Text::new("Run an analysis to see how the flight varies").into(),
+        };
+
+        Row::new()
+            .spacing(20)
+            .push(
+                Scrollable::new(&mut self.scroll)
+                    .width(Length::FillPortion(3))
+                    .push(settings),
+            )
+            .push(Column::new().width(Length::FillPortion(2)).push(results))
+            .into()
+    }
+}
+
+/// Statistics of the apogee and landing points of an analysis
+fn results_view(results: &DispersionResults) -> Column<'_, DispersionMessage> {
+    results.rows().into_iter().fold(
+        Column::new()
+            .spacing(4)
+            .push(Text::new("Dispersion").size(24)),
+        |column, (label, value)| {
+            column.push(
+                Row::new()
+                    .push(Text::new(label).size(16).width(Length::Units(160)))
+                    .push(Text::new(value).size(16)),
+            )
+        },
+    )
+}
+
+/// A parameter of [`DispersionSettings`] drawn from a distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parameter {
+    ThrustScale,
+    MassScale,
+    DragScale,
+    WindSpeed,
+    WindDirection,
+    RailAngle,
+    RailHeading,
+    DeployDelay,
+    FinMisalignment,
+}
+
+impl Parameter {
+    const ALL: [Parameter; 9] = [
+        Parameter::ThrustScale,
+        Parameter::MassScale,
+        Parameter::DragScale,
+        Parameter::WindSpeed,
+        Parameter::WindDirection,
+        Parameter::RailAngle,
+        Parameter::RailHeading,
+        Parameter::DeployDelay,
+        Parameter::FinMisalignment,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Parameter::ThrustScale => "Motor thrust",
+            Parameter::MassScale => "Dry mass",
+            Parameter::DragScale => "Drag coefficient",
+            Parameter::WindSpeed => "Wind speed",
+            Parameter::WindDirection => "Wind direction",
+            Parameter::RailAngle => "Rail angle",
+            Parameter::RailHeading => "Rail heading",
+            Parameter::DeployDelay => "Deployment delay",
+            Parameter::FinMisalignment => "Fin misalignment",
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Parameter::ThrustScale | Parameter::MassScale | Parameter::DragScale => {
+                Quantity::Fraction
+            }
+            Parameter::WindSpeed => Quantity::Speed,
+            Parameter::DeployDelay => Quantity::Time,
+            _ => Quantity::Angle,
+        }
+    }
+
+    fn get(self, settings: &DispersionSettings) -> Distribution {
+        match self {
+            Parameter::ThrustScale => settings.thrust_scale,
+            Parameter::MassScale => settings.mass_scale,
+            Parameter::DragScale => settings.drag_scale,
+            Parameter::WindSpeed => settings.wind_speed,
+            Parameter::WindDirection => settings.wind_direction,
+            Parameter::RailAngle => settings.rail_angle,
+            Parameter::RailHeading => settings.rail_heading,
+            Parameter::DeployDelay => settings.deploy_delay,
+            Parameter::FinMisalignment => settings.fin_misalignment,
+        }
+    }
+
+    fn set(self, settings: &mut DispersionSettings, distribution: Distribution) {
+        let target = match self {
+            Parameter::ThrustScale => &mut settings.thrust_scale,
+            Parameter::MassScale => &mut settings.mass_scale,
+            Parameter::DragScale => &mut settings.drag_scale,
+            Parameter::WindSpeed => &mut settings.wind_speed,
+            Parameter::WindDirection => &mut settings.wind_direction,
+            Parameter::RailAngle => &mut settings.rail_angle,
+            Parameter::RailHeading => &mut settings.rail_heading,
+            Parameter::DeployDelay => &mut settings.deploy_delay,
+            Parameter::FinMisalignment => &mut settings.fin_misalignment,
+        };
+
+        *target = distribution;
+    }
+}
@@ -0,0 +1,897 @@
+use std::fmt::{self, Display};
+
+use iced::{
+    button, pick_list, scrollable, text_input, Align, Button, Column, Element, Length, PickList,
+    Row, Scrollable, Space, Text, TextInput,
+};
+
+use crate::{
+    rocket::{
+        Aerodynamics, Component, ComponentKind, ImportLevel, ImportMessage, NoseShape, Rocket,
+    },
+    sim::{Deployment, Motor},
+};
+
+use super::{
+    units::{format_value, Quantity, Unit},
+    ERROR_COLOR, WARNING_COLOR,
+};
+
+/// Screen for building a rocket out of a tree of components
+pub struct Editor {
+    rocket: Rocket,
+    selected: Option<Vec<usize>>,
+    fields: Vec<Field>,
+    /// Notes from converting the design, if it was imported
+    import_log: Vec<ImportMessage>,
+
+    rocket_name: text_input::State,
+    component_name: text_input::State,
+    shape_picker: pick_list::State<NoseShape>,
+    deployment_picker: pick_list::State<DeploymentKind>,
+    tree_buttons: Vec<button::State>,
+    add_buttons: [button::State; ComponentTemplate::ALL.len()],
+    remove_button: button::State,
+    move_up_button: button::State,
+    move_down_button: button::State,
+    apply_button: button::State,
+    tree_scroll: scrollable::State,
+    properties_scroll: scrollable::State,
+    import_scroll: scrollable::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum EditorMessage {
+    Select(Vec<usize>),
+    Add(ComponentTemplate),
+    Remove,
+    MoveUp,
+    MoveDown,
+    RocketNameChanged(String),
+    ComponentNameChanged(String),
+    FieldChanged(usize, String),
+    UnitChanged(usize, Unit),
+    ShapeChanged(NoseShape),
+    DeploymentChanged(DeploymentKind),
+    Apply,
+}
+
+/// A numeric property of the selected component being edited as text
+struct Field {
+    parameter: Parameter,
+    text: String,
+    unit: Unit,
+    error: Option<&'static str>,
+    input: text_input::State,
+    unit_picker: pick_list::State<Unit>,
+}
+
+impl Field {
+    fn new(parameter: Parameter, value: f64) -> Self {
+        let unit = parameter.quantity().default_unit();
+
+        Self {
+            parameter,
+            text: format_value(unit.in_unit(value)),
+            unit,
+            error: None,
+            input: text_input::State::new(),
+            unit_picker: pick_list::State::default(),
+        }
+    }
+}
+
+impl Editor {
+    pub fn new(rocket: Rocket) -> Self {
+        Self {
+            rocket,
+            selected: None,
+            fields: Vec::new(),
+            import_log: Vec::new(),
+
+            rocket_name: text_input::State::new(),
+            component_name: text_input::State::new(),
+            shape_picker: pick_list::State::default(),
+            deployment_picker: pick_list::State::default(),
+            tree_buttons: Vec::new(),
+            add_buttons: Default::default(),
+            remove_button: button::State::new(),
+            move_up_button: button::State::new(),
+            move_down_button: button::State::new(),
+            apply_button: button::State::new(),
+            tree_scroll: scrollable::State::new(),
+            properties_scroll: scrollable::State::new(),
+            import_scroll: scrollable::State::new(),
+        }
+    }
+
+    /// Show how an imported design was converted
+    pub fn set_import_log(&mut self, log: Vec<ImportMessage>) {
+        self.import_log = log;
+    }
+
+    pub fn rocket(&self) -> &Rocket {
+        &self.rocket
+    }
+
+    fn selected_component(&self) -> Option<&Component> {
+        self.rocket.component(self.selected.as_deref()?)
+    }
+
+    fn selected_component_mut(&mut self) -> Option<&mut Component> {
+        let path = self.selected.as_deref()?;
+
+        self.rocket.component_mut(path)
+    }
+
+    fn select(&mut self, path: Option<Vec<usize>>) {
+        self.selected = path;
+        self.load_fields();
+    }
+
+    /// Rebuild the field editors for the selected component
+    fn load_fields(&mut self) {
+        let is_child = self.selected.as_ref().is_some_and(|path| path.len() > 1);
+
+        self.fields = self
+            .selected_component()
+            .map(|component| {
+                Parameter::of(component, is_child)
+                    .into_iter()
+                    .filter_map(|parameter| Some(Field::new(parameter, parameter.get(component)?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    /// Handle a message, returning the rocket to send to the simulation when
+    /// the user applies a valid design
+    pub fn update(&mut self, message: EditorMessage) -> Option<Rocket> {
+        match message {
+            EditorMessage::Select(path) => self.select(Some(path)),
+            EditorMessage::Add(template) => {
+                let component = template.create(self.rocket.reference_diameter());
+                let selected_top = self
+                    .selected
+                    .as_ref()
+                    .and_then(|path| path.first().copied());
+
+                let path = if component.kind.is_external() {
+                    let index = selected_top
+                        .map(|index| index + 1)
+                        .unwrap_or_else(|| self.rocket.components.len());
+
+                    self.rocket.components.insert(index, component);
+
+                    vec![index]
+                } else {
+                    let parent = selected_top?;
+                    let children = &mut self.rocket.components.get_mut(parent)?.children;
+
+                    children.push(component);
+
+                    vec![parent, children.len() - 1]
+                };
+
+                self.select(Some(path));
+            }
+            EditorMessage::Remove => {
+                let path = self.selected.take()?;
+                let (&index, parent) = path.split_last()?;
+
+                self.rocket.children_mut(parent)?.remove(index);
+                self.select(None);
+            }
+            EditorMessage::MoveUp | EditorMessage::MoveDown => {
+                let mut path = self.selected.clone()?;
+                let (&index, parent) = path.split_last()?;
+                let siblings = self.rocket.children_mut(parent)?;
+
+                let target = match message {
+                    EditorMessage::MoveUp => index.checked_sub(1)?,
+                    _ => Some(index + 1).filter(|&target| target < siblings.len())?,
+                };
+
+                siblings.swap(index, target);
+                *path.last_mut()? = target;
+                self.select(Some(path));
+            }
+            EditorMessage::RocketNameChanged(name) => self.rocket.name = name,
+            EditorMessage::ComponentNameChanged(name) => self.selected_component_mut()?.name = name,
+            EditorMessage::FieldChanged(index, text) => {
+                let field = self.fields.get_mut(index)?;
+                let parameter = field.parameter;
+                let unit = field.unit;
+
+                field.text = text;
+
+                let value = match field.text.trim().parse::<f64>() {
+                    Ok(value) if !value.is_finite() => Err("not a number"),
+                    Ok(value) if value < 0.0 && !parameter.signed() => Err("must not be negative"),
+                    Ok(value)
+                        if parameter.quantity() == Quantity::Count && value.fract() != 0.0 =>
+                    {
+                        Err("must be a whole number")
+                    }
+                    Ok(value) => Ok(unit.to_si(value)),
+                    Err(_) => Err("not a number"),
+                };
+
+                match value {
+                    Ok(value) => {
+                        field.error = None;
+                        parameter.set(self.selected_component_mut()?, value);
+                    }
+                    Err(error) => field.error = Some(error),
+                }
+            }
+            EditorMessage::UnitChanged(index, unit) => {
+                let parameter = self.fields.get(index)?.parameter;
+                let value = parameter.get(self.selected_component()?)?;
+                let field = &mut self.fields[index];
+
+                field.unit = unit;
+                field.text = format_value(unit.in_unit(value));
+                field.error = None;
+            }
+            EditorMessage::ShapeChanged(new_shape) => {
+                if let ComponentKind::NoseCone { shape, .. } =
+                    &mut self.selected_component_mut()?.kind
+                {
+                    *shape = new_shape;
+                }
+            }
+            EditorMessage::DeploymentChanged(kind) => {
+                if let ComponentKind::Parachute { deployment, .. } =
+                    &mut self.selected_component_mut()?.kind
+                {
+                    *deployment = match kind {
+                        DeploymentKind::Apogee => Deployment::Apogee { delay: 0.0 },
+                        DeploymentKind::Altitude => Deployment::Altitude(150.0),
+                    };
+                }
+
+                self.load_fields();
+            }
+            EditorMessage::Apply => {
+                if self.rocket.validate().is_empty()
+                    && self.fields.iter().all(|f| f.error.is_none())
+                {
+                    return Some(self.rocket.clone());
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn view(&mut self, motor: &Motor) -> Element<'_, EditorMessage> {
+        let rows = tree_rows(&self.rocket);
+        let validation = self.rocket.validate();
+        let has_field_errors = self.fields.iter().any(|field| field.error.is_some());
+        let is_valid = !has_field_errors && validation.is_empty();
+
+        // Summary of the design as it currently stands
+        let mass = self.rocket.mass_properties();
+        let loaded_mass = self.rocket.loaded_mass_properties(Some(motor));
+        let aerodynamics = Aerodynamics::new(&self.rocket);
+        let reference_diameter = self.rocket.reference_diameter();
+        let totals = Row::new()
+            .spacing(20)
+            .push(Text::new(format!("Mass: {:.1} g", mass.mass * 1000.0)))
+            .push(Text::new(format!("CG: {:.1} mm", mass.cg * 1000.0)))
+            .push(Text::new(format!("CP: {:.1} mm", aerodynamics.cp * 1000.0)))
+            .push(Text::new(format!(
+                "Length: {:.1} mm",
+                self.rocket.length() * 1000.0
+            )))
+            .push(Text::new(if reference_diameter > 0.0 {
+                format!(
+                    "Stability: {:.2} cal with the {}",
+                    (aerodynamics.cp - loaded_mass.cg) / reference_diameter,
+                    motor.designation
+                )
+            } else {
+                "Stability: N/A".into()
+            }));
+
+        self.tree_buttons
+            .resize_with(rows.len(), button::State::new);
+
+        let selected = self.selected.clone();
+        let tree = rows.into_iter().zip(self.tree_buttons.iter_mut()).fold(
+            Scrollable::new(&mut self.tree_scroll)
+                .height(Length::Fill)
+                .spacing(2),
+            |tree, ((path, depth, label), state)| {
+                let label = if selected.as_ref() == Some(&path) {
+                    format!("> {}", label)
+                } else {
+                    label
+                };
+
+                tree.push(
+                    Row::new()
+                        .push(Space::with_width(Length::Units(depth as u16 * 16)))
+                        .push(
+                            Button::new(state, Text::new(label).size(16))
+                                .width(Length::Fill)
+                                .on_press(EditorMessage::Select(path)),
+                        ),
+                )
+            },
+        );
+
+        let can_add_internal = self.selected.is_some();
+        let add_buttons = ComponentTemplate::ALL
+            .iter()
+            .zip(self.add_buttons.iter_mut())
+            .fold(
+                Column::new().spacing(2).push(Text::new("Add component")),
+                |column, (&template, state)| {
+                    let button = Button::new(state, Text::new(template.to_string()).size(16))
+                        .width(Length::Fill);
+
+                    column.push(if template.is_external() || can_add_internal {
+                        button.on_press(EditorMessage::Add(template))
+                    } else {
+                        button
+                    })
+                },
+            );
+
+        let has_selection = self.selected.is_some();
+        let enable = |button, message| enable_if(button, has_selection, message);
+        let arrange = Row::new()
+            .spacing(2)
+            .push(enable(
+                Button::new(&mut self.move_up_button, Text::new("Up").size(16)),
+                EditorMessage::MoveUp,
+            ))
+            .push(enable(
+                Button::new(&mut self.move_down_button, Text::new("Down").size(16)),
+                EditorMessage::MoveDown,
+            ))
+            .push(enable(
+                Button::new(&mut self.remove_button, Text::new("Remove").size(16)),
+                EditorMessage::Remove,
+            ));
+
+        let sidebar = Column::new()
+            .width(Length::Units(260))
+            .spacing(5)
+            .push(
+                TextInput::new(
+                    &mut self.rocket_name,
+                    "Rocket name",
+                    &self.rocket.name,
+                    EditorMessage::RocketNameChanged,
+                )
+                .padding(4),
+            )
+            .push(tree)
+            .push(arrange)
+            .push(add_buttons);
+
+        let properties = match self
+            .rocket
+            .component(self.selected.as_deref().unwrap_or(&[]))
+        {
+            Some(component) => {
+                let mut properties = Scrollable::new(&mut self.properties_scroll)
+                    .spacing(5)
+                    .push(Text::new(component.kind.type_name()).size(24))
+                    .push(property_row(
+                        "Name",
+                        TextInput::new(
+                            &mut self.component_name,
+                            "Name",
+                            &component.name,
+                            EditorMessage::ComponentNameChanged,
+                        )
+                        .padding(4)
+                        .into(),
+                    ));
+
+                match component.kind {
+                    ComponentKind::NoseCone { shape, .. } => {
+                        properties = properties.push(property_row(
+                            "Shape",
+                            PickList::new(
+                                &mut self.shape_picker,
+                                &NoseShape::ALL[..],
+                                Some(shape),
+                                EditorMessage::ShapeChanged,
+                            )
+                            .into(),
+                        ));
+                    }
+                    ComponentKind::Parachute { deployment, .. } => {
+                        properties = properties.push(property_row(
+                            "Deploy at",
+                            PickList::new(
+                                &mut self.deployment_picker,
+                                &DeploymentKind::ALL[..],
+                                Some(DeploymentKind::of(&deployment)),
+                                EditorMessage::DeploymentChanged,
+                            )
+                            .into(),
+                        ));
+                    }
+                    _ => {}
+                }
+
+                self.fields
+                    .iter_mut()
+                    .enumerate()
+                    .fold(properties, |properties, (index, field)| {
+                        let mut row = Row::new()
+                            .spacing(5)
+                            .align_items(Align::Center)
+                            .push(
+                                TextInput::new(&mut field.input, "", &field.text, move |text| {
+                                    EditorMessage::FieldChanged(index, text)
+                                })
+                                .padding(4)
+                                .width(Length::Units(100)),
+                            )
+                            .push(PickList::new(
+                                &mut field.unit_picker,
+                                field.parameter.quantity().units(),
+                                Some(field.unit),
+                                move |unit| EditorMessage::UnitChanged(index, unit),
+                            ));
+
+                        if let Some(error) = field.error {
+                            row = row.push(Text::new(error).color(ERROR_COLOR));
+                        }
+
+                        properties.push(property_row(field.parameter.label(), row.into()))
+                    })
+                    .into()
+            }
+            None => Element::from(Text::new("Select a component to edit its properties")),
+        };
+
+        let validation = validation
+            .iter()
+            .chain(&self.rocket.check_motor(motor))
+            .fold(Column::new().spacing(2), |column, error| {
+                column.push(Text::new(error.to_string()).size(16).color(ERROR_COLOR))
+            });
+
+        let import_log = if self.import_log.is_empty() {
+            None
+        } else {
+            let messages = self.import_log.iter().fold(
+                Column::new().spacing(2).push(Text::new("Import log")),
+                |column, message| {
+                    let text = Text::new(message.to_string()).size(16);
+
+                    column.push(match message.level {
+                        ImportLevel::Info => text,
+                        ImportLevel::Warning => text.color(WARNING_COLOR),
+                    })
+                },
+            );
+
+            Some(
+                Scrollable::new(&mut self.import_scroll)
+                    .height(Length::Units(120))
+                    .push(messages),
+            )
+        };
+
+        let mut apply = Button::new(
+            &mut self.apply_button,
+            Text::new("Use Design for Simulation"),
+        );
+        if is_valid {
+            apply = apply.on_press(EditorMessage::Apply);
+        }
+
+        let column = Column::new()
+            .spacing(10)
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .height(Length::Fill)
+                    .push(sidebar)
+                    .push(Column::new().width(Length::Fill).push(properties)),
+            )
+            .push(totals)
+            .push(validation);
+
+        let column = match import_log {
+            Some(import_log) => column.push(import_log),
+            None => column,
+        };
+
+        column.push(apply).into()
+    }
+}
+
+fn property_row<'a>(label: &str, input: Element<'a, EditorMessage>) -> Element<'a, EditorMessage> {
+    Row::new()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(Text::new(label).width(Length::Units(140)))
+        .push(input)
+        .into()
+}
+
+/// Every component with its path, depth and label, in display order
+fn tree_rows(rocket: &Rocket) -> Vec<(Vec<usize>, usize, String)> {
+    fn visit(path: Vec<usize>, component: &Component, rows: &mut Vec<(Vec<usize>, usize, String)>) {
+        rows.push((
+            path.clone(),
+            path.len() - 1,
+            format!("{} ({})", component.name, component.kind.type_name()),
+        ));
+
+        for (index, child) in component.children.iter().enumerate() {
+            let mut child_path = path.clone();
+            child_path.push(index);
+
+            visit(child_path, child, rows);
+        }
+    }
+
+    let mut rows = Vec::new();
+
+    for (index, component) in rocket.components.iter().enumerate() {
+        visit(vec![index], component, &mut rows);
+    }
+
+    rows
+}
+
+/// Kinds of component that can be added to a design, with sensible defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentTemplate {
+    NoseCone,
+    BodyTube,
+    Transition,
+    FinSet,
+    MotorMount,
+    MassItem,
+    Parachute,
+}
+
+impl ComponentTemplate {
+    const ALL: [ComponentTemplate; 7] = [
+        ComponentTemplate::NoseCone,
+        ComponentTemplate::BodyTube,
+        ComponentTemplate::Transition,
+        ComponentTemplate::FinSet,
+        ComponentTemplate::MotorMount,
+        ComponentTemplate::MassItem,
+        ComponentTemplate::Parachute,
+    ];
+
+    fn is_external(self) -> bool {
+        matches!(
+            self,
+            ComponentTemplate::NoseCone
+                | ComponentTemplate::BodyTube
+                | ComponentTemplate::Transition
+        )
+    }
+
+    /// Create a new component sized to fit a rocket of a given diameter
+    fn create(self, diameter: f64) -> Component {
+        let diameter = if diameter > 0.0 { diameter } else { 0.066 };
+
+        let kind = match self {
+            ComponentTemplate::NoseCone => ComponentKind::NoseCone {
+                shape: NoseShape::Ogive,
+                length: diameter * 4.0,
+                diameter,
+                thickness: 0.002,
+                density: 1050.0,
+            },
+            ComponentTemplate::BodyTube => ComponentKind::BodyTube {
+                length: diameter * 8.0,
+                diameter,
+                thickness: 0.0015,
+                density: 680.0,
+            },
+            ComponentTemplate::Transition => ComponentKind::Transition {
+                length: diameter,
+                fore_diameter: diameter,
+                aft_diameter: diameter * 0.75,
+                thickness: 0.002,
+                density: 1050.0,
+            },
+            ComponentTemplate::FinSet => ComponentKind::FinSet {
+                count: 3,
+                root_chord: diameter * 2.0,
+                tip_chord: diameter,
+                span: diameter,
+                sweep: diameter,
+                thickness: 0.003,
+                cant: 0.0,
+                density: 630.0,
+            },
+            ComponentTemplate::MotorMount => ComponentKind::MotorMount {
+                length: 0.2,
+                diameter: 0.031,
+                thickness: 0.001,
+                density: 680.0,
+            },
+            ComponentTemplate::MassItem => ComponentKind::MassItem {
+                mass: 0.05,
+                length: 0.02,
+            },
+            ComponentTemplate::Parachute => ComponentKind::Parachute {
+                diameter: 0.6,
+                drag_coefficient: 0.8,
+                mass: 0.04,
+                deployment: Deployment::Apogee { delay: 0.0 },
+            },
+        };
+
+        Component::new(kind.type_name(), kind)
+    }
+}
+
+impl Display for ComponentTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.create(0.0).kind.type_name())
+    }
+}
+
+/// The variants of [`Deployment`], for selection in a pick list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeploymentKind {
+    Apogee,
+    Altitude,
+}
+
+impl DeploymentKind {
+    const ALL: [DeploymentKind; 2] = [DeploymentKind::Apogee, DeploymentKind::Altitude];
+
+    fn of(deployment: &Deployment) -> Self {
+        match deployment {
+            Deployment::Apogee { .. } => DeploymentKind::Apogee,
+            Deployment::Altitude(_) => DeploymentKind::Altitude,
+        }
+    }
+}
+
+impl Display for DeploymentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DeploymentKind::Apogee => "Apogee",
+            DeploymentKind::Altitude => "Altitude",
+        })
+    }
+}
+
+/// A numeric property of a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parameter {
+    Position,
+    Length,
+    Diameter,
+    ForeDiameter,
+    AftDiameter,
+    Thickness,
+    Density,
+    Count,
+    RootChord,
+    TipChord,
+    Span,
+    Sweep,
+    Cant,
+    Mass,
+    DragCoefficient,
+    DeployDelay,
+    DeployAltitude,
+}
+
+impl Parameter {
+    /// Parameters of a component, including its position if it sits inside another
+    fn of(component: &Component, is_child: bool) -> Vec<Parameter> {
+        use Parameter::*;
+
+        let mut parameters = if is_child { vec![Position] } else { Vec::new() };
+
+        parameters.extend_from_slice(match component.kind {
+            ComponentKind::NoseCone { .. } => &[Length, Diameter, Thickness, Density][..],
+            ComponentKind::BodyTube { .. } | ComponentKind::MotorMount { .. } => {
+                &[Length, Diameter, Thickness, Density]
+            }
+            ComponentKind::Transition { .. } => {
+                &[Length, ForeDiameter, AftDiameter, Thickness, Density]
+            }
+            ComponentKind::FinSet { .. } => &[
+                Count, RootChord, TipChord, Span, Sweep, Thickness, Cant, Density,
+            ],
+            ComponentKind::MassItem { .. } => &[Mass, Length],
+            ComponentKind::Parachute {
+                deployment: Deployment::Apogee { .. },
+                ..
+            } => &[Diameter, DragCoefficient, Mass, DeployDelay],
+            ComponentKind::Parachute {
+                deployment: Deployment::Altitude(_),
+                ..
+            } => &[Diameter, DragCoefficient, Mass, DeployAltitude],
+        });
+
+        parameters
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Parameter::Position => "Position",
+            Parameter::Length => "Length",
+            Parameter::Diameter => "Diameter",
+            Parameter::ForeDiameter => "Fore diameter",
+            Parameter::AftDiameter => "Aft diameter",
+            Parameter::Thickness => "Thickness",
+            Parameter::Density => "Density",
+            Parameter::Count => "Number of fins",
+            Parameter::RootChord => "Root chord",
+            Parameter::TipChord => "Tip chord",
+            Parameter::Span => "Span",
+            Parameter::Sweep => "Sweep",
+            Parameter::Cant => "Cant",
+            Parameter::Mass => "Mass",
+            Parameter::DragCoefficient => "Drag coefficient",
+            Parameter::DeployDelay => "Delay after apogee",
+            Parameter::DeployAltitude => "Altitude",
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Parameter::Density => Quantity::Density,
+            Parameter::Count => Quantity::Count,
+            Parameter::Cant => Quantity::Angle,
+            Parameter::Mass => Quantity::Mass,
+            Parameter::DragCoefficient => Quantity::Dimensionless,
+            Parameter::DeployDelay => Quantity::Time,
+            _ => Quantity::Length,
+        }
+    }
+
+    /// Whether negative values are meaningful
+    fn signed(self) -> bool {
+        matches!(self, Parameter::Sweep | Parameter::Cant)
+    }
+
+    fn get(self, component: &Component) -> Option<f64> {
+        use ComponentKind::*;
+
+        Some(match (self, &component.kind) {
+            (Parameter::Position, _) => component.position,
+            (Parameter::Length, NoseCone { length, .. })
+            | (Parameter::Length, BodyTube { length, .. })
+            | (Parameter::Length, Transition { length, .. })
+            | (Parameter::Length, MotorMount { length, .. })
+            | (Parameter::Length, MassItem { length, .. }) => *length,
+            (Parameter::Diameter, NoseCone { diameter, .. })
+            | (Parameter::Diameter, BodyTube { diameter, .. })
+            | (Parameter::Diameter, MotorMount { diameter, .. })
+            | (Parameter::Diameter, Parachute { diameter, .. }) => *diameter,
+            (Parameter::ForeDiameter, Transition { fore_diameter, .. }) => *fore_diameter,
+            (Parameter::AftDiameter, Transition { aft_diameter, .. }) => *aft_diameter,
+            (Parameter::Thickness, NoseCone { thickness, .. })
+            | (Parameter::Thickness, BodyTube { thickness, .. })
+            | (Parameter::Thickness, Transition { thickness, .. })
+            | (Parameter::Thickness, MotorMount { thickness, .. })
+            | (Parameter::Thickness, FinSet { thickness, .. }) => *thickness,
+            (Parameter::Density, NoseCone { density, .. })
+            | (Parameter::Density, BodyTube { density, .. })
+            | (Parameter::Density, Transition { density, .. })
+            | (Parameter::Density, MotorMount { density, .. })
+            | (Parameter::Density, FinSet { density, .. }) => *density,
+            (Parameter::Count, FinSet { count, .. }) => *count as f64,
+            (Parameter::RootChord, FinSet { root_chord, .. }) => *root_chord,
+            (Parameter::TipChord, FinSet { tip_chord, .. }) => *tip_chord,
+            (Parameter::Span, FinSet { span, .. }) => *span,
+            (Parameter::Sweep, FinSet { sweep, .. }) => *sweep,
+            (Parameter::Cant, FinSet { cant, .. }) => *cant,
+            (Parameter::Mass, MassItem { mass, .. })
+            | (Parameter::Mass, Parachute { mass, .. }) => *mass,
+            (
+                Parameter::DragCoefficient,
+                Parachute {
+                    drag_coefficient, ..
+                },
+            ) => *drag_coefficient,
+            (
+                Parameter::DeployDelay,
+                Parachute {
+                    deployment: Deployment::Apogee { delay },
+                    ..
+                },
+            ) => *delay,
+            (
+                Parameter::DeployAltitude,
+                Parachute {
+                    deployment: Deployment::Altitude(altitude),
+                    ..
+                },
+            ) => *altitude,
+            _ => return None,
+        })
+    }
+
+    fn set(self, component: &mut Component, value: f64) {
+        use ComponentKind::*;
+
+        let target = match (self, &mut component.kind) {
+            (Parameter::Position, _) => &mut component.position,
+            (Parameter::Length, NoseCone { length, .. })
+            | (Parameter::Length, BodyTube { length, .. })
+            | (Parameter::Length, Transition { length, .. })
+            | (Parameter::Length, MotorMount { length, .. })
+            | (Parameter::Length, MassItem { length, .. }) => length,
+            (Parameter::Diameter, NoseCone { diameter, .. })
+            | (Parameter::Diameter, BodyTube { diameter, .. })
+            | (Parameter::Diameter, MotorMount { diameter, .. })
+            | (Parameter::Diameter, Parachute { diameter, .. }) => diameter,
+            (Parameter::ForeDiameter, Transition { fore_diameter, .. }) => fore_diameter,
+            (Parameter::AftDiameter, Transition { aft_diameter, .. }) => aft_diameter,
+            (Parameter::Thickness, NoseCone { thickness, .. })
+            | (Parameter::Thickness, BodyTube { thickness, .. })
+            | (Parameter::Thickness, Transition { thickness, .. })
+            | (Parameter::Thickness, MotorMount { thickness, .. })
+            | (Parameter::Thickness, FinSet { thickness, .. }) => thickness,
+            (Parameter::Density, NoseCone { density, .. })
+            | (Parameter::Density, BodyTube { density, .. })
+            | (Parameter::Density, Transition { density, .. })
+            | (Parameter::Density, MotorMount { density, .. })
+            | (Parameter::Density, FinSet { density, .. }) => density,
+            (Parameter::Count, FinSet { count, .. }) => {
+                *count = value as u32;
+                return;
+            }
+            (Parameter::RootChord, FinSet { root_chord, .. }) => root_chord,
+            (Parameter::TipChord, FinSet { tip_chord, .. }) => tip_chord,
+            (Parameter::Span, FinSet { span, .. }) => span,
+            (Parameter::Sweep, FinSet { sweep, .. }) => sweep,
+            (Parameter::Cant, FinSet { cant, .. }) => cant,
+            (Parameter::Mass, MassItem { mass, .. })
+            | (Parameter::Mass, Parachute { mass, .. }) => mass,
+            (
+                Parameter::DragCoefficient,
+                Parachute {
+                    drag_coefficient, ..
+                },
+            ) => drag_coefficient,
+            (
+                Parameter::DeployDelay,
+                Parachute {
+                    deployment: Deployment::Apogee { delay },
+                    ..
+                },
+            ) => delay,
+            (
+                Parameter::DeployAltitude,
+                Parachute {
+                    deployment: Deployment::Altitude(altitude),
+                    ..
+                },
+            ) => altitude,
+            _ => return,
+        };
+
+        *target = value;
+    }
+}
+
+/// Make a button pressable only when a condition holds
+fn enable_if(
+    button: Button<'_, EditorMessage>,
+    enabled: bool,
+    message: EditorMessage,
+) -> Button<'_, EditorMessage> {
+    if enabled {
+        button.on_press(message)
+    } else {
+        button
+    }
+}
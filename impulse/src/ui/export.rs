@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+
+use cgmath::Vector3;
+use iced::{
+    button, pick_list, text_input, Align, Button, Checkbox, Column, Command, Element, Length,
+    PickList, Row, Text, TextInput,
+};
+use rfd::AsyncFileDialog;
+use tracing::{info, warn};
+
+use crate::sim::{self, ExportFormat, ExportOptions, FlightRecord, Sampling};
+
+use super::ERROR_COLOR;
+
+/// Controls for writing the trajectory of the last flight to a file
+pub struct ExportPanel {
+    format: ExportFormat,
+    /// Whether each of [`sim::Column::ALL`] is included
+    columns: [bool; sim::Column::ALL.len()],
+    every_step: bool,
+    rate: String,
+    status: Option<Result<String, String>>,
+
+    format_picker: pick_list::State<ExportFormat>,
+    rate_input: text_input::State,
+    export_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExportMessage {
+    FormatChanged(ExportFormat),
+    ColumnToggled(usize, bool),
+    EveryStepToggled(bool),
+    RateChanged(String),
+    Export,
+    PathPicked(Option<PathBuf>),
+}
+
+impl ExportPanel {
+    pub fn new() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            columns: [true; sim::Column::ALL.len()],
+            every_step: true,
+            rate: "10".into(),
+            status: None,
+
+            format_picker: pick_list::State::default(),
+            rate_input: text_input::State::new(),
+            export_button: button::State::new(),
+        }
+    }
+
+    /// Options as currently chosen, or why they cannot be used
+    fn options(&self) -> Result<ExportOptions, String> {
+        let sampling = if self.every_step {
+            Sampling::EveryStep
+        } else {
+            match self.rate.trim().parse::<f64>() {
+                Ok(rate) if rate > 0.0 => Sampling::FixedRate(rate),
+                _ => return Err("the sample rate must be a positive number".into()),
+            }
+        };
+
+        let columns: Vec<_> = sim::Column::ALL
+            .iter()
+            .zip(&self.columns)
+            .filter(|(_, &included)| included)
+            .map(|(&column, _)| column)
+            .collect();
+
+        if columns.is_empty() && !self.format.is_map() {
+            return Err("select at least one column".into());
+        }
+
+        Ok(ExportOptions {
+            format: self.format,
+            sampling,
+            columns,
+            ..ExportOptions::default()
+        })
+    }
+
+    pub fn update(
+        &mut self,
+        message: ExportMessage,
+        record: Option<&FlightRecord>,
+        landing_points: &[Vector3<f64>],
+    ) -> Command<ExportMessage> {
+        match message {
+            ExportMessage::FormatChanged(format) => self.format = format,
+            ExportMessage::ColumnToggled(index, included) => self.columns[index] = included,
+            ExportMessage::EveryStepToggled(every_step) => self.every_step = every_step,
+            ExportMessage::RateChanged(rate) => self.rate = rate,
+            ExportMessage::Export => {
+                let extension = self.format.extension();
+
+                return Command::perform(
+                    AsyncFileDialog::new()
+                        .set_title("Export Trajectory")
+                        .add_filter(&self.format.to_string(), &[extension])
+                        .set_file_name(&format!("trajectory.{}", extension))
+                        .save_file(),
+                    |file| ExportMessage::PathPicked(file.map(|file| file.path().to_path_buf())),
+                );
+            }
+            ExportMessage::PathPicked(None) => {}
+            ExportMessage::PathPicked(Some(mut path)) => {
+                let (record, mut options) = match (record, self.options()) {
+                    (Some(record), Ok(options)) => (record, options),
+                    (None, _) => return Command::none(),
+                    (_, Err(error)) => {
+                        self.status = Some(Err(error));
+
+                        return Command::none();
+                    }
+                };
+
+                options.landing_points = landing_points.to_vec();
+
+                if path.extension().is_none() {
+                    path.set_extension(options.format.extension());
+                }
+
+                self.status = Some(match sim::export_trajectory(&path, record, &options) {
+                    Ok(()) => {
+                        info!(path = %path.display(), "Exported trajectory");
+
+                        Ok(format!("Exported to {}", path.display()))
+                    }
+                    Err(error) => {
+                        warn!(path = %path.display(), %error, "Failed to export trajectory");
+
+                        Err(format!("Failed to export: {}", error))
+                    }
+                });
+            }
+        }
+
+        Command::none()
+    }
+
+    pub fn view(&mut self, has_record: bool) -> Element<'_, ExportMessage> {
+        let valid = self.options();
+
+        // Maps only hold the flight path, so there are no columns to pick
+        let columns = if self.format.is_map() {
+            Column::new()
+        } else {
+            sim::Column::ALL.iter().enumerate().fold(
+                Column::new().spacing(2),
+                |column, (index, name)| {
+                    column.push(
+                        Checkbox::new(self.columns[index], name.to_string(), move |included| {
+                            ExportMessage::ColumnToggled(index, included)
+                        })
+                        .size(16)
+                        .text_size(16),
+                    )
+                },
+            )
+        };
+
+        let rate = TextInput::new(
+            &mut self.rate_input,
+            "Rate",
+            &self.rate,
+            ExportMessage::RateChanged,
+        )
+        .width(Length::Units(60))
+        .padding(2);
+
+        let mut export = Button::new(&mut self.export_button, Text::new("Export..."));
+        if has_record && valid.is_ok() {
+            export = export.on_press(ExportMessage::Export);
+        }
+
+        let status = match (&self.status, &valid) {
+            (_, Err(error)) => Some(Text::new(error.as_str()).color(ERROR_COLOR)),
+            (Some(Err(error)), _) => Some(Text::new(error.as_str()).color(ERROR_COLOR)),
+            (Some(Ok(message)), _) => Some(Text::new(message.as_str())),
+            (None, _) => None,
+        };
+
+        let panel = Column::new()
+            .spacing(5)
+            .width(Length::Units(280))
+            .push(Text::new("Export Trajectory").size(24))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(Text::new("Format").size(16))
+                    .push(PickList::new(
+                        &mut self.format_picker,
+                        &ExportFormat::ALL[..],
+                        Some(self.format),
+                        ExportMessage::FormatChanged,
+                    )),
+            )
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(
+                        Checkbox::new(
+                            self.every_step,
+                            "Every step",
+                            ExportMessage::EveryStepToggled,
+                        )
+                        .size(16)
+                        .text_size(16),
+                    )
+                    .push(rate)
+                    .push(Text::new("Hz").size(16)),
+            )
+            .push(columns)
+            .push(export);
+
+        match status {
+            Some(status) => panel.push(status.size(16)).into(),
+            None => panel.into(),
+        }
+    }
+}
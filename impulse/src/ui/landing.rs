@@ -0,0 +1,220 @@
+use std::f64::consts::PI;
+
+use cgmath::Vector2;
+use plotters::prelude::*;
+use plotters_iced::{Chart, DrawingBackend};
+use tracing::warn;
+
+use crate::sim::{DispersionResults, Ellipse, FieldLimits, RangeCheck};
+
+use super::dispersion::DispersionMessage;
+
+/// Segments used to draw ellipses and circles
+const OUTLINE_SEGMENTS: usize = 90;
+
+/// Smallest distance shown either side of the center of the map (m)
+const MIN_EXTENT: f64 = 50.0;
+
+/// Top-down map of where the flights of a dispersion analysis landed, and the
+/// limits of the launch field
+pub struct LandingPlot {
+    /// East and north landing points, and whether each is outside the limits
+    landings: Vec<(Vector2<f64>, bool)>,
+    /// Landing ellipses at one, two and three standard deviations
+    ellipses: Vec<Ellipse>,
+    limits: FieldLimits,
+}
+
+impl LandingPlot {
+    pub fn new() -> Self {
+        Self {
+            landings: Vec::new(),
+            ellipses: Vec::new(),
+            limits: FieldLimits::default(),
+        }
+    }
+
+    pub fn set(
+        &mut self,
+        results: Option<&DispersionResults>,
+        range_check: Option<&RangeCheck>,
+        limits: &FieldLimits,
+    ) {
+        self.landings = match (results, range_check) {
+            (Some(results), Some(range_check)) => results
+                .runs
+                .iter()
+                .zip(&range_check.outside)
+                .map(|(run, &outside)| (run.landing.truncate(), outside))
+                .collect(),
+            (Some(results), None) => results
+                .runs
+                .iter()
+                .map(|run| (run.landing.truncate(), false))
+                .collect(),
+            (None, _) => Vec::new(),
+        };
+        self.ellipses = results
+            .map(|results| {
+                (1..=3)
+                    .map(|sigmas| results.landing_ellipse(sigmas as f64))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.limits = limits.clone();
+    }
+
+    /// Square east and north ranges covering everything drawn on the map
+    fn extent(&self) -> (std::ops::Range<f64>, std::ops::Range<f64>) {
+        let radius = self.limits.waiver_radius.unwrap_or(0.0);
+        let points = self
+            .landings
+            .iter()
+            .map(|&(point, _)| point)
+            .chain(
+                self.ellipses
+                    .last()
+                    .into_iter()
+                    .flat_map(|ellipse| ellipse.outline(OUTLINE_SEGMENTS)),
+            )
+            .chain(
+                self.limits
+                    .boundary
+                    .iter()
+                    .map(|&(east, north)| Vector2::new(east, north)),
+            )
+            .chain(vec![
+                Vector2::new(-radius, -radius),
+                Vector2::new(radius, radius),
+            ]);
+
+        let (min, max) = points.fold(
+            (Vector2::new(0.0f64, 0.0f64), Vector2::new(0.0f64, 0.0f64)),
+            |(min, max), point| {
+                (
+                    Vector2::new(min.x.min(point.x), min.y.min(point.y)),
+                    Vector2::new(max.x.max(point.x), max.y.max(point.y)),
+                )
+            },
+        );
+
+        let center = (min + max) / 2.0;
+        let half_width = ((max.x - min.x).max(max.y - min.y) / 2.0 * 1.1).max(MIN_EXTENT);
+
+        (
+            (center.x - half_width)..(center.x + half_width),
+            (center.y - half_width)..(center.y + half_width),
+        )
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, plotters::coord::Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let (east, north) = self.extent();
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Landing Dispersion", ("sans-serif", 14))
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d(east, north)?;
+
+        chart
+            .configure_mesh()
+            .x_desc("East (m)")
+            .y_desc("North (m)")
+            .x_labels(8)
+            .y_labels(8)
+            .draw()?;
+
+        if self.limits.has_boundary() {
+            let corners: Vec<_> = self.limits.boundary.clone();
+            let green = RGBColor(0, 150, 80);
+
+            chart.draw_series(std::iter::once(Polygon::new(
+                corners.clone(),
+                green.mix(0.1).filled(),
+            )))?;
+            chart
+                .draw_series(LineSeries::new(
+                    corners.iter().chain(corners.first()).copied(),
+                    &green,
+                ))?
+                .label("Field boundary")
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &green));
+        }
+
+        if let Some(radius) = self.limits.waiver_radius {
+            let orange = RGBColor(230, 120, 0);
+
+            chart
+                .draw_series(LineSeries::new(
+                    (0..=OUTLINE_SEGMENTS).map(|index| {
+                        let angle = index as f64 / OUTLINE_SEGMENTS as f64 * 2.0 * PI;
+
+                        (radius * angle.sin(), radius * angle.cos())
+                    }),
+                    &orange,
+                ))?
+                .label("Waiver radius")
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &orange));
+        }
+
+        for (index, ellipse) in self.ellipses.iter().enumerate() {
+            let color = BLUE.mix(0.8 - 0.2 * index as f64);
+
+            chart
+                .draw_series(LineSeries::new(
+                    ellipse
+                        .outline(OUTLINE_SEGMENTS)
+                        .map(|point| (point.x, point.y)),
+                    &color,
+                ))?
+                .label(format!("{}σ ellipse", index + 1))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &color));
+        }
+
+        chart.draw_series(
+            self.landings
+                .iter()
+                .filter(|(_, outside)| !outside)
+                .map(|&(point, _)| Circle::new((point.x, point.y), 2, BLUE.mix(0.6).filled())),
+        )?;
+
+        if self.landings.iter().any(|&(_, outside)| outside) {
+            chart
+                .draw_series(
+                    self.landings
+                        .iter()
+                        .filter(|(_, outside)| *outside)
+                        .map(|&(point, _)| Circle::new((point.x, point.y), 3, RED.filled())),
+                )?
+                .label("Out of bounds")
+                .legend(|(x, y)| Circle::new((x + 10, y), 3, RED.filled()));
+        }
+
+        chart
+            .draw_series(std::iter::once(Cross::new((0.0, 0.0), 5, &BLACK)))?
+            .label("Launch rail")
+            .legend(|(x, y)| Cross::new((x + 10, y), 5, &BLACK));
+
+        chart
+            .configure_series_labels()
+            .background_style(&WHITE.mix(0.8))
+            .border_style(&BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+}
+
+impl Chart<DispersionMessage> for LandingPlot {
+    fn build_chart<DB: DrawingBackend>(&self, _builder: ChartBuilder<DB>) {}
+
+    fn draw_chart<DB: DrawingBackend>(&self, root: DrawingArea<DB, plotters::coord::Shift>) {
+        if let Err(error) = self.draw(root) {
+            warn!(%error, "Failed to draw landing plot");
+        }
+    }
+}
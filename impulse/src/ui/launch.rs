@@ -0,0 +1,252 @@
+use iced::{pick_list, text_input, Align, Column, Element, Length, PickList, Row, Text, TextInput};
+
+use crate::sim::LaunchConditions;
+
+use super::{
+    units::{format_value, Quantity, Unit},
+    ERROR_COLOR,
+};
+
+/// Screen for setting up the launch site, the rail and the weather
+pub struct LaunchEditor {
+    launch: LaunchConditions,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LaunchMessage {
+    FieldChanged(usize, String),
+    UnitChanged(usize, Unit),
+}
+
+/// A launch condition being edited as text
+struct Field {
+    parameter: Parameter,
+    text: String,
+    unit: Unit,
+    error: Option<&'static str>,
+    input: text_input::State,
+    unit_picker: pick_list::State<Unit>,
+}
+
+impl LaunchEditor {
+    pub fn new(launch: LaunchConditions) -> Self {
+        let fields = Parameter::ALL
+            .iter()
+            .map(|&parameter| {
+                let unit = parameter.default_unit();
+
+                Field {
+                    parameter,
+                    text: format_value(unit.in_unit(parameter.get(&launch))),
+                    unit,
+                    error: None,
+                    input: text_input::State::new(),
+                    unit_picker: pick_list::State::default(),
+                }
+            })
+            .collect();
+
+        Self { launch, fields }
+    }
+
+    /// Handle a message, returning the launch conditions to send to the
+    /// simulation when a value has been changed to something valid
+    pub fn update(&mut self, message: LaunchMessage) -> Option<LaunchConditions> {
+        match message {
+            LaunchMessage::FieldChanged(index, text) => {
+                let field = self.fields.get_mut(index)?;
+                let parameter = field.parameter;
+
+                field.text = text;
+
+                let value = field
+                    .text
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| "not a number")
+                    .and_then(|value| parameter.check(field.unit.to_si(value)));
+
+                match value {
+                    Ok(value) => {
+                        field.error = None;
+                        parameter.set(&mut self.launch, value);
+
+                        return Some(self.launch);
+                    }
+                    Err(error) => field.error = Some(error),
+                }
+            }
+            LaunchMessage::UnitChanged(index, unit) => {
+                let field = self.fields.get_mut(index)?;
+
+                field.unit = unit;
+                field.text = format_value(unit.in_unit(field.parameter.get(&self.launch)));
+                field.error = None;
+            }
+        }
+
+        None
+    }
+
+    pub fn view(&mut self) -> Element<'_, LaunchMessage> {
+        let mut sections = Column::new().spacing(5);
+        let mut heading = None;
+
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            let parameter = field.parameter;
+
+            if heading != Some(parameter.section()) {
+                heading = Some(parameter.section());
+                sections = sections.push(Text::new(parameter.section()).size(24));
+            }
+
+            let mut row = Row::new()
+                .spacing(5)
+                .align_items(Align::Center)
+                .push(Text::new(parameter.label()).width(Length::Units(140)))
+                .push(
+                    TextInput::new(&mut field.input, "", &field.text, move |text| {
+                        LaunchMessage::FieldChanged(index, text)
+                    })
+                    .padding(4)
+                    .width(Length::Units(120)),
+                )
+                .push(PickList::new(
+                    &mut field.unit_picker,
+                    parameter.quantity().units(),
+                    Some(field.unit),
+                    move |unit| LaunchMessage::UnitChanged(index, unit),
+                ));
+
+            if let Some(error) = field.error {
+                row = row.push(Text::new(error).color(ERROR_COLOR));
+            }
+
+            sections = sections.push(row);
+        }
+
+        sections.into()
+    }
+}
+
+/// A value of [`LaunchConditions`] that can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parameter {
+    Latitude,
+    Longitude,
+    SiteAltitude,
+    RailLength,
+    RailAngle,
+    RailHeading,
+    WindSpeed,
+    WindDirection,
+}
+
+impl Parameter {
+    const ALL: [Parameter; 8] = [
+        Parameter::Latitude,
+        Parameter::Longitude,
+        Parameter::SiteAltitude,
+        Parameter::RailLength,
+        Parameter::RailAngle,
+        Parameter::RailHeading,
+        Parameter::WindSpeed,
+        Parameter::WindDirection,
+    ];
+
+    fn section(self) -> &'static str {
+        match self {
+            Parameter::Latitude | Parameter::Longitude | Parameter::SiteAltitude => "Launch Site",
+            Parameter::RailLength | Parameter::RailAngle | Parameter::RailHeading => "Launch Rail",
+            Parameter::WindSpeed | Parameter::WindDirection => "Wind",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Parameter::Latitude => "Latitude",
+            Parameter::Longitude => "Longitude",
+            Parameter::SiteAltitude => "Altitude (MSL)",
+            Parameter::RailLength => "Length",
+            Parameter::RailAngle => "Angle from vertical",
+            Parameter::RailHeading => "Heading",
+            Parameter::WindSpeed => "Speed",
+            Parameter::WindDirection => "From heading",
+        }
+    }
+
+    fn quantity(self) -> Quantity {
+        match self {
+            Parameter::SiteAltitude | Parameter::RailLength => Quantity::Length,
+            Parameter::WindSpeed => Quantity::Speed,
+            _ => Quantity::Angle,
+        }
+    }
+
+    /// Unit the value is first shown in, which for lengths is meters rather
+    /// than the millimeters used for component dimensions
+    fn default_unit(self) -> Unit {
+        match self.quantity() {
+            Quantity::Length => Unit::Meter,
+            quantity => quantity.default_unit(),
+        }
+    }
+
+    /// Validate a value in SI units
+    fn check(self, value: f64) -> Result<f64, &'static str> {
+        let limit = match self {
+            Parameter::Latitude => Some(90.0),
+            Parameter::Longitude => Some(180.0),
+            Parameter::RailAngle => Some(90.0),
+            _ => None,
+        };
+
+        if !value.is_finite() {
+            Err("not a number")
+        } else if limit.is_some_and(|limit: f64| value.abs() > limit.to_radians()) {
+            Err(match self {
+                Parameter::Latitude => "must be within ±90°",
+                Parameter::Longitude => "must be within ±180°",
+                _ => "must be within 90° of vertical",
+            })
+        } else if value < 0.0
+            && matches!(
+                self,
+                Parameter::RailLength | Parameter::RailAngle | Parameter::WindSpeed
+            )
+        {
+            Err("must not be negative")
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn get(self, launch: &LaunchConditions) -> f64 {
+        match self {
+            Parameter::Latitude => launch.latitude,
+            Parameter::Longitude => launch.longitude,
+            Parameter::SiteAltitude => launch.site_altitude,
+            Parameter::RailLength => launch.rail_length,
+            Parameter::RailAngle => launch.rail_angle,
+            Parameter::RailHeading => launch.rail_heading,
+            Parameter::WindSpeed => launch.wind_speed,
+            Parameter::WindDirection => launch.wind_direction,
+        }
+    }
+
+    fn set(self, launch: &mut LaunchConditions, value: f64) {
+        let target = match self {
+            Parameter::Latitude => &mut launch.latitude,
+            Parameter::Longitude => &mut launch.longitude,
+            Parameter::SiteAltitude => &mut launch.site_altitude,
+            Parameter::RailLength => &mut launch.rail_length,
+            Parameter::RailAngle => &mut launch.rail_angle,
+            Parameter::RailHeading => &mut launch.rail_heading,
+            Parameter::WindSpeed => &mut launch.wind_speed,
+            Parameter::WindDirection => &mut launch.wind_direction,
+        };
+
+        *target = value;
+    }
+}
@@ -0,0 +1,781 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+};
+
+use iced::{
+    button, pick_list, scrollable, Align, Button, Column, Command, Element, Length, PickList, Row,
+    Scrollable, Text,
+};
+use plotters::prelude::*;
+use plotters_iced::{Chart, ChartWidget, DrawingBackend};
+use rfd::AsyncFileDialog;
+use thrustcurve_api::{
+    builder::SearchBuilder,
+    model::{Metadata, MotorInfo, Sample},
+    Client,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    rocket::{MotorReference, Rocket},
+    sim::{impulse_class, parse_delays, Motor},
+};
+
+use super::{ERROR_COLOR, WARNING_COLOR};
+
+/// Most motors requested from a single search
+const MAX_RESULTS: u32 = 200;
+
+/// Screen for finding motors on ThrustCurve or in local files and loading one
+/// into the rocket
+pub struct MotorBrowser {
+    client: Client,
+    status: String,
+    manufacturer: Filter,
+    impulse_class: Filter,
+    diameter: Filter,
+    /// Diameters offered by the metadata, in the same order as the filter options
+    diameters: Vec<f32>,
+    searching: bool,
+    results: Vec<MotorInfo>,
+    local: Vec<LocalMotor>,
+    selected: Option<Selection>,
+    /// Thrust curves that have been downloaded, by motor id
+    curves: HashMap<String, Result<Vec<(f64, f64)>, String>>,
+    preview: CurvePreview,
+
+    search_button: button::State,
+    open_button: button::State,
+    assign_button: button::State,
+    result_buttons: Vec<button::State>,
+    results_scroll: scrollable::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum MotorMessage {
+    MetadataLoaded(Result<Option<Metadata>, String>),
+    ManufacturerChanged(FilterChoice),
+    ImpulseClassChanged(FilterChoice),
+    DiameterChanged(FilterChoice),
+    Search,
+    SearchCompleted(Result<Vec<MotorInfo>, String>),
+    OpenFiles,
+    FilesPicked(Vec<PathBuf>),
+    Select(Selection),
+    CurveLoaded(String, Result<Vec<Sample>, String>),
+    Assign,
+}
+
+/// An entry in the list of motors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Index into the motors loaded from files
+    Local(usize),
+    /// Index into the search results
+    Search(usize),
+}
+
+/// A motor loaded from a file on disk
+struct LocalMotor {
+    path: PathBuf,
+    motor: Motor,
+}
+
+/// One of the values offered by a search filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterChoice {
+    /// Index into the values of the filter, or [`None`] to match anything
+    index: Option<usize>,
+    label: String,
+}
+
+impl FilterChoice {
+    fn any() -> Self {
+        Self {
+            index: None,
+            label: "Any".into(),
+        }
+    }
+}
+
+impl Display for FilterChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// A search criterion picked from the values known to ThrustCurve
+struct Filter {
+    options: Vec<FilterChoice>,
+    selected: FilterChoice,
+    picker: pick_list::State<FilterChoice>,
+}
+
+impl Filter {
+    fn new() -> Self {
+        Self {
+            options: vec![FilterChoice::any()],
+            selected: FilterChoice::any(),
+            picker: pick_list::State::default(),
+        }
+    }
+
+    fn set_options(&mut self, labels: impl IntoIterator<Item = String>) {
+        self.options = std::iter::once(FilterChoice::any())
+            .chain(
+                labels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, label)| FilterChoice {
+                        index: Some(index),
+                        label,
+                    }),
+            )
+            .collect();
+        self.selected = FilterChoice::any();
+    }
+
+    fn view(
+        &mut self,
+        label: &str,
+        on_change: fn(FilterChoice) -> MotorMessage,
+    ) -> Row<'_, MotorMessage> {
+        Row::new()
+            .spacing(5)
+            .align_items(Align::Center)
+            .push(Text::new(label))
+            .push(PickList::new(
+                &mut self.picker,
+                &self.options[..],
+                Some(self.selected.clone()),
+                on_change,
+            ))
+    }
+}
+
+impl MotorBrowser {
+    /// Create the browser along with the request for the values to filter by
+    pub fn new() -> (Self, Command<MotorMessage>) {
+        let client = Client::new();
+        let request = client.metadata();
+
+        (
+            Self {
+                client,
+                status: "Loading motor list from ThrustCurve...".into(),
+                manufacturer: Filter::new(),
+                impulse_class: Filter::new(),
+                diameter: Filter::new(),
+                diameters: Vec::new(),
+                searching: false,
+                results: Vec::new(),
+                local: Vec::new(),
+                selected: None,
+                curves: HashMap::new(),
+                preview: CurvePreview::default(),
+
+                search_button: button::State::new(),
+                open_button: button::State::new(),
+                assign_button: button::State::new(),
+                result_buttons: Vec::new(),
+                results_scroll: scrollable::State::new(),
+            },
+            Command::perform(request.get(), |result| {
+                MotorMessage::MetadataLoaded(result.map_err(|error| error.to_string()))
+            }),
+        )
+    }
+
+    /// Handle a message, returning the motor to load into the rocket once one
+    /// has been assigned
+    pub fn update(
+        &mut self,
+        message: MotorMessage,
+        rocket: &Rocket,
+    ) -> (Command<MotorMessage>, Option<Motor>) {
+        match message {
+            MotorMessage::MetadataLoaded(Ok(Some(metadata))) => {
+                self.manufacturer.set_options(
+                    metadata
+                        .manufacturers
+                        .into_iter()
+                        .map(|manufacturer| manufacturer.name),
+                );
+                self.impulse_class.set_options(metadata.impulse_classes);
+                self.diameter.set_options(
+                    metadata
+                        .diameters
+                        .iter()
+                        .map(|diameter| format!("{} mm", diameter)),
+                );
+                self.diameters = metadata.diameters;
+                self.status = "Choose filters and search for motors".into();
+            }
+            MotorMessage::MetadataLoaded(Ok(None)) => {
+                self.status = "ThrustCurve returned no motor list".into();
+            }
+            MotorMessage::MetadataLoaded(Err(error)) => {
+                warn!(%error, "Failed to load motor metadata");
+
+                self.status = format!("Failed to load motor list: {}", error);
+            }
+            MotorMessage::ManufacturerChanged(choice) => self.manufacturer.selected = choice,
+            MotorMessage::ImpulseClassChanged(choice) => self.impulse_class.selected = choice,
+            MotorMessage::DiameterChanged(choice) => self.diameter.selected = choice,
+            MotorMessage::Search => return (self.search(), None),
+            MotorMessage::SearchCompleted(result) => {
+                self.searching = false;
+                if let Some(Selection::Search(_)) = self.selected {
+                    self.selected = None;
+                    self.preview = CurvePreview::default();
+                }
+
+                match result {
+                    Ok(results) => {
+                        self.status = format!("Found {} motors", results.len());
+                        self.results = results;
+                    }
+                    Err(error) => {
+                        warn!(%error, "Motor search failed");
+
+                        self.status = format!("Search failed: {}", error);
+                        self.results.clear();
+                    }
+                }
+            }
+            MotorMessage::OpenFiles => {
+                let dialog = AsyncFileDialog::new()
+                    .set_title("Open Motor Files")
+                    .add_filter("Motor files", &["eng", "rse"]);
+
+                return (
+                    Command::perform(dialog.pick_files(), |files| {
+                        MotorMessage::FilesPicked(
+                            files
+                                .unwrap_or_default()
+                                .iter()
+                                .map(|file| file.path().to_path_buf())
+                                .collect(),
+                        )
+                    }),
+                    None,
+                );
+            }
+            MotorMessage::FilesPicked(paths) => {
+                for path in paths {
+                    self.load_file(&path);
+                }
+            }
+            MotorMessage::Select(selection) => {
+                self.selected = Some(selection);
+                self.update_preview();
+
+                if let Selection::Search(index) = selection {
+                    return (self.load_curve(index), None);
+                }
+            }
+            MotorMessage::CurveLoaded(motor_id, result) => {
+                let curve = result.and_then(|samples| match samples.as_slice() {
+                    [] => Err("ThrustCurve has no thrust curve for this motor".into()),
+                    samples => Ok(samples
+                        .iter()
+                        .map(|sample| (sample.time, sample.thrust))
+                        .collect()),
+                });
+
+                self.curves.insert(motor_id, curve);
+                self.update_preview();
+            }
+            MotorMessage::Assign => {
+                let motor = self.selected_motor().and_then(|motor| {
+                    let errors = rocket.check_motor(&motor);
+
+                    match errors.first() {
+                        Some(error) => Err(error.to_string()),
+                        None => Ok(motor),
+                    }
+                });
+
+                match motor {
+                    Ok(motor) => {
+                        self.status =
+                            format!("Loaded the {} into the motor mount", motor.designation);
+
+                        return (Command::none(), Some(motor));
+                    }
+                    Err(error) => self.status = error,
+                }
+            }
+        }
+
+        (Command::none(), None)
+    }
+
+    /// Load a motor from a RASP or RockSim file and select it
+    pub fn load_file(&mut self, path: &Path) {
+        match Motor::load(path) {
+            Ok(motor) => {
+                let warnings = motor.validate();
+
+                for warning in &warnings {
+                    warn!(path = %path.display(), %warning, "Motor file has problems");
+                }
+
+                self.status = match warnings.len() {
+                    0 => format!("Loaded the {} from {}", motor.designation, path.display()),
+                    count => format!(
+                        "Loaded the {} from {} with {} warnings",
+                        motor.designation,
+                        path.display(),
+                        count
+                    ),
+                };
+
+                // Reloading a file replaces the motor loaded from it before
+                let index = match self.local.iter().position(|local| local.path == path) {
+                    Some(index) => {
+                        self.local[index].motor = motor;
+                        index
+                    }
+                    None => {
+                        self.local.push(LocalMotor {
+                            path: path.to_path_buf(),
+                            motor,
+                        });
+                        self.local.len() - 1
+                    }
+                };
+
+                self.selected = Some(Selection::Local(index));
+                self.update_preview();
+            }
+            Err(error) => {
+                warn!(path = %path.display(), %error, "Failed to load motor file");
+
+                self.status = format!("Failed to load {}: {}", path.display(), error);
+            }
+        }
+    }
+
+    fn search(&mut self) -> Command<MotorMessage> {
+        let mut request = self.client.search().max_results(MAX_RESULTS);
+
+        if self.manufacturer.selected.index.is_some() {
+            request = request.by_manufacturer(&self.manufacturer.selected.label);
+        }
+        if self.impulse_class.selected.index.is_some() {
+            request = request.by_impulse_class(&self.impulse_class.selected.label);
+        }
+        if let Some(&diameter) = self
+            .diameter
+            .selected
+            .index
+            .and_then(|index| self.diameters.get(index))
+        {
+            request = request.by_diameter(diameter);
+        }
+
+        self.status = "Searching...".into();
+
+        self.run_search(request)
+    }
+
+    /// Search ThrustCurve for the motor an imported design was set up with
+    pub fn find(&mut self, motor: &MotorReference) -> Command<MotorMessage> {
+        let request = self
+            .client
+            .search()
+            .by_designation(&motor.designation)
+            .max_results(MAX_RESULTS);
+
+        self.status = format!(
+            "Searching for the {} from the imported design...",
+            motor.designation
+        );
+
+        self.run_search(request)
+    }
+
+    fn run_search(&mut self, request: SearchBuilder) -> Command<MotorMessage> {
+        debug!(?request, "Searching for motors");
+
+        self.searching = true;
+
+        Command::perform(request.get(), |result| {
+            MotorMessage::SearchCompleted(
+                result
+                    .map(|search| search.map(|search| search.results).unwrap_or_default())
+                    .map_err(|error| error.to_string()),
+            )
+        })
+    }
+
+    /// Download the thrust curve of a motor unless it has been already
+    fn load_curve(&self, index: usize) -> Command<MotorMessage> {
+        let motor_id = match self.results.get(index) {
+            Some(info) if !self.curves.contains_key(&info.motor_id) => info.motor_id.clone(),
+            _ => return Command::none(),
+        };
+        let request = self.client.download().motor_id(&motor_id).max_results(1);
+
+        Command::perform(request.get(), move |result| {
+            let samples = result.map_err(|error| error.to_string()).map(|download| {
+                download
+                    .into_iter()
+                    .flat_map(|download| download.results)
+                    .find_map(|file| file.samples)
+                    .unwrap_or_default()
+            });
+
+            MotorMessage::CurveLoaded(motor_id.clone(), samples)
+        })
+    }
+
+    fn update_preview(&mut self) {
+        self.preview = match self.selected {
+            Some(Selection::Local(index)) => match self.local.get(index) {
+                Some(local) => CurvePreview {
+                    title: local.motor.designation.clone(),
+                    curve: local.motor.thrust_curve.clone(),
+                },
+                None => CurvePreview::default(),
+            },
+            Some(Selection::Search(index)) => match self.results.get(index) {
+                Some(info) => CurvePreview {
+                    title: format!("{} {}", info.manufacturer_abbrev, info.designation),
+                    curve: match self.curves.get(&info.motor_id) {
+                        Some(Ok(curve)) => curve.clone(),
+                        _ => Vec::new(),
+                    },
+                },
+                None => CurvePreview::default(),
+            },
+            None => CurvePreview::default(),
+        };
+    }
+
+    /// Motor for the simulation, either loaded from a file or built from the
+    /// selected search result and its thrust curve
+    fn selected_motor(&self) -> Result<Motor, String> {
+        let index = match self.selected {
+            Some(Selection::Local(index)) => {
+                return self
+                    .local
+                    .get(index)
+                    .map(|local| local.motor.clone())
+                    .ok_or_else(|| "No motor is selected".into())
+            }
+            Some(Selection::Search(index)) => index,
+            None => return Err("No motor is selected".into()),
+        };
+        let info = self.results.get(index).ok_or("No motor is selected")?;
+        let curve = match self.curves.get(&info.motor_id) {
+            Some(Ok(curve)) => curve,
+            Some(Err(error)) => return Err(error.clone()),
+            None => return Err("The thrust curve is still loading".into()),
+        };
+        let (total_mass, propellant_mass) = match (info.total_mass, info.propellant_mass) {
+            (Some(total), Some(propellant)) => (total / 1000.0, propellant / 1000.0),
+            _ => {
+                return Err(format!(
+                    "ThrustCurve has no mass data for the {}",
+                    info.designation
+                ))
+            }
+        };
+
+        let mut thrust_curve = curve.clone();
+        if thrust_curve.first().is_some_and(|&(time, _)| time > 0.0) {
+            thrust_curve.insert(0, (0.0, 0.0));
+        }
+
+        Ok(Motor {
+            designation: info.designation.clone(),
+            diameter: info.diameter / 1000.0,
+            length: info.length / 1000.0,
+            total_mass,
+            propellant_mass,
+            thrust_curve,
+            delays: info.delays.as_deref().map(parse_delays).unwrap_or_default(),
+        })
+    }
+
+    pub fn view(&mut self, rocket: &Rocket, current: &Motor) -> Element<'_, MotorMessage> {
+        let selected = self.selected;
+        let selected_motor = self.selected_motor();
+        let warnings = selected_motor
+            .as_ref()
+            .map(Motor::validate)
+            .unwrap_or_default();
+        let fit = selected_motor
+            .as_ref()
+            .map(|motor| rocket.check_motor(motor))
+            .unwrap_or_default();
+
+        let format_optional = |value: Option<f64>, unit: &str| {
+            value
+                .map(|value| format!("{:.1} {}", value, unit))
+                .unwrap_or_else(|| "unknown".into())
+        };
+        let mut details = Column::new().spacing(5).width(Length::Fill);
+
+        match selected {
+            Some(Selection::Search(index)) => {
+                if let Some(info) = self.results.get(index) {
+                    details = details
+                        .push(
+                            Text::new(format!("{} {}", info.manufacturer, info.designation))
+                                .size(24),
+                        )
+                        .push(Text::new(format!(
+                            "Total impulse: {}",
+                            format_optional(info.total_impulse, "N·s")
+                        )))
+                        .push(Text::new(format!(
+                            "Average thrust: {}, peak {}",
+                            format_optional(info.average_thrust, "N"),
+                            format_optional(info.max_thrust, "N")
+                        )))
+                        .push(Text::new(format!(
+                            "Burn time: {}",
+                            format_optional(info.burn_time, "s")
+                        )))
+                        .push(Text::new(format!(
+                            "Mass: {} loaded, {} propellant",
+                            format_optional(info.total_mass, "g"),
+                            format_optional(info.propellant_mass, "g")
+                        )))
+                        .push(Text::new(format!(
+                            "Delays: {}",
+                            info.delays.as_deref().unwrap_or("none")
+                        )));
+                }
+            }
+            Some(Selection::Local(index)) => {
+                if let Some(LocalMotor { path, motor }) = self.local.get(index) {
+                    let total_impulse = motor.total_impulse();
+                    let burn_time = motor.burn_time();
+
+                    details = details
+                        .push(Text::new(motor.designation.as_str()).size(24))
+                        .push(Text::new(path.display().to_string()).size(16))
+                        .push(Text::new(format!(
+                            "Total impulse: {:.1} N·s (class {})",
+                            total_impulse,
+                            impulse_class(total_impulse).unwrap_or_else(|| "unknown".into())
+                        )))
+                        .push(Text::new(format!(
+                            "Average thrust: {}, peak {:.1} N",
+                            format_optional(
+                                Some(total_impulse / burn_time).filter(|thrust| thrust.is_finite()),
+                                "N"
+                            ),
+                            motor
+                                .thrust_curve
+                                .iter()
+                                .fold(0.0f64, |peak, &(_, thrust)| peak.max(thrust))
+                        )))
+                        .push(Text::new(format!("Burn time: {:.2} s", burn_time)))
+                        .push(Text::new(format!(
+                            "Mass: {:.1} g loaded, {:.1} g propellant",
+                            motor.total_mass * 1000.0,
+                            motor.propellant_mass * 1000.0
+                        )))
+                        .push(Text::new(format!(
+                            "Delays: {}",
+                            if motor.delays.is_empty() {
+                                "none".to_string()
+                            } else {
+                                motor
+                                    .delays
+                                    .iter()
+                                    .map(|delay| format!("{}", delay))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            }
+                        )));
+                }
+            }
+            None => {
+                details = details.push(Text::new("Select a motor to preview its thrust curve"));
+            }
+        }
+
+        if selected.is_some() {
+            if let Err(error) = &selected_motor {
+                details = details.push(Text::new(error.as_str()).size(16).color(ERROR_COLOR));
+            }
+
+            details = warnings.iter().fold(details, |details, warning| {
+                details.push(
+                    Text::new(format!("Warning: {}", warning))
+                        .size(16)
+                        .color(WARNING_COLOR),
+                )
+            });
+            details = fit.iter().fold(details, |details, error| {
+                details.push(Text::new(error.to_string()).size(16).color(ERROR_COLOR))
+            });
+
+            let mut assign =
+                Button::new(&mut self.assign_button, Text::new("Load into Motor Mount"));
+            if selected_motor.is_ok() && fit.is_empty() {
+                assign = assign.on_press(MotorMessage::Assign);
+            }
+
+            details = details.push(assign).push(
+                ChartWidget::new(&mut self.preview)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            );
+        }
+
+        let mut search = Button::new(&mut self.search_button, Text::new("Search"));
+        if !self.searching {
+            search = search.on_press(MotorMessage::Search);
+        }
+
+        let filters = Row::new()
+            .spacing(15)
+            .align_items(Align::Center)
+            .push(
+                self.manufacturer
+                    .view("Manufacturer", MotorMessage::ManufacturerChanged),
+            )
+            .push(
+                self.impulse_class
+                    .view("Class", MotorMessage::ImpulseClassChanged),
+            )
+            .push(
+                self.diameter
+                    .view("Diameter", MotorMessage::DiameterChanged),
+            )
+            .push(search)
+            .push(
+                Button::new(&mut self.open_button, Text::new("Open Motor Files..."))
+                    .on_press(MotorMessage::OpenFiles),
+            );
+
+        // Motors loaded from files are listed ahead of the search results
+        let entries = self
+            .local
+            .iter()
+            .enumerate()
+            .map(|(index, local)| {
+                (
+                    Selection::Local(index),
+                    format!(
+                        "[file] {} ({:.0} x {:.0} mm, {:.0} N·s)",
+                        local.motor.designation,
+                        local.motor.diameter * 1000.0,
+                        local.motor.length * 1000.0,
+                        local.motor.total_impulse()
+                    ),
+                )
+            })
+            .chain(self.results.iter().enumerate().map(|(index, info)| {
+                (
+                    Selection::Search(index),
+                    format!(
+                        "{} {} ({:.0} x {:.0} mm, {})",
+                        info.manufacturer_abbrev,
+                        info.designation,
+                        info.diameter,
+                        info.length,
+                        info.total_impulse
+                            .map(|impulse| format!("{:.0} N·s", impulse))
+                            .unwrap_or_else(|| "? N·s".into()),
+                    ),
+                )
+            }))
+            .collect::<Vec<_>>();
+
+        self.result_buttons
+            .resize_with(entries.len(), button::State::new);
+
+        let results = entries
+            .into_iter()
+            .zip(self.result_buttons.iter_mut())
+            .fold(
+                Scrollable::new(&mut self.results_scroll)
+                    .width(Length::Units(420))
+                    .height(Length::Fill)
+                    .spacing(2),
+                |results, ((selection, label), state)| {
+                    let label = if selected == Some(selection) {
+                        format!("> {}", label)
+                    } else {
+                        label
+                    };
+
+                    results.push(
+                        Button::new(state, Text::new(label).size(16))
+                            .width(Length::Fill)
+                            .on_press(MotorMessage::Select(selection)),
+                    )
+                },
+            );
+
+        Column::new()
+            .spacing(10)
+            .push(filters)
+            .push(Text::new(format!(
+                "{} | Loaded motor: {}",
+                self.status, current.designation
+            )))
+            .push(Row::new().spacing(10).push(results).push(details))
+            .into()
+    }
+}
+
+/// Plot of the thrust curve of the selected motor
+#[derive(Default)]
+struct CurvePreview {
+    title: String,
+    curve: Vec<(f64, f64)>,
+}
+
+impl CurvePreview {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        mut builder: ChartBuilder<DB>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let (max_time, max_thrust) = self
+            .curve
+            .iter()
+            .fold((0.0f64, 0.0f64), |(time, thrust), point| {
+                (time.max(point.0), thrust.max(point.1))
+            });
+
+        let mut chart = builder
+            .caption(&self.title, ("sans-serif", 16))
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d(0.0..max_time.max(0.1), 0.0..(max_thrust * 1.1).max(1.0))?;
+
+        chart.configure_mesh().x_desc("s").y_desc("N").draw()?;
+
+        chart.draw_series(AreaSeries::new(
+            self.curve.iter().copied(),
+            0.0,
+            &RGBColor(230, 120, 0).mix(0.3),
+        ))?;
+        chart.draw_series(LineSeries::new(
+            self.curve.iter().copied(),
+            &RGBColor(230, 120, 0),
+        ))?;
+
+        Ok(())
+    }
+}
+
+impl Chart<MotorMessage> for CurvePreview {
+    fn build_chart<DB: DrawingBackend>(&self, builder: ChartBuilder<DB>) {
+        if let Err(error) = self.draw(builder) {
+            warn!(%error, "Failed to draw thrust curve");
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::{cell::Cell, ops::Range};
+
+use iced::{
+    canvas::{event::Status, Cursor, Event},
+    mouse::{self, ScrollDelta},
+    Rectangle,
+};
+use plotters::prelude::*;
+use plotters_iced::{Chart, DrawingBackend};
+use tracing::warn;
+
+use crate::sim::FlightSample;
+
+use super::Message;
+
+/// A quantity that can be plotted against time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Series {
+    Altitude,
+    Velocity,
+    Acceleration,
+    Thrust,
+    Mach,
+}
+
+impl Series {
+    pub const ALL: [Series; 5] = [
+        Series::Altitude,
+        Series::Velocity,
+        Series::Acceleration,
+        Series::Thrust,
+        Series::Mach,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Series::Altitude => "Altitude",
+            Series::Velocity => "Velocity",
+            Series::Acceleration => "Acceleration",
+            Series::Thrust => "Thrust",
+            Series::Mach => "Mach",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Series::Altitude => "m",
+            Series::Velocity => "m/s",
+            Series::Acceleration => "m/s²",
+            Series::Thrust => "N",
+            Series::Mach => "",
+        }
+    }
+
+    pub fn value(self, sample: &FlightSample) -> f64 {
+        match self {
+            Series::Altitude => sample.altitude(),
+            Series::Velocity => sample.speed(),
+            Series::Acceleration => cgmath::InnerSpace::magnitude(sample.acceleration),
+            Series::Thrust => sample.thrust,
+            Series::Mach => sample.mach,
+        }
+    }
+
+    fn color(self) -> RGBColor {
+        match self {
+            Series::Altitude => BLUE,
+            Series::Velocity => RED,
+            Series::Acceleration => MAGENTA,
+            Series::Thrust => RGBColor(230, 120, 0),
+            Series::Mach => RGBColor(0, 150, 80),
+        }
+    }
+}
+
+/// Stacked time series panels sharing a zoomable and pannable time axis
+pub struct FlightPlot {
+    samples: Vec<FlightSample>,
+    visible: Vec<Series>,
+    /// Time range being displayed, or [`None`] to fit all samples
+    view: Option<Range<f64>>,
+    /// Time under the mouse cursor
+    crosshair: Option<f64>,
+    /// Cursor position and view at the start of a pan
+    drag: Option<(f32, Range<f64>)>,
+    /// Horizontal pixel extent of the plotting area from the last draw
+    plotting_area: Cell<Option<(i32, i32)>>,
+}
+
+impl FlightPlot {
+    pub fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+            visible: vec![Series::Altitude, Series::Velocity, Series::Acceleration],
+            view: None,
+            crosshair: None,
+            drag: None,
+            plotting_area: Cell::new(None),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.view = None;
+        self.crosshair = None;
+    }
+
+    pub fn extend(&mut self, samples: impl IntoIterator<Item = FlightSample>) {
+        self.samples.extend(samples);
+    }
+
+    pub fn is_visible(&self, series: Series) -> bool {
+        self.visible.contains(&series)
+    }
+
+    pub fn set_visible(&mut self, series: Series, visible: bool) {
+        self.visible.retain(|&s| s != series);
+
+        if visible {
+            self.visible.push(series);
+            self.visible
+                .sort_by_key(|s| Series::ALL.iter().position(|a| a == s));
+        }
+    }
+
+    pub fn reset_view(&mut self) {
+        self.view = None;
+    }
+
+    /// Value of a series at a given time, linearly interpolated between samples
+    pub fn value_at(&self, series: Series, time: f64) -> Option<f64> {
+        let index = self.samples.partition_point(|sample| sample.time < time);
+
+        match (
+            self.samples.get(index.wrapping_sub(1)),
+            self.samples.get(index),
+        ) {
+            (Some(before), Some(after)) if after.time > before.time => {
+                let fraction = (time - before.time) / (after.time - before.time);
+
+                Some(series.value(before) + (series.value(after) - series.value(before)) * fraction)
+            }
+            (_, Some(sample)) | (Some(sample), None) => Some(series.value(sample)),
+            (None, None) => None,
+        }
+    }
+
+    fn time_range(&self) -> Range<f64> {
+        self.view.clone().unwrap_or_else(|| {
+            0.0..self
+                .samples
+                .last()
+                .map(|sample| sample.time)
+                .unwrap_or(0.0)
+                .max(1.0)
+        })
+    }
+
+    /// Convert a horizontal position within the widget into a time
+    fn time_at(&self, x: f32) -> Option<f64> {
+        let (left, right) = self.plotting_area.get()?;
+        let range = self.time_range();
+        let fraction = (x as f64 - left as f64) / (right - left).max(1) as f64;
+
+        if (0.0..=1.0).contains(&fraction) {
+            Some(range.start + fraction * (range.end - range.start))
+        } else {
+            None
+        }
+    }
+
+    fn draw_panel<DB: DrawingBackend>(
+        &self,
+        area: &DrawingArea<DB, plotters::coord::Shift>,
+        series: Series,
+        time_range: Range<f64>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let visible_samples = self
+            .samples
+            .iter()
+            .filter(|sample| time_range.contains(&sample.time));
+
+        let (min, max) = visible_samples
+            .clone()
+            .map(|sample| series.value(sample))
+            .fold((0.0f64, 0.0f64), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        let padding = ((max - min) * 0.05).max(f64::EPSILON);
+
+        let caption = match self
+            .crosshair
+            .and_then(|time| Some((time, self.value_at(series, time)?)))
+        {
+            Some((time, value)) => format!(
+                "{} at {:.2} s: {:.2} {}",
+                series.name(),
+                time,
+                value,
+                series.unit()
+            ),
+            None => series.name().to_string(),
+        };
+
+        let mut chart = ChartBuilder::on(area)
+            .caption(caption, ("sans-serif", 14))
+            .margin(5)
+            .x_label_area_size(20)
+            .y_label_area_size(50)
+            .build_cartesian_2d(time_range.clone(), (min - padding)..(max + padding))?;
+
+        chart
+            .configure_mesh()
+            .y_desc(series.unit())
+            .x_labels(10)
+            .y_labels(5)
+            .draw()?;
+
+        let (pixels, _) = chart.plotting_area().get_pixel_range();
+        self.plotting_area.set(Some((pixels.start, pixels.end)));
+
+        chart.draw_series(LineSeries::new(
+            visible_samples.map(|sample| (sample.time, series.value(sample))),
+            &series.color(),
+        ))?;
+
+        if let Some(time) = self.crosshair {
+            chart.draw_series(LineSeries::new(
+                vec![(time, min - padding), (time, max + padding)],
+                &BLACK.mix(0.4),
+            ))?;
+
+            if let Some(value) = self.value_at(series, time) {
+                chart.draw_series(std::iter::once(Circle::new(
+                    (time, value),
+                    3,
+                    series.color().filled(),
+                )))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Chart<Message> for FlightPlot {
+    fn build_chart<DB: DrawingBackend>(&self, _builder: ChartBuilder<DB>) {}
+
+    fn draw_chart<DB: DrawingBackend>(&self, root: DrawingArea<DB, plotters::coord::Shift>) {
+        if self.visible.is_empty() {
+            return;
+        }
+
+        let time_range = self.time_range();
+
+        for (area, &series) in root
+            .split_evenly((self.visible.len(), 1))
+            .iter()
+            .zip(&self.visible)
+        {
+            if let Err(error) = self.draw_panel(area, series, time_range.clone()) {
+                warn!(%error, ?series, "Failed to draw plot panel");
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (Status, Option<Message>) {
+        let position = match cursor.position_in(&bounds) {
+            Some(position) => position,
+            None => {
+                self.drag = None;
+
+                return match self.crosshair.take() {
+                    Some(_) => (Status::Ignored, Some(Message::PlotChanged)),
+                    None => (Status::Ignored, None),
+                };
+            }
+        };
+
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Some((start, view)) = &self.drag {
+                    if let Some((left, right)) = self.plotting_area.get() {
+                        let offset = (start - position.x) as f64 / (right - left).max(1) as f64
+                            * (view.end - view.start);
+
+                        self.view = Some((view.start + offset)..(view.end + offset));
+                    }
+                }
+
+                self.crosshair = self.time_at(position.x);
+
+                (Status::Captured, Some(Message::PlotChanged))
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let lines = match delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / 60.0,
+                };
+
+                if let Some(center) = self.time_at(position.x) {
+                    let range = self.time_range();
+                    let factor = 0.8f64.powf(lines as f64);
+
+                    self.view = Some(
+                        (center - (center - range.start) * factor)
+                            ..(center + (range.end - center) * factor),
+                    );
+                }
+
+                (Status::Captured, Some(Message::PlotChanged))
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                self.drag = Some((position.x, self.time_range()));
+
+                (Status::Captured, None)
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                self.drag = None;
+
+                (Status::Captured, None)
+            }
+            _ => (Status::Ignored, None),
+        }
+    }
+}
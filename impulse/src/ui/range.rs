@@ -0,0 +1,286 @@
+use iced::{
+    button, pick_list, text_input, Align, Button, Column, Element, Length, PickList, Row, Text,
+    TextInput,
+};
+
+use crate::sim::FieldLimits;
+
+use super::{
+    units::{format_value, Quantity, Unit},
+    ERROR_COLOR,
+};
+
+/// Editor of the boundary of the launch field and the waiver radius
+pub struct RangeEditor {
+    limits: FieldLimits,
+    unit: Unit,
+    waiver: String,
+    waiver_error: Option<&'static str>,
+    corners: Vec<Corner>,
+
+    unit_picker: pick_list::State<Unit>,
+    waiver_input: text_input::State,
+    add_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum RangeMessage {
+    UnitChanged(Unit),
+    WaiverChanged(String),
+    EastChanged(usize, String),
+    NorthChanged(usize, String),
+    AddCorner,
+    RemoveCorner(usize),
+}
+
+/// A corner of the field boundary being edited as text
+struct Corner {
+    east: String,
+    north: String,
+    error: Option<&'static str>,
+    east_input: text_input::State,
+    north_input: text_input::State,
+    remove_button: button::State,
+}
+
+impl Corner {
+    fn new(east: String, north: String) -> Self {
+        Self {
+            east,
+            north,
+            error: None,
+            east_input: text_input::State::new(),
+            north_input: text_input::State::new(),
+            remove_button: button::State::new(),
+        }
+    }
+
+    /// East and north position in the given unit, if both can be parsed
+    fn position(&mut self, unit: Unit) -> Option<(f64, f64)> {
+        let parse = |text: &str| {
+            text.trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+        };
+
+        match (parse(&self.east), parse(&self.north)) {
+            (Some(east), Some(north)) => {
+                self.error = None;
+
+                Some((unit.to_si(east), unit.to_si(north)))
+            }
+            _ => {
+                self.error = Some("not a number");
+
+                None
+            }
+        }
+    }
+}
+
+impl RangeEditor {
+    pub fn new(limits: FieldLimits) -> Self {
+        let mut editor = Self {
+            limits,
+            unit: Quantity::Distance.default_unit(),
+            waiver: String::new(),
+            waiver_error: None,
+            corners: Vec::new(),
+
+            unit_picker: pick_list::State::default(),
+            waiver_input: text_input::State::new(),
+            add_button: button::State::new(),
+        };
+
+        editor.reformat();
+        editor
+    }
+
+    pub fn limits(&self) -> &FieldLimits {
+        &self.limits
+    }
+
+    /// Replace the text of every value with the limits in the current unit
+    fn reformat(&mut self) {
+        let unit = self.unit;
+
+        self.waiver = self
+            .limits
+            .waiver_radius
+            .map(|radius| format_value(unit.in_unit(radius)))
+            .unwrap_or_default();
+        self.waiver_error = None;
+        self.corners = self
+            .limits
+            .boundary
+            .iter()
+            .map(|&(east, north)| {
+                Corner::new(
+                    format_value(unit.in_unit(east)),
+                    format_value(unit.in_unit(north)),
+                )
+            })
+            .collect();
+    }
+
+    /// Handle a message, returning the new limits if every value is valid
+    pub fn update(&mut self, message: RangeMessage) -> Option<FieldLimits> {
+        match message {
+            RangeMessage::UnitChanged(unit) => {
+                self.unit = unit;
+                self.reformat();
+
+                return None;
+            }
+            RangeMessage::WaiverChanged(text) => self.waiver = text,
+            RangeMessage::EastChanged(index, text) => self.corners.get_mut(index)?.east = text,
+            RangeMessage::NorthChanged(index, text) => self.corners.get_mut(index)?.north = text,
+            RangeMessage::AddCorner => {
+                // Start new corners on the previous one so the boundary stays
+                // valid while they are edited
+                let (east, north) = self
+                    .corners
+                    .last()
+                    .map(|corner| (corner.east.clone(), corner.north.clone()))
+                    .unwrap_or_else(|| ("0".into(), "0".into()));
+
+                self.corners.push(Corner::new(east, north));
+            }
+            RangeMessage::RemoveCorner(index) => {
+                if index < self.corners.len() {
+                    self.corners.remove(index);
+                }
+            }
+        }
+
+        let unit = self.unit;
+        let waiver_radius = match self.waiver.trim() {
+            "" => {
+                self.waiver_error = None;
+
+                None
+            }
+            text => match text.parse::<f64>() {
+                Ok(radius) if radius.is_finite() && radius > 0.0 => {
+                    self.waiver_error = None;
+
+                    Some(unit.to_si(radius))
+                }
+                Ok(_) => {
+                    self.waiver_error = Some("must be positive");
+
+                    return None;
+                }
+                Err(_) => {
+                    self.waiver_error = Some("not a number");
+
+                    return None;
+                }
+            },
+        };
+
+        let boundary: Vec<_> = self
+            .corners
+            .iter_mut()
+            .map(|corner| corner.position(unit))
+            .collect();
+
+        self.limits = FieldLimits {
+            boundary: boundary.into_iter().collect::<Option<_>>()?,
+            waiver_radius,
+        };
+
+        Some(self.limits.clone())
+    }
+
+    pub fn view(&mut self) -> Element<'_, RangeMessage> {
+        let mut waiver = Row::new()
+            .spacing(5)
+            .align_items(Align::Center)
+            .push(Text::new("Waiver radius").width(Length::Units(160)))
+            .push(
+                TextInput::new(
+                    &mut self.waiver_input,
+                    "none",
+                    &self.waiver,
+                    RangeMessage::WaiverChanged,
+                )
+                .padding(4)
+                .width(Length::Units(80)),
+            )
+            .push(PickList::new(
+                &mut self.unit_picker,
+                Quantity::Distance.units(),
+                Some(self.unit),
+                RangeMessage::UnitChanged,
+            ));
+
+        if let Some(error) = self.waiver_error {
+            waiver = waiver.push(Text::new(error).color(ERROR_COLOR));
+        }
+
+        let boundary_note = match self.corners.len() {
+            0 => Some("No field boundary"),
+            1 | 2 => Some("The field boundary needs at least three corners"),
+            _ => None,
+        };
+
+        let unit = self.unit;
+        let corners = self.corners.iter_mut().enumerate().fold(
+            Column::new().spacing(5),
+            |column, (index, corner)| {
+                let mut row = Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(Text::new(format!("Corner {}", index + 1)).width(Length::Units(160)))
+                    .push(
+                        TextInput::new(&mut corner.east_input, "east", &corner.east, move |text| {
+                            RangeMessage::EastChanged(index, text)
+                        })
+                        .padding(4)
+                        .width(Length::Units(80)),
+                    )
+                    .push(Text::new(format!("{} E", unit)))
+                    .push(
+                        TextInput::new(
+                            &mut corner.north_input,
+                            "north",
+                            &corner.north,
+                            move |text| RangeMessage::NorthChanged(index, text),
+                        )
+                        .padding(4)
+                        .width(Length::Units(80)),
+                    )
+                    .push(Text::new(format!("{} N", unit)))
+                    .push(
+                        Button::new(&mut corner.remove_button, Text::new("Remove"))
+                            .on_press(RangeMessage::RemoveCorner(index)),
+                    );
+
+                if let Some(error) = corner.error {
+                    row = row.push(Text::new(error).color(ERROR_COLOR));
+                }
+
+                column.push(row)
+            },
+        );
+
+        let mut content = Column::new()
+            .spacing(5)
+            .push(Text::new("Range Safety").size(24))
+            .push(waiver)
+            .push(corners);
+
+        if let Some(note) = boundary_note {
+            content = content.push(Text::new(note).size(16));
+        }
+
+        content
+            .push(
+                Button::new(&mut self.add_button, Text::new("Add Corner"))
+                    .on_press(RangeMessage::AddCorner),
+            )
+            .into()
+    }
+}
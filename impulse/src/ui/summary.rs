@@ -0,0 +1,37 @@
+use iced::{Column, Element, Length, Row, Text};
+
+use crate::sim::FlightSummary;
+
+use super::{Message, WARNING_COLOR};
+
+/// Panel listing the key figures of a completed flight
+pub fn view(summary: &FlightSummary) -> Element<'_, Message> {
+    let warning = summary
+        .ejection
+        .as_ref()
+        .and_then(|ejection| ejection.warning());
+
+    summary
+        .rows()
+        .into_iter()
+        .fold(
+            Column::new()
+                .spacing(4)
+                .width(Length::Units(280))
+                .push(Text::new("Flight Summary").size(24)),
+            |column, (label, value)| {
+                let mut value_text = Text::new(value.as_str()).size(16);
+
+                if warning.as_ref() == Some(&value) {
+                    value_text = value_text.color(WARNING_COLOR);
+                }
+
+                column.push(
+                    Row::new()
+                        .push(Text::new(label).size(16).width(Length::Fill))
+                        .push(value_text),
+                )
+            },
+        )
+        .into()
+}
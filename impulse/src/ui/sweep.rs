@@ -0,0 +1,769 @@
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use iced::{
+    button, pick_list, text_input, Align, Button, Checkbox, Column, Element, Length, PickList,
+    ProgressBar, Row, Text, TextInput,
+};
+use plotters::prelude::*;
+use plotters_iced::{Chart, ChartWidget, DrawingBackend};
+use tracing::warn;
+
+use crate::{
+    model::{SimulationControl, SimulationStatus},
+    sim::{Axis, Goal, Metric, Optimum, SweepParameter, SweepResults},
+};
+
+use super::{
+    units::{format_value, Quantity, Unit},
+    ERROR_COLOR,
+};
+
+/// Screen for sweeping parameters of the flight across ranges and searching
+/// for the value of one that best meets a goal
+pub struct SweepScreen {
+    mode: Mode,
+    axes: [AxisEditor; 2],
+    /// Whether the sweep varies the second parameter as well as the first
+    two_parameters: bool,
+    metric: Metric,
+    goal: GoalKind,
+    target: String,
+    target_error: Option<&'static str>,
+    /// Flights simulated so far and the number expected
+    progress: Option<(usize, usize)>,
+    results: Option<SweepResults>,
+    optimum: Option<Optimum>,
+    plot: SweepPlot,
+
+    mode_picker: pick_list::State<Mode>,
+    metric_picker: pick_list::State<Metric>,
+    goal_picker: pick_list::State<GoalKind>,
+    target_input: text_input::State,
+    control_button: button::State,
+}
+
+#[derive(Debug, Clone)]
+pub enum SweepMessage {
+    ModeChanged(Mode),
+    ParameterChanged(usize, SweepParameter),
+    RangeStartChanged(usize, String),
+    RangeEndChanged(usize, String),
+    StepsChanged(usize, String),
+    UnitChanged(usize, Unit),
+    TwoParametersToggled(bool),
+    MetricChanged(Metric),
+    GoalChanged(GoalKind),
+    TargetChanged(String),
+    Start,
+    Stop,
+}
+
+/// Whether the screen sweeps parameters or optimizes one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Sweep,
+    Optimize,
+}
+
+impl Mode {
+    const ALL: [Mode; 2] = [Mode::Sweep, Mode::Optimize];
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Mode::Sweep => "Sweep parameters",
+            Mode::Optimize => "Optimize a parameter",
+        })
+    }
+}
+
+/// The kind of a [`Goal`], without its target value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalKind {
+    Target,
+    Maximize,
+    Minimize,
+}
+
+impl GoalKind {
+    const ALL: [GoalKind; 3] = [GoalKind::Target, GoalKind::Maximize, GoalKind::Minimize];
+}
+
+impl Display for GoalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GoalKind::Target => "Hit a target",
+            GoalKind::Maximize => "Maximize",
+            GoalKind::Minimize => "Minimize",
+        })
+    }
+}
+
+/// Units that values of a parameter are entered in
+fn quantity(parameter: SweepParameter) -> Quantity {
+    match parameter {
+        SweepParameter::NoseWeight => Quantity::Mass,
+        SweepParameter::DeployDelay => Quantity::Time,
+        SweepParameter::DeployAltitude => Quantity::Distance,
+        SweepParameter::RailAngle => Quantity::Angle,
+        SweepParameter::WindSpeed => Quantity::Speed,
+    }
+}
+
+/// The range of a parameter being edited as text
+struct AxisEditor {
+    parameter: SweepParameter,
+    start: String,
+    end: String,
+    steps: String,
+    unit: Unit,
+
+    parameter_picker: pick_list::State<SweepParameter>,
+    start_input: text_input::State,
+    end_input: text_input::State,
+    steps_input: text_input::State,
+    unit_picker: pick_list::State<Unit>,
+}
+
+impl AxisEditor {
+    fn new(parameter: SweepParameter, start: f64, end: f64, steps: usize) -> Self {
+        let unit = quantity(parameter).default_unit();
+
+        Self {
+            parameter,
+            start: format_value(start),
+            end: format_value(end),
+            steps: steps.to_string(),
+            unit,
+
+            parameter_picker: pick_list::State::default(),
+            start_input: text_input::State::new(),
+            end_input: text_input::State::new(),
+            steps_input: text_input::State::new(),
+            unit_picker: pick_list::State::default(),
+        }
+    }
+
+    fn set_parameter(&mut self, parameter: SweepParameter) {
+        self.parameter = parameter;
+        self.unit = quantity(parameter).default_unit();
+    }
+
+    /// Change the unit, rewriting the range so that it stays the same
+    fn set_unit(&mut self, unit: Unit) {
+        if let (Ok(start), Ok(end)) = (self.parse(&self.start), self.parse(&self.end)) {
+            self.start = format_value(unit.in_unit(start));
+            self.end = format_value(unit.in_unit(end));
+        }
+
+        self.unit = unit;
+    }
+
+    fn parse(&self, text: &str) -> Result<f64, &'static str> {
+        match text.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(self.unit.to_si(value)),
+            _ => Err("the range must be numbers"),
+        }
+    }
+
+    /// Start and end of the range in SI units, or why they cannot be used
+    fn range(&self) -> Result<(f64, f64), &'static str> {
+        let start = self.parse(&self.start)?;
+        let end = self.parse(&self.end)?;
+
+        if start == end {
+            Err("the range must not be empty")
+        } else {
+            Ok((start, end))
+        }
+    }
+
+    /// The range and its steps in SI units, or why they cannot be used
+    fn axis(&self) -> Result<Axis, &'static str> {
+        let (start, end) = self.range()?;
+        let steps = match self.steps.trim().parse::<usize>() {
+            Ok(steps) if steps >= 2 => steps,
+            _ => return Err("there must be at least two steps"),
+        };
+
+        Ok(Axis {
+            parameter: self.parameter,
+            start,
+            end,
+            steps,
+        })
+    }
+
+    fn view(&mut self, index: usize, label: &str, show_steps: bool) -> Element<'_, SweepMessage> {
+        let error = if show_steps {
+            self.axis().err()
+        } else {
+            self.range().err()
+        };
+
+        let mut row = Row::new()
+            .spacing(5)
+            .align_items(Align::Center)
+            .push(Text::new(label).width(Length::Units(120)))
+            .push(PickList::new(
+                &mut self.parameter_picker,
+                &SweepParameter::ALL[..],
+                Some(self.parameter),
+                move |parameter| SweepMessage::ParameterChanged(index, parameter),
+            ))
+            .push(Text::new("from"))
+            .push(
+                TextInput::new(&mut self.start_input, "", &self.start, move |text| {
+                    SweepMessage::RangeStartChanged(index, text)
+                })
+                .padding(4)
+                .width(Length::Units(70)),
+            )
+            .push(Text::new("to"))
+            .push(
+                TextInput::new(&mut self.end_input, "", &self.end, move |text| {
+                    SweepMessage::RangeEndChanged(index, text)
+                })
+                .padding(4)
+                .width(Length::Units(70)),
+            )
+            .push(PickList::new(
+                &mut self.unit_picker,
+                quantity(self.parameter).units(),
+                Some(self.unit),
+                move |unit| SweepMessage::UnitChanged(index, unit),
+            ));
+
+        if show_steps {
+            row = row.push(Text::new("in")).push(
+                TextInput::new(&mut self.steps_input, "", &self.steps, move |text| {
+                    SweepMessage::StepsChanged(index, text)
+                })
+                .padding(4)
+                .width(Length::Units(50)),
+            );
+            row = row.push(Text::new("steps"));
+        }
+
+        match error {
+            Some(error) => row.push(Text::new(error).color(ERROR_COLOR)).into(),
+            None => row.into(),
+        }
+    }
+}
+
+impl SweepScreen {
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Sweep,
+            axes: [
+                AxisEditor::new(SweepParameter::NoseWeight, 0.0, 200.0, 11),
+                AxisEditor::new(SweepParameter::DeployDelay, 0.0, 6.0, 7),
+            ],
+            two_parameters: false,
+            metric: Metric::Apogee,
+            goal: GoalKind::Target,
+            target: "500".into(),
+            target_error: None,
+            progress: None,
+            results: None,
+            optimum: None,
+            plot: SweepPlot::default(),
+
+            mode_picker: pick_list::State::default(),
+            metric_picker: pick_list::State::default(),
+            goal_picker: pick_list::State::default(),
+            target_input: text_input::State::new(),
+            control_button: button::State::new(),
+        }
+    }
+
+    pub fn set_progress(&mut self, completed: usize, total: usize) {
+        self.progress = Some((completed, total));
+    }
+
+    pub fn set_results(&mut self, results: SweepResults) {
+        self.results = Some(results);
+        self.refresh_plot();
+    }
+
+    pub fn set_optimum(&mut self, optimum: Optimum) {
+        self.optimum = Some(optimum);
+        self.refresh_plot();
+    }
+
+    /// The goal of an optimization, or why it cannot be used
+    fn goal(&self) -> Result<Goal, &'static str> {
+        match self.goal {
+            GoalKind::Target => match self.target.trim().parse::<f64>() {
+                Ok(target) if target.is_finite() => Ok(Goal::Target(target)),
+                _ => Err("not a number"),
+            },
+            GoalKind::Maximize => Ok(Goal::Maximize),
+            GoalKind::Minimize => Ok(Goal::Minimize),
+        }
+    }
+
+    /// The control that starts the analysis, if everything entered is valid
+    fn start_control(&self) -> Option<SimulationControl> {
+        let x = self.axes[0].axis();
+
+        match self.mode {
+            Mode::Sweep => Some(SimulationControl::StartSweep {
+                x: x.ok()?,
+                y: if self.two_parameters {
+                    Some(self.axes[1].axis().ok()?)
+                } else {
+                    None
+                },
+            }),
+            // Steps are not used by the search, so only the range matters
+            Mode::Optimize => Some(SimulationControl::StartOptimization {
+                parameter: self.axes[0].parameter,
+                range: self.axes[0].range().ok()?,
+                metric: self.metric,
+                goal: self.goal().ok()?,
+            }),
+        }
+    }
+
+    /// Draw the results of the current mode in the units being entered
+    fn refresh_plot(&mut self) {
+        let x = &self.axes[0];
+        let y = &self.axes[1];
+
+        self.plot = match self.mode {
+            Mode::Sweep => match &self.results {
+                Some(results) => {
+                    SweepPlot::sweep(results, self.metric, (x.unit, results.y.map(|_| y.unit)))
+                }
+                None => SweepPlot::default(),
+            },
+            Mode::Optimize => match &self.optimum {
+                Some(optimum) => SweepPlot::search(optimum, x.unit, self.goal().ok()),
+                None => SweepPlot::default(),
+            },
+        };
+    }
+
+    pub fn update(&mut self, message: SweepMessage) -> Option<SimulationControl> {
+        match message {
+            SweepMessage::ModeChanged(mode) => self.mode = mode,
+            SweepMessage::ParameterChanged(index, parameter) => {
+                self.axes.get_mut(index)?.set_parameter(parameter)
+            }
+            SweepMessage::RangeStartChanged(index, text) => self.axes.get_mut(index)?.start = text,
+            SweepMessage::RangeEndChanged(index, text) => self.axes.get_mut(index)?.end = text,
+            SweepMessage::StepsChanged(index, text) => self.axes.get_mut(index)?.steps = text,
+            SweepMessage::UnitChanged(index, unit) => self.axes.get_mut(index)?.set_unit(unit),
+            SweepMessage::TwoParametersToggled(two_parameters) => {
+                self.two_parameters = two_parameters
+            }
+            SweepMessage::MetricChanged(metric) => self.metric = metric,
+            SweepMessage::GoalChanged(goal) => self.goal = goal,
+            SweepMessage::TargetChanged(target) => {
+                self.target = target;
+                self.target_error = self.goal().err();
+            }
+            SweepMessage::Start => {
+                let control = self.start_control()?;
+
+                self.progress = None;
+
+                match self.mode {
+                    Mode::Sweep => self.results = None,
+                    Mode::Optimize => self.optimum = None,
+                }
+
+                self.refresh_plot();
+
+                return Some(control);
+            }
+            SweepMessage::Stop => return Some(SimulationControl::Stop),
+        }
+
+        self.refresh_plot();
+
+        None
+    }
+
+    pub fn view(&mut self, status: Option<SimulationStatus>) -> Element<'_, SweepMessage> {
+        let mode = self.mode;
+        let unit = self.axes[0].unit;
+        let can_start = self.start_control().is_some();
+
+        let mut settings = Column::new()
+            .spacing(5)
+            .push(Text::new("Parameter Study").size(24))
+            .push(PickList::new(
+                &mut self.mode_picker,
+                &Mode::ALL[..],
+                Some(mode),
+                SweepMessage::ModeChanged,
+            ));
+
+        let [x, y] = &mut self.axes;
+
+        match mode {
+            Mode::Sweep => {
+                settings = settings
+                    .push(x.view(0, "Parameter", true))
+                    .push(Checkbox::new(
+                        self.two_parameters,
+                        "Sweep a second parameter",
+                        SweepMessage::TwoParametersToggled,
+                    ));
+
+                if self.two_parameters {
+                    settings = settings.push(y.view(1, "Second parameter", true));
+                }
+
+                settings = settings.push(
+                    Row::new()
+                        .spacing(5)
+                        .align_items(Align::Center)
+                        .push(Text::new("Plot").width(Length::Units(120)))
+                        .push(PickList::new(
+                            &mut self.metric_picker,
+                            &Metric::ALL[..],
+                            Some(self.metric),
+                            SweepMessage::MetricChanged,
+                        )),
+                );
+            }
+            Mode::Optimize => {
+                let mut goal = Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(Text::new("Goal").width(Length::Units(120)))
+                    .push(PickList::new(
+                        &mut self.goal_picker,
+                        &GoalKind::ALL[..],
+                        Some(self.goal),
+                        SweepMessage::GoalChanged,
+                    ))
+                    .push(PickList::new(
+                        &mut self.metric_picker,
+                        &Metric::ALL[..],
+                        Some(self.metric),
+                        SweepMessage::MetricChanged,
+                    ));
+
+                if self.goal == GoalKind::Target {
+                    goal = goal
+                        .push(Text::new("of"))
+                        .push(
+                            TextInput::new(
+                                &mut self.target_input,
+                                "",
+                                &self.target,
+                                SweepMessage::TargetChanged,
+                            )
+                            .padding(4)
+                            .width(Length::Units(70)),
+                        )
+                        .push(Text::new(self.metric.unit()));
+
+                    if let Some(error) = self.target_error {
+                        goal = goal.push(Text::new(error).color(ERROR_COLOR));
+                    }
+                }
+
+                settings = settings.push(x.view(0, "Search", false)).push(goal);
+            }
+        }
+
+        let control = if matches!(status, Some(SimulationStatus::Running)) {
+            Button::new(&mut self.control_button, Text::new("Stop")).on_press(SweepMessage::Stop)
+        } else {
+            let button = Button::new(&mut self.control_button, Text::new("Start"));
+
+            match status {
+                Some(_) if can_start => button.on_press(SweepMessage::Start),
+                _ => button,
+            }
+        };
+
+        settings = settings.push(control);
+
+        if let Some((completed, total)) = self.progress {
+            settings = settings.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        ProgressBar::new(0.0..=total.max(1) as f32, completed.min(total) as f32)
+                            .width(Length::Units(300)),
+                    )
+                    .push(Text::new(format!("{} / {} flights", completed, total)).size(16)),
+            );
+        }
+
+        if let (Mode::Optimize, Some(optimum)) = (mode, &self.optimum) {
+            settings = settings.push(Text::new(format!(
+                "Best {}: {} {} giving {} after {} flights",
+                optimum.parameter.to_string().to_lowercase(),
+                format_value(unit.in_unit(optimum.value)),
+                unit,
+                match optimum.metric_value {
+                    Some(value) => format!(
+                        "{} of {:.2} {}",
+                        optimum.metric.to_string().to_lowercase(),
+                        value,
+                        optimum.metric.unit()
+                    ),
+                    None => format!("no {}", optimum.metric.to_string().to_lowercase()),
+                },
+                optimum.evaluations.len()
+            )));
+        }
+
+        Row::new()
+            .spacing(20)
+            .push(settings.width(Length::FillPortion(2)))
+            .push(
+                ChartWidget::new(&mut self.plot)
+                    .width(Length::FillPortion(3))
+                    .height(Length::Fill),
+            )
+            .into()
+    }
+}
+
+/// Plot of the results of a sweep or an optimization, in the units they
+/// were entered in
+#[derive(Default)]
+struct SweepPlot {
+    caption: String,
+    x_desc: String,
+    y_desc: String,
+    data: PlotData,
+}
+
+#[derive(Default)]
+enum PlotData {
+    #[default]
+    Empty,
+    /// A figure against one parameter
+    Curve(Vec<(f64, f64)>),
+    /// Cells of a figure against two parameters, centered on the parameter
+    /// values and sized by the steps between them
+    Heatmap {
+        cells: Vec<(f64, f64, Option<f64>)>,
+        size: (f64, f64),
+        range: (f64, f64),
+    },
+    /// Parameter values tried by an optimization, the best, and the target
+    Search {
+        points: Vec<(f64, f64)>,
+        best: Option<(f64, f64)>,
+        target: Option<f64>,
+    },
+}
+
+/// Description of an axis of a parameter in a unit
+fn parameter_desc(parameter: SweepParameter, unit: Unit) -> String {
+    format!("{} ({})", parameter, unit)
+}
+
+/// Range covering values, padded so that none sit on the edge
+fn padded_range(values: impl Iterator<Item = f64>) -> Range<f64> {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+
+    if min > max {
+        return 0.0..1.0;
+    }
+
+    let padding = ((max - min) * 0.05).max(1e-6 * max.abs().max(1.0));
+
+    (min - padding)..(max + padding)
+}
+
+impl SweepPlot {
+    fn sweep(results: &SweepResults, metric: Metric, units: (Unit, Option<Unit>)) -> Self {
+        let metric_desc = format!("{} ({})", metric, metric.unit());
+        let x_unit = units.0;
+        let x_values: Vec<_> = results.x.values().map(|x| x_unit.in_unit(x)).collect();
+
+        match (results.y, units.1) {
+            (Some(y_axis), Some(y_unit)) => {
+                let y_values: Vec<_> = y_axis.values().map(|y| y_unit.in_unit(y)).collect();
+                let step = |values: &[f64]| (values[1] - values[0]).abs();
+                let cells = y_values
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(j, &y)| {
+                        x_values
+                            .iter()
+                            .enumerate()
+                            .map(move |(i, &x)| (x, y, results.value(metric, i, j)))
+                    })
+                    .collect();
+
+                let range = results.range(metric).unwrap_or((0.0, 1.0));
+
+                Self {
+                    caption: format!(
+                        "{} from {:.2} (blue) to {:.2} (red)",
+                        metric_desc, range.0, range.1
+                    ),
+                    x_desc: parameter_desc(results.x.parameter, x_unit),
+                    y_desc: parameter_desc(y_axis.parameter, y_unit),
+                    data: PlotData::Heatmap {
+                        cells,
+                        size: (step(&x_values), step(&y_values)),
+                        range,
+                    },
+                }
+            }
+            _ => Self {
+                caption: format!("{} against {}", metric, results.x.parameter),
+                x_desc: parameter_desc(results.x.parameter, x_unit),
+                y_desc: metric_desc,
+                data: PlotData::Curve(
+                    x_values
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, &x)| Some((x, results.value(metric, i, 0)?)))
+                        .collect(),
+                ),
+            },
+        }
+    }
+
+    fn search(optimum: &Optimum, unit: Unit, goal: Option<Goal>) -> Self {
+        let points = optimum
+            .evaluations
+            .iter()
+            .filter_map(|&(value, metric)| Some((unit.in_unit(value), metric?)))
+            .collect();
+
+        Self {
+            caption: format!("{} of each flight of the search", optimum.metric),
+            x_desc: parameter_desc(optimum.parameter, unit),
+            y_desc: format!("{} ({})", optimum.metric, optimum.metric.unit()),
+            data: PlotData::Search {
+                points,
+                best: optimum
+                    .metric_value
+                    .map(|value| (unit.in_unit(optimum.value), value)),
+                target: match goal {
+                    Some(Goal::Target(target)) => Some(target),
+                    _ => None,
+                },
+            },
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, plotters::coord::Shift>,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let (x_range, y_range) = match &self.data {
+            PlotData::Empty => return Ok(()),
+            PlotData::Curve(points) => (
+                padded_range(points.iter().map(|point| point.0)),
+                padded_range(points.iter().map(|point| point.1)),
+            ),
+            PlotData::Heatmap { cells, size, .. } => {
+                let x = padded_range(cells.iter().map(|cell| cell.0));
+                let y = padded_range(cells.iter().map(|cell| cell.1));
+
+                (
+                    (x.start - size.0 / 2.0)..(x.end + size.0 / 2.0),
+                    (y.start - size.1 / 2.0)..(y.end + size.1 / 2.0),
+                )
+            }
+            PlotData::Search { points, target, .. } => (
+                padded_range(points.iter().map(|point| point.0)),
+                padded_range(points.iter().map(|point| point.1).chain(*target)),
+            ),
+        };
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.caption, ("sans-serif", 14))
+            .margin(5)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range.clone(), y_range)?;
+
+        chart
+            .configure_mesh()
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .draw()?;
+
+        match &self.data {
+            PlotData::Empty => {}
+            PlotData::Curve(points) => {
+                chart.draw_series(LineSeries::new(points.iter().copied(), &BLUE))?;
+                chart.draw_series(
+                    points
+                        .iter()
+                        .map(|&point| Circle::new(point, 3, BLUE.filled())),
+                )?;
+            }
+            PlotData::Heatmap { cells, size, range } => {
+                let span = (range.1 - range.0).max(f64::EPSILON);
+
+                chart.draw_series(cells.iter().map(|&(x, y, value)| {
+                    let color = match value {
+                        // Blue for the lowest value through to red for the highest
+                        Some(value) => {
+                            HSLColor(0.66 * (1.0 - (value - range.0) / span), 0.8, 0.5).filled()
+                        }
+                        None => RGBColor(200, 200, 200).filled(),
+                    };
+
+                    Rectangle::new(
+                        [
+                            (x - size.0 / 2.0, y - size.1 / 2.0),
+                            (x + size.0 / 2.0, y + size.1 / 2.0),
+                        ],
+                        color,
+                    )
+                }))?;
+            }
+            PlotData::Search {
+                points,
+                best,
+                target,
+            } => {
+                if let Some(target) = *target {
+                    chart.draw_series(LineSeries::new(
+                        vec![(x_range.start, target), (x_range.end, target)],
+                        &BLACK.mix(0.4),
+                    ))?;
+                }
+
+                chart.draw_series(
+                    points
+                        .iter()
+                        .map(|&point| Circle::new(point, 3, BLUE.mix(0.6).filled())),
+                )?;
+
+                if let Some(best) = *best {
+                    chart.draw_series(std::iter::once(Circle::new(best, 5, RED.filled())))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Chart<SweepMessage> for SweepPlot {
+    fn build_chart<DB: DrawingBackend>(&self, _builder: ChartBuilder<DB>) {}
+
+    fn draw_chart<DB: DrawingBackend>(&self, root: DrawingArea<DB, plotters::coord::Shift>) {
+        if let Err(error) = self.draw(root) {
+            warn!(%error, "Failed to draw sweep plot");
+        }
+    }
+}
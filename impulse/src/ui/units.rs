@@ -0,0 +1,152 @@
+use std::fmt::{self, Display};
+
+/// A physical quantity that a value can be entered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    Length,
+    /// A length across the ground, such as the size of a launch field
+    Distance,
+    Mass,
+    Density,
+    Angle,
+    Time,
+    Speed,
+    Count,
+    /// A proportion of some other value
+    Fraction,
+    Dimensionless,
+}
+
+/// A unit of measure and its conversion to the SI unit used internally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Meter,
+    Centimeter,
+    Millimeter,
+    Inch,
+    Foot,
+    Kilometer,
+    Mile,
+    Kilogram,
+    Gram,
+    Pound,
+    Ounce,
+    KilogramPerCubicMeter,
+    GramPerCubicCentimeter,
+    Degree,
+    Radian,
+    Second,
+    MeterPerSecond,
+    KilometerPerHour,
+    MilePerHour,
+    Percent,
+    None,
+}
+
+impl Quantity {
+    /// Units that values of this quantity can be entered in, the first being
+    /// the default
+    pub fn units(self) -> &'static [Unit] {
+        match self {
+            Quantity::Length => &[
+                Unit::Millimeter,
+                Unit::Centimeter,
+                Unit::Meter,
+                Unit::Inch,
+                Unit::Foot,
+            ],
+            Quantity::Distance => &[Unit::Meter, Unit::Kilometer, Unit::Foot, Unit::Mile],
+            Quantity::Mass => &[Unit::Gram, Unit::Kilogram, Unit::Ounce, Unit::Pound],
+            Quantity::Density => &[Unit::KilogramPerCubicMeter, Unit::GramPerCubicCentimeter],
+            Quantity::Angle => &[Unit::Degree, Unit::Radian],
+            Quantity::Time => &[Unit::Second],
+            Quantity::Speed => &[
+                Unit::MeterPerSecond,
+                Unit::KilometerPerHour,
+                Unit::MilePerHour,
+            ],
+            Quantity::Fraction => &[Unit::Percent, Unit::None],
+            Quantity::Count | Quantity::Dimensionless => &[Unit::None],
+        }
+    }
+
+    pub fn default_unit(self) -> Unit {
+        self.units()[0]
+    }
+}
+
+impl Unit {
+    /// Value of one of this unit in the SI unit of its quantity
+    pub fn factor(self) -> f64 {
+        match self {
+            Unit::Meter
+            | Unit::Kilogram
+            | Unit::KilogramPerCubicMeter
+            | Unit::Radian
+            | Unit::Second
+            | Unit::MeterPerSecond
+            | Unit::None => 1.0,
+            Unit::Centimeter => 0.01,
+            Unit::Millimeter => 0.001,
+            Unit::Inch => 0.0254,
+            Unit::Foot => 0.3048,
+            Unit::Kilometer => 1000.0,
+            Unit::Mile => 1609.344,
+            Unit::Gram => 0.001,
+            Unit::Pound => 0.453_592_37,
+            Unit::Ounce => 0.028_349_523_125,
+            Unit::GramPerCubicCentimeter => 1000.0,
+            Unit::Degree => std::f64::consts::PI / 180.0,
+            Unit::KilometerPerHour => 1.0 / 3.6,
+            Unit::MilePerHour => 0.447_04,
+            Unit::Percent => 0.01,
+        }
+    }
+
+    pub fn to_si(self, value: f64) -> f64 {
+        value * self.factor()
+    }
+
+    pub fn in_unit(self, value: f64) -> f64 {
+        value / self.factor()
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Meter => "m",
+            Unit::Centimeter => "cm",
+            Unit::Millimeter => "mm",
+            Unit::Inch => "in",
+            Unit::Foot => "ft",
+            Unit::Kilometer => "km",
+            Unit::Mile => "mi",
+            Unit::Kilogram => "kg",
+            Unit::Gram => "g",
+            Unit::Pound => "lb",
+            Unit::Ounce => "oz",
+            Unit::KilogramPerCubicMeter => "kg/m³",
+            Unit::GramPerCubicCentimeter => "g/cm³",
+            Unit::Degree => "°",
+            Unit::Radian => "rad",
+            Unit::Second => "s",
+            Unit::MeterPerSecond => "m/s",
+            Unit::KilometerPerHour => "km/h",
+            Unit::MilePerHour => "mph",
+            Unit::Percent => "%",
+            Unit::None => "",
+        }
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// Format a number without trailing zeros, rounded to a sensible precision
+pub fn format_value(value: f64) -> String {
+    let rounded = (value * 1e6).round() / 1e6;
+
+    format!("{}", rounded)
+}
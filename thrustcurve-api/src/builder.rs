@@ -2,7 +2,7 @@ use serde::Serialize;
 
 use crate::{
     get_endpoint,
-    model::{Availability, Metadata, MotorType},
+    model::{Availability, DataFormat, Download, Metadata, MotorType, Search},
     Error, InnerClient,
 };
 
@@ -61,3 +61,112 @@ impl MetadataBuilder {
         get_endpoint(self.client.clone(), &self, "metadata.json").await
     }
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchBuilder {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manufacturer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    designation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    common_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    impulse_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diameter: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "type")]
+    ty: Option<MotorType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cert_org: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    availability: Option<Availability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_results: Option<u32>,
+    #[serde(skip)]
+    client: InnerClient,
+}
+
+impl SearchBuilder {
+    by!(manufacturer(manufacturer: &str));
+    by!(designation(designation: &str));
+    by!(common_name(common_name: &str));
+    by!(impulse_class(impulse_class: &str));
+    by!(diameter(diameter: f32));
+    by!(motor_type(ty: MotorType));
+    by!(cert_org(cert_org: &str));
+    by!(availability(availability: Availability));
+
+    pub(crate) fn new(client: InnerClient) -> Self {
+        Self {
+            client,
+            availability: None,
+            cert_org: None,
+            common_name: None,
+            designation: None,
+            diameter: None,
+            impulse_class: None,
+            manufacturer: None,
+            max_results: None,
+            ty: None,
+        }
+    }
+
+    /// Limit the number of motors returned
+    pub fn max_results(mut self, max_results: u32) -> Self {
+        self.max_results.replace(max_results);
+
+        self
+    }
+
+    pub async fn get(self) -> Result<Option<Search>, Error> {
+        get_endpoint(self.client.clone(), &self, "search.json").await
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadBuilder {
+    motor_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<DataFormat>,
+    /// Always request parsed samples alongside the raw files
+    data: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_results: Option<u32>,
+    #[serde(skip)]
+    client: InnerClient,
+}
+
+impl DownloadBuilder {
+    by!(format(format: DataFormat));
+
+    pub(crate) fn new(client: InnerClient) -> Self {
+        Self {
+            client,
+            data: "both",
+            format: None,
+            max_results: None,
+            motor_ids: Vec::new(),
+        }
+    }
+
+    /// Add a motor, as identified by [`MotorInfo::motor_id`](crate::model::MotorInfo::motor_id),
+    /// to download the data files of
+    pub fn motor_id(mut self, motor_id: &str) -> Self {
+        self.motor_ids.push(motor_id.to_owned());
+
+        self
+    }
+
+    /// Limit the number of data files returned
+    pub fn max_results(mut self, max_results: u32) -> Self {
+        self.max_results.replace(max_results);
+
+        self
+    }
+
+    pub async fn get(self) -> Result<Option<Download>, Error> {
+        get_endpoint(self.client.clone(), &self, "download.json").await
+    }
+}
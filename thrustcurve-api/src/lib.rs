@@ -1,14 +1,14 @@
 #![deny(unsafe_code)]
 
-use builder::MetadataBuilder;
+use builder::{DownloadBuilder, MetadataBuilder, SearchBuilder};
 use hyper::{body, client::HttpConnector, Body, Request, StatusCode};
 use hyper_tls::HttpsConnector;
 use model::ApiResult;
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
-mod builder;
-mod model;
+pub mod builder;
+pub mod model;
 
 pub const API_URL: &str = "https://www.thrustcurve.org/api/v1";
 
@@ -37,6 +37,16 @@ impl Client {
     pub fn metadata(&self) -> MetadataBuilder {
         MetadataBuilder::new(self.inner.clone())
     }
+
+    /// Search for motors matching some criteria.
+    pub fn search(&self) -> SearchBuilder {
+        SearchBuilder::new(self.inner.clone())
+    }
+
+    /// Download the data files, such as thrust curves, of specific motors.
+    pub fn download(&self) -> DownloadBuilder {
+        DownloadBuilder::new(self.inner.clone())
+    }
 }
 
 impl Default for Client {
@@ -1,14 +1,15 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
-pub enum ApiResult<T> {
+pub(crate) enum ApiResult<T> {
     Error { error: String },
     Response(T),
     Empty {},
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Metadata {
     pub manufacturers: Vec<NameAndAbbrev>,
@@ -30,6 +31,11 @@ pub enum MotorType {
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Availability {
+    /// In regular production
+    Regular,
+    /// Produced from time to time
+    Occasional,
+    /// Either regular or occasional, only used to search
     Available,
     #[serde(rename = "OOP")]
     OutOfProduction,
@@ -40,3 +46,191 @@ pub struct NameAndAbbrev {
     pub name: String,
     pub abbrev: String,
 }
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub enum DataFormat {
+    #[serde(rename = "RASP")]
+    Rasp,
+    #[serde(rename = "RockSim")]
+    RockSim,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Search {
+    pub results: Vec<MotorInfo>,
+}
+
+/// Description of a single motor as returned from a search
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MotorInfo {
+    pub motor_id: String,
+    pub manufacturer: String,
+    pub manufacturer_abbrev: String,
+    pub designation: String,
+    pub common_name: String,
+    pub impulse_class: String,
+    /// Casing diameter (mm)
+    pub diameter: f64,
+    /// Casing length (mm)
+    pub length: f64,
+    #[serde(rename = "type")]
+    pub ty: MotorType,
+    #[serde(default)]
+    pub cert_org: Option<String>,
+    /// Average thrust (N)
+    #[serde(default, rename = "avgThrustN")]
+    pub average_thrust: Option<f64>,
+    /// Peak thrust (N)
+    #[serde(default, rename = "maxThrustN")]
+    pub max_thrust: Option<f64>,
+    /// Total impulse (N·s)
+    #[serde(default, rename = "totImpulseNs")]
+    pub total_impulse: Option<f64>,
+    /// Burn time (s)
+    #[serde(default, rename = "burnTimeS")]
+    pub burn_time: Option<f64>,
+    /// Number of data files available for download
+    #[serde(default)]
+    pub data_files: u32,
+    /// Mass of the loaded motor (g)
+    #[serde(default, rename = "totalWeightG")]
+    pub total_mass: Option<f64>,
+    /// Mass of the propellant (g)
+    #[serde(default, rename = "propWeightG")]
+    pub propellant_mass: Option<f64>,
+    /// Available ejection delays, comma separated (s)
+    #[serde(default)]
+    pub delays: Option<String>,
+    /// Whether the motor can still be bought, if it is one impulse knows of
+    #[serde(default, deserialize_with = "lenient")]
+    pub availability: Option<Availability>,
+}
+
+/// Deserialize a value, or nothing if it is not one this crate knows of, so
+/// that additions to the API do not fail whole responses
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: for<'a> Deserialize<'a>,
+{
+    Ok(serde_json::from_value(Value::deserialize(deserializer)?).ok())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Download {
+    pub results: Vec<DataFile>,
+}
+
+/// A single data file of a motor, such as a thrust curve
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataFile {
+    pub motor_id: String,
+    pub simfile_id: String,
+    pub format: DataFormat,
+    /// Where the data came from, such as `cert` or `user`
+    pub source: String,
+    /// Base64 encoded contents of the file
+    #[serde(default)]
+    pub data: Option<String>,
+    /// Thrust curve parsed from the file
+    #[serde(default)]
+    pub samples: Option<Vec<Sample>>,
+}
+
+/// A point on a thrust curve
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Sample {
+    /// Time since ignition (s)
+    pub time: f64,
+    /// Thrust (N)
+    pub thrust: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Response of search.json for two motors, with the criteria it echoes
+    /// back and the fields impulse does not use
+    const SEARCH: &str = r#"{
+        "criteria": [
+            { "name": "commonName", "value": "C6", "matches": 1 },
+            { "name": "availability", "value": "available", "matches": 1 }
+        ],
+        "results": [
+            {
+                "motorId": "5f4294d20002e900000002d5",
+                "manufacturer": "Estes Industries",
+                "manufacturerAbbrev": "Estes",
+                "designation": "C6",
+                "commonName": "C6",
+                "impulseClass": "C",
+                "diameter": 18,
+                "length": 70,
+                "type": "SU",
+                "certOrg": "National Association of Rocketry",
+                "avgThrustN": 4.74,
+                "maxThrustN": 14.09,
+                "totImpulseNs": 8.82,
+                "burnTimeS": 1.86,
+                "dataFiles": 4,
+                "infoUrl": "http://www.nar.org/SandT/pdf/Estes/C6.pdf",
+                "totalWeightG": 24.1,
+                "propWeightG": 10.8,
+                "delays": "0,3,5,7",
+                "caseInfo": "SU 18x70",
+                "propInfo": "black powder",
+                "sparkyFlag": false,
+                "updatedOn": "2019-04-16",
+                "availability": "regular"
+            },
+            {
+                "motorId": "5f4294d20002e900000002e1",
+                "manufacturer": "Estes Industries",
+                "manufacturerAbbrev": "Estes",
+                "designation": "E12",
+                "commonName": "E12",
+                "impulseClass": "E",
+                "diameter": 24,
+                "length": 95,
+                "type": "SU",
+                "dataFiles": 0,
+                "availability": "occasional"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn deserializes_search() {
+        let search = match serde_json::from_str(SEARCH).unwrap() {
+            ApiResult::<Search>::Response(search) => search,
+            other => panic!("expected a response, got {:?}", other),
+        };
+
+        assert_eq!(search.results.len(), 2);
+
+        let c6 = &search.results[0];
+        assert_eq!(c6.designation, "C6");
+        assert_eq!(c6.impulse_class, "C");
+        assert_eq!(c6.total_impulse, Some(8.82));
+        assert_eq!(c6.data_files, 4);
+        assert!(matches!(c6.availability, Some(Availability::Regular)));
+
+        let e12 = &search.results[1];
+        assert_eq!(e12.total_impulse, None);
+        assert!(matches!(e12.availability, Some(Availability::Occasional)));
+    }
+
+    #[test]
+    fn ignores_unknown_availability() {
+        let search = SEARCH.replace("occasional", "discontinued");
+        let search: Search = serde_json::from_str(&search).unwrap();
+
+        assert_eq!(search.results.len(), 2);
+        assert!(search.results[1].availability.is_none());
+    }
+}